@@ -0,0 +1,117 @@
+package app.rkz.airgap
+
+/**
+ * AirgapAutoDecoder accepts native airgap, UR, BBQr and plain QR codes,
+ * detecting the format from each scanned string
+ *
+ * The first accepted frame fixes the format of the session; frames in another
+ * format are rejected until [reset] is called.
+ */
+class AirgapAutoDecoder : AutoCloseable {
+
+    private var nativeHandle: Long = 0
+
+    init {
+        System.loadLibrary("airgap_jni")
+        nativeHandle = nativeNew()
+        if (nativeHandle == 0L) {
+            throw AirgapException("Failed to create decoder")
+        }
+    }
+
+    /**
+     * The format detected for the current session (null if no frame accepted yet)
+     */
+    val format: FrameFormat?
+        get() {
+            checkNotClosed()
+            return FrameFormat.fromCode(nativeGetFormat(nativeHandle))
+        }
+
+    /**
+     * Whether all chunks have been received and the data is complete
+     */
+    val isComplete: Boolean
+        get() {
+            checkNotClosed()
+            return nativeIsComplete(nativeHandle)
+        }
+
+    /**
+     * Total number of chunks expected (0 if not yet known)
+     */
+    val totalChunks: Int
+        get() {
+            checkNotClosed()
+            return nativeGetTotal(nativeHandle)
+        }
+
+    /**
+     * Number of unique chunks received so far
+     */
+    val receivedChunks: Int
+        get() {
+            checkNotClosed()
+            return nativeGetReceived(nativeHandle)
+        }
+
+    /**
+     * Process a QR code string of any supported format
+     *
+     * @param qrString The string data from a scanned QR code
+     * @return AutoQRResult with chunk information and the detected format
+     * @throws AirgapException if processing fails
+     */
+    @Throws(AirgapException::class)
+    fun processQrString(qrString: String): AutoQRResult {
+        checkNotClosed()
+        // JNI will throw AirgapException on error
+        return nativeProcessQr(nativeHandle, qrString)
+            ?: throw AirgapException("Failed to process QR code")
+    }
+
+    /**
+     * Reset the decoder to its initial state
+     */
+    fun reset() {
+        checkNotClosed()
+        nativeReset(nativeHandle)
+    }
+
+    /**
+     * Get the decoded data once complete
+     *
+     * @return The decoded data
+     * @throws AirgapException if decoding is not complete or retrieval fails
+     */
+    @Throws(AirgapException::class)
+    fun getData(): ByteArray {
+        checkNotClosed()
+        return nativeGetData(nativeHandle)
+            ?: throw AirgapException("Failed to retrieve decoded data")
+    }
+
+    override fun close() {
+        if (nativeHandle != 0L) {
+            nativeFree(nativeHandle)
+            nativeHandle = 0
+        }
+    }
+
+    private fun checkNotClosed() {
+        if (nativeHandle == 0L) {
+            throw IllegalStateException("Decoder has been closed")
+        }
+    }
+
+    // Native methods
+    private external fun nativeNew(): Long
+    private external fun nativeFree(handle: Long)
+    private external fun nativeGetFormat(handle: Long): Int
+    private external fun nativeIsComplete(handle: Long): Boolean
+    private external fun nativeGetTotal(handle: Long): Int
+    private external fun nativeGetReceived(handle: Long): Int
+    private external fun nativeProcessQr(handle: Long, qrString: String): AutoQRResult?
+    private external fun nativeGetData(handle: Long): ByteArray?
+    private external fun nativeReset(handle: Long)
+}
@@ -1,15 +1,30 @@
 package app.rkz.airgap
 
+import java.util.concurrent.locks.ReentrantReadWriteLock
+import kotlin.concurrent.read
+import kotlin.concurrent.write
+
 /**
  * AirgapDecoder decodes QR code chunks back into the original data
+ *
+ * The decoder is thread-safe: a camera analyzer may call [processQrString]
+ * on a background thread while the UI thread reads [progress]. Progress
+ * reads never wait for a frame being processed. [close] waits for calls in
+ * flight to finish; it must not be called from a [DecoderListener] callback.
+ *
+ * @property format Wire format of the frames this decoder accepts
  */
-class AirgapDecoder : AutoCloseable {
+class AirgapDecoder(format: FrameFormat = FrameFormat.AIRGAP) : AutoCloseable {
 
+    @Volatile
     private var nativeHandle: Long = 0
 
+    // Native calls hold the read lock so that close() cannot free the handle under them
+    private val handleLock = ReentrantReadWriteLock()
+
     init {
-        System.loadLibrary("airgap")
-        nativeHandle = nativeNew()
+        System.loadLibrary("airgap_jni")
+        nativeHandle = if (format == FrameFormat.AIRGAP) nativeNew() else nativeNewWithFormat(format.code)
         if (nativeHandle == 0L) {
             throw AirgapException("Failed to create decoder")
         }
@@ -19,37 +34,32 @@ class AirgapDecoder : AutoCloseable {
      * Whether all chunks have been received and the data is complete
      */
     val isComplete: Boolean
-        get() {
-            checkNotClosed()
-            return nativeIsComplete(nativeHandle)
-        }
+        get() = withHandle { nativeIsComplete(it) }
 
     /**
      * Total number of chunks expected (0 if not yet known)
      */
     val totalChunks: Int
-        get() {
-            checkNotClosed()
-            return nativeGetTotal(nativeHandle)
-        }
+        get() = withHandle { nativeGetTotal(it) }
 
     /**
      * Number of unique chunks received so far
      */
     val receivedChunks: Int
-        get() {
-            checkNotClosed()
-            return nativeGetReceived(nativeHandle)
-        }
+        get() = withHandle { nativeGetReceived(it) }
 
     /**
      * The session ID of the current decoding session (-1 if no session started)
      */
     val sessionId: Int
-        get() {
-            checkNotClosed()
-            return nativeGetSessionId(nativeHandle)
-        }
+        get() = withHandle { nativeGetSessionId(it) }
+
+    /**
+     * Where the current session stands, taking the timeouts into account
+     */
+    val status: DecoderStatus
+        get() = withHandle { DecoderStatus.fromCode(nativeGetStatus(it)) }
+            ?: throw AirgapException("Unknown decoder status")
 
     /**
      * Get the decoding progress as a pair of (received, total)
@@ -66,18 +76,54 @@ class AirgapDecoder : AutoCloseable {
      */
     @Throws(AirgapException::class)
     fun processQrString(qrString: String): QRResult {
-        checkNotClosed()
         // JNI will throw AirgapException on error
-        return nativeProcessQr(nativeHandle, qrString)
+        return withHandle { nativeProcessQr(it, qrString) }
             ?: throw AirgapException("Failed to process QR code")
     }
 
+    /**
+     * Notify [listener] of session start, new chunks, duplicates, conflicts,
+     * errors and completion; pass null to stop notifications
+     */
+    fun setListener(listener: DecoderListener?) {
+        withHandle { nativeSetListener(it, listener) }
+    }
+
+    /**
+     * Report the session as [DecoderStatus.STALLED] after [idleMs] without a
+     * new chunk and as [DecoderStatus.EXPIRED] [totalMs] after its first frame;
+     * 0 disables either limit
+     *
+     * @param autoExpire Drop stale sessions as new frames arrive; otherwise
+     *   frames for an expired session throw AirgapException
+     */
+    fun setTimeouts(idleMs: Long = 0, totalMs: Long = 0, autoExpire: Boolean = false) {
+        require(idleMs >= 0 && totalMs >= 0) { "Timeouts must not be negative" }
+        withHandle { nativeSetTimeouts(it, idleMs, totalMs, autoExpire) }
+    }
+
+    /**
+     * Enforce [limits] on every frame from now on; frames over a limit throw
+     * AirgapException
+     */
+    fun setLimits(limits: DecoderLimits) {
+        withHandle {
+            nativeSetLimits(it, limits.maxTotalChunks, limits.maxPayloadBytes, limits.maxSessions, limits.maxFrameLength)
+        }
+    }
+
+    /**
+     * Reset the session if it is stalled or expired
+     *
+     * @return Whether the session was reset
+     */
+    fun expireStale(): Boolean = withHandle { nativeExpireStale(it) }
+
     /**
      * Reset the decoder to its initial state
      */
     fun reset() {
-        checkNotClosed()
-        nativeReset(nativeHandle)
+        withHandle { nativeReset(it) }
     }
 
     /**
@@ -88,27 +134,31 @@ class AirgapDecoder : AutoCloseable {
      */
     @Throws(AirgapException::class)
     fun getData(): ByteArray {
-        checkNotClosed()
         // JNI will throw AirgapException on error (including if not complete)
-        return nativeGetData(nativeHandle)
+        return withHandle { nativeGetData(it) }
             ?: throw AirgapException("Failed to retrieve decoded data")
     }
 
     override fun close() {
-        if (nativeHandle != 0L) {
-            nativeFree(nativeHandle)
-            nativeHandle = 0
+        handleLock.write {
+            if (nativeHandle != 0L) {
+                nativeFree(nativeHandle)
+                nativeHandle = 0
+            }
         }
     }
 
-    private fun checkNotClosed() {
-        if (nativeHandle == 0L) {
+    private inline fun <T> withHandle(block: (Long) -> T): T = handleLock.read {
+        val handle = nativeHandle
+        if (handle == 0L) {
             throw IllegalStateException("Decoder has been closed")
         }
+        block(handle)
     }
 
     // Native methods
     private external fun nativeNew(): Long
+    private external fun nativeNewWithFormat(format: Int): Long
     private external fun nativeFree(handle: Long)
     private external fun nativeIsComplete(handle: Long): Boolean
     private external fun nativeGetTotal(handle: Long): Int
@@ -117,4 +167,9 @@ class AirgapDecoder : AutoCloseable {
     private external fun nativeProcessQr(handle: Long, qrString: String): QRResult?
     private external fun nativeGetData(handle: Long): ByteArray?
     private external fun nativeReset(handle: Long)
+    private external fun nativeSetListener(handle: Long, listener: DecoderListener?)
+    private external fun nativeSetTimeouts(handle: Long, idleMs: Long, totalMs: Long, autoExpire: Boolean)
+    private external fun nativeSetLimits(handle: Long, maxTotalChunks: Int, maxPayloadBytes: Long, maxSessions: Int, maxFrameLength: Int)
+    private external fun nativeGetStatus(handle: Long): Int
+    private external fun nativeExpireStale(handle: Long): Boolean
 }
\ No newline at end of file
@@ -0,0 +1,91 @@
+package app.rkz.airgap
+
+/**
+ * AirgapDisplayScheduler decides which frame of an [AirgapEncoder] to show
+ * and for how long when looping the sequence on screen
+ *
+ * Call [advance] from the render loop with the time elapsed since the
+ * previous call and display the returned frame. The scheduler does not keep
+ * a reference to the encoder.
+ *
+ * @param encoder The encoder whose frames are displayed
+ * @param framesPerSecond Display rate
+ */
+class AirgapDisplayScheduler(
+    encoder: AirgapEncoder,
+    framesPerSecond: Float = 5f
+) : AutoCloseable {
+
+    private var nativeHandle: Long = 0
+
+    init {
+        System.loadLibrary("airgap_jni")
+        if (encoder.nativeHandle == 0L) {
+            throw IllegalStateException("Encoder has been closed")
+        }
+        nativeHandle = nativeNew(encoder.nativeHandle, framesPerSecond)
+    }
+
+    /**
+     * Change the display rate
+     */
+    fun setFrameRate(framesPerSecond: Float) {
+        checkNotClosed()
+        nativeSetFrameRate(nativeHandle, framesPerSecond)
+    }
+
+    /**
+     * Show one fountain frame after every [every] regular frames (UR only); 0 disables
+     */
+    fun setFountainInterval(every: Int) {
+        checkNotClosed()
+        nativeSetFountainInterval(nativeHandle, every)
+    }
+
+    /**
+     * Show chunks the receiver is still missing before continuing the loop
+     */
+    fun reportMissing(indices: IntArray) {
+        checkNotClosed()
+        nativeReportMissing(nativeHandle, indices)
+    }
+
+    /**
+     * Account for [elapsedMs] since the previous call and return the frame to display
+     */
+    fun advance(elapsedMs: Long): ScheduledFrame {
+        checkNotClosed()
+        return nativeAdvance(nativeHandle, elapsedMs)
+            ?: throw AirgapException("Failed to advance scheduler")
+    }
+
+    /**
+     * Start over from the first chunk
+     */
+    fun reset() {
+        checkNotClosed()
+        nativeReset(nativeHandle)
+    }
+
+    override fun close() {
+        if (nativeHandle != 0L) {
+            nativeFree(nativeHandle)
+            nativeHandle = 0
+        }
+    }
+
+    private fun checkNotClosed() {
+        if (nativeHandle == 0L) {
+            throw IllegalStateException("Scheduler has been closed")
+        }
+    }
+
+    // Native methods
+    private external fun nativeNew(encoderHandle: Long, framesPerSecond: Float): Long
+    private external fun nativeFree(handle: Long)
+    private external fun nativeSetFrameRate(handle: Long, framesPerSecond: Float)
+    private external fun nativeSetFountainInterval(handle: Long, every: Int)
+    private external fun nativeReportMissing(handle: Long, indices: IntArray)
+    private external fun nativeAdvance(handle: Long, elapsedMs: Long): ScheduledFrame?
+    private external fun nativeReset(handle: Long)
+}
@@ -5,19 +5,34 @@ package app.rkz.airgap
  *
  * @property data The data to encode
  * @property chunkSize Size of each chunk (must be between 16 and 1920 bytes, recommended: 1100)
+ * @property format Wire format of the generated frames (for UR, chunkSize is the max fragment length)
+ * @property fixedSessionId Session ID to use instead of a random one, making the frames
+ *   reproducible (e.g. for the golden test vectors); only supported for [FrameFormat.AIRGAP]
+ * @property config Rendering settings; defaults to [qrSize] with everything else at its default
  * @throws AirgapException if initialization fails
  */
 class AirgapEncoder @Throws(AirgapException::class) constructor(
     data: ByteArray,
     chunkSize: Int = RECOMMENDED_MAX_CHUNK_SIZE,
-    qrSize: Int = 400
+    qrSize: Int = 400,
+    format: FrameFormat = FrameFormat.AIRGAP,
+    fixedSessionId: Int? = null,
+    config: QrConfig = QrConfig(qrSize = qrSize)
 ) : AutoCloseable {
 
-    private var nativeHandle: Long = 0
+    internal var nativeHandle: Long = 0
+        private set
 
     init {
-        System.loadLibrary("airgap")
-        nativeHandle = nativeNew(data, chunkSize, qrSize)
+        System.loadLibrary("airgap_jni")
+        if (fixedSessionId != null && format != FrameFormat.AIRGAP) {
+            throw AirgapException(
+                "A fixed session ID is only supported for native frames",
+                code = AirgapException.INVALID_CONFIG,
+                kind = "InvalidConfig"
+            )
+        }
+        nativeHandle = nativeNew(data, chunkSize, format.code, fixedSessionId != null, fixedSessionId ?: 0, config)
     }
 
     /**
@@ -69,6 +84,51 @@ class AirgapEncoder @Throws(AirgapException::class) constructor(
             ?: throw AirgapException("Failed to generate PNG at index $index")
     }
 
+    /**
+     * Returns the QR module grid for the chunk at the given index,
+     * for UIs that draw the symbol themselves
+     *
+     * @param index The chunk index (0-based)
+     * @return The module grid
+     * @throws AirgapException if generation fails or index is out of bounds
+     */
+    @Throws(AirgapException::class)
+    fun getMatrix(index: Int): QrMatrix {
+        checkNotClosed()
+        return nativeGetMatrix(nativeHandle, index)
+            ?: throw AirgapException("Failed to get QR matrix at index $index")
+    }
+
+    /**
+     * Renders the chunk at the given index into a direct ByteBuffer,
+     * avoiding a PNG encode/decode round trip
+     *
+     * @param index The chunk index (0-based)
+     * @param format The pixel layout
+     * @return Image dimensions and pixel data
+     * @throws AirgapException if rendering fails or index is out of bounds
+     */
+    @Throws(AirgapException::class)
+    fun renderPixels(index: Int, format: PixelFormat = PixelFormat.RGBA8): QrPixels {
+        checkNotClosed()
+        return nativeRenderPixels(nativeHandle, index, format.code)
+            ?: throw AirgapException("Failed to render pixels at index $index")
+    }
+
+    /**
+     * Renders the chunk at the given index as packed ARGB colors
+     *
+     * @param index The chunk index (0-based)
+     * @return Image dimensions and colors for `Bitmap.createBitmap`
+     * @throws AirgapException if rendering fails or index is out of bounds
+     */
+    @Throws(AirgapException::class)
+    fun renderArgb(index: Int): QrBitmap {
+        checkNotClosed()
+        return nativeRenderArgb(nativeHandle, index)
+            ?: throw AirgapException("Failed to render bitmap at index $index")
+    }
+
     /**
      * Generates all PNG images for all chunks
      *
@@ -95,12 +155,17 @@ class AirgapEncoder @Throws(AirgapException::class) constructor(
     }
 
     // Native methods
-    private external fun nativeNew(data: ByteArray, chunkSize: Int, qrSize: Int): Long
+    private external fun nativeNew(
+        data: ByteArray, chunkSize: Int, format: Int, hasSessionId: Boolean, sessionId: Int, config: QrConfig
+    ): Long
     private external fun nativeFree(handle: Long)
     private external fun nativeChunkCount(handle: Long): Int
     private external fun nativeSessionId(handle: Long): Int
     private external fun nativeGetQRString(handle: Long, index: Int): String?
     private external fun nativeGeneratePng(handle: Long, index: Int): ByteArray?
+    private external fun nativeGetMatrix(handle: Long, index: Int): QrMatrix?
+    private external fun nativeRenderPixels(handle: Long, index: Int, format: Int): QrPixels?
+    private external fun nativeRenderArgb(handle: Long, index: Int): QrBitmap?
 
     companion object {
         const val MIN_CHUNK_SIZE = 16
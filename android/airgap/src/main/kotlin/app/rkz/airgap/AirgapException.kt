@@ -1,9 +1,35 @@
 package app.rkz.airgap
 
 /**
- * Exception thrown when an Airgap operation fails
+ * Exception thrown when an Airgap operation fails, including when the native
+ * library panics; the panic never unwinds into the JVM
+ *
+ * @property code The `AIRGAP_ERR_*` code, shared with the C and JS bindings
+ * @property kind The error kind name for [code], e.g. `"CrcMismatch"` or `"Panic"`
+ * @property chunkIndex The chunk the error is about, for `ChunkOutOfBounds` and `MissingChunk`
+ * @property value The offending size, count or version, for errors that carry one
+ * @property limit The limit that was exceeded, for errors that carry one
  */
-class AirgapException : Exception {
-    constructor(message: String) : super(message)
-    constructor(message: String, cause: Throwable?) : super(message, cause)
-}
\ No newline at end of file
+class AirgapException(
+    message: String,
+    val code: Int = UNKNOWN_ERROR,
+    val kind: String = "UnknownError",
+    val chunkIndex: Int? = null,
+    val value: Long? = null,
+    val limit: Long? = null,
+    cause: Throwable? = null
+) : Exception(message, cause) {
+    constructor(message: String, cause: Throwable?) : this(message, UNKNOWN_ERROR, cause = cause)
+
+    // Called from JNI, where -1 marks a field the error doesn't carry
+    @Suppress("unused")
+    private constructor(message: String, code: Int, kind: String, chunkIndex: Int, value: Long, limit: Long) : this(
+        message, code, kind,
+        chunkIndex.takeIf { it >= 0 }, value.takeIf { it >= 0 }, limit.takeIf { it >= 0 }
+    )
+
+    companion object {
+        const val UNKNOWN_ERROR = -10
+        const val INVALID_CONFIG = -25
+    }
+}
@@ -0,0 +1,20 @@
+package app.rkz.airgap
+
+/**
+ * Result from processing a QR code with [AirgapAutoDecoder]
+ *
+ * @property chunkNumber The chunk number that was processed (0-based index)
+ * @property totalChunks The total number of chunks in this session
+ * @property formatCode The native code of the detected frame format
+ */
+data class AutoQRResult(
+    val chunkNumber: Int,
+    val totalChunks: Int,
+    val formatCode: Int
+) {
+    /**
+     * The detected frame format
+     */
+    val format: FrameFormat?
+        get() = FrameFormat.fromCode(formatCode)
+}
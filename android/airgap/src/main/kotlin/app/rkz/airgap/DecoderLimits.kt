@@ -0,0 +1,28 @@
+package app.rkz.airgap
+
+/**
+ * Caps on what [AirgapDecoder] accepts from untrusted frames; 0 disables a limit.
+ * The defaults match a new decoder's; [UNLIMITED] turns every cap off
+ *
+ * @property maxTotalChunks Largest chunk count a frame may announce
+ * @property maxPayloadBytes Most bytes buffered for one session, including a decompressed BBQr payload
+ * @property maxSessions Sessions the decoder will start until the next reset, including ones after expiry
+ * @property maxFrameLength Longest QR string accepted, checked before any decoding
+ */
+data class DecoderLimits(
+    val maxTotalChunks: Int = 4096,
+    val maxPayloadBytes: Long = 16L shl 20,
+    val maxSessions: Int = 0,
+    val maxFrameLength: Int = 4296
+) {
+    init {
+        require(maxTotalChunks >= 0 && maxPayloadBytes >= 0 && maxSessions >= 0 && maxFrameLength >= 0) {
+            "Limits must not be negative"
+        }
+    }
+
+    companion object {
+        /** No limits at all, for trusted input only */
+        val UNLIMITED = DecoderLimits(0, 0, 0, 0)
+    }
+}
@@ -0,0 +1,39 @@
+package app.rkz.airgap
+
+/**
+ * Receives decoder progress from [AirgapDecoder.setListener]
+ *
+ * Methods are called synchronously from [AirgapDecoder.processQrString],
+ * on the calling thread, before it returns. All methods default to no-ops.
+ */
+interface DecoderListener {
+    /**
+     * The first frame of a transfer was accepted
+     */
+    fun onSessionStart(sessionId: Int, totalChunks: Int) {}
+
+    /**
+     * A frame added data the decoder did not have yet
+     */
+    fun onChunk(chunkIndex: Int, received: Int, total: Int) {}
+
+    /**
+     * A frame was valid but added nothing new
+     */
+    fun onDuplicate(chunkIndex: Int) {}
+
+    /**
+     * A frame belongs to another transfer
+     */
+    fun onConflict(errorCode: Int, message: String) {}
+
+    /**
+     * Any other frame was rejected
+     */
+    fun onError(errorCode: Int, message: String) {}
+
+    /**
+     * All data has been received; [AirgapDecoder.getData] will succeed
+     */
+    fun onComplete() {}
+}
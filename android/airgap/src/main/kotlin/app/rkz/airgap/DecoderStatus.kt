@@ -0,0 +1,30 @@
+package app.rkz.airgap
+
+/**
+ * Where a decoding session stands, see [AirgapDecoder.status]
+ *
+ * @property code The native code passed across JNI
+ */
+enum class DecoderStatus(val code: Int) {
+    /** No frame accepted yet */
+    IDLE(0),
+
+    /** A session is in progress */
+    RECEIVING(1),
+
+    /** No new chunk within the idle timeout; the session resumes if one arrives */
+    STALLED(2),
+
+    /** All chunks received */
+    COMPLETE(3),
+
+    /** The session outlived the total timeout and accepts no more frames */
+    EXPIRED(4);
+
+    companion object {
+        /**
+         * Look up a status by its native code, or null for an unknown code
+         */
+        fun fromCode(code: Int): DecoderStatus? = entries.firstOrNull { it.code == code }
+    }
+}
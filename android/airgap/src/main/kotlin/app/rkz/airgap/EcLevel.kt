@@ -0,0 +1,28 @@
+package app.rkz.airgap
+
+/**
+ * QR error correction level; higher levels survive more damage (or a larger
+ * logo) at the cost of denser symbols
+ *
+ * @property code The native code passed across JNI
+ */
+enum class EcLevel(val code: Int) {
+    /** Recovers about 7% of the symbol */
+    L(0),
+
+    /** Recovers about 15% of the symbol */
+    M(1),
+
+    /** Recovers about 25% of the symbol */
+    Q(2),
+
+    /** Recovers about 30% of the symbol */
+    H(3);
+
+    companion object {
+        /**
+         * Look up an error correction level by its native code, or null for an unknown code
+         */
+        fun fromCode(code: Int): EcLevel? = entries.firstOrNull { it.code == code }
+    }
+}
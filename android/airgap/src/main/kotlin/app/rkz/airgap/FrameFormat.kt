@@ -0,0 +1,48 @@
+package app.rkz.airgap
+
+/**
+ * Wire format of the QR frames produced by [AirgapEncoder] and accepted by [AirgapDecoder]
+ *
+ * @property code The numeric code passed to the native library
+ */
+enum class FrameFormat(val code: Int) {
+    /** Native airgap chunks, Base45 encoded */
+    AIRGAP(0),
+
+    /** Blockchain Commons `ur:bytes` (fountain-coded when multipart) */
+    UR_BYTES(1),
+
+    /** Blockchain Commons `ur:crypto-psbt` (fountain-coded when multipart) */
+    UR_CRYPTO_PSBT(2),
+
+    /** BBQr PSBT (`B$?P`), zlib-compressed when that is smaller */
+    BBQR_PSBT(3),
+
+    /** BBQr signed transaction (`B$?T`) */
+    BBQR_TRANSACTION(4),
+
+    /** BBQr JSON (`B$?J`) */
+    BBQR_JSON(5),
+
+    /** BBQr CBOR (`B$?C`) */
+    BBQR_CBOR(6),
+
+    /** BBQr Unicode text (`B$?U`) */
+    BBQR_UNICODE_TEXT(7),
+
+    /** BBQr binary (`B$?B`) */
+    BBQR_BINARY(8),
+
+    /** BBQr executable (`B$?X`) */
+    BBQR_EXECUTABLE(9),
+
+    /** Plain single-QR text; only reported by [AirgapAutoDecoder] */
+    RAW(10);
+
+    companion object {
+        /**
+         * Look up a format by its native code, or null for an unknown code
+         */
+        fun fromCode(code: Int): FrameFormat? = entries.firstOrNull { it.code == code }
+    }
+}
@@ -0,0 +1,21 @@
+package app.rkz.airgap
+
+/**
+ * Layout of the uncompressed pixels returned by [AirgapEncoder.renderPixels]
+ *
+ * @property code The native code passed across JNI
+ */
+enum class PixelFormat(val code: Int) {
+    /** 4 bytes per pixel, R G B A; fits `Bitmap.copyPixelsFromBuffer` with `ARGB_8888` */
+    RGBA8(0),
+
+    /** 1 byte per pixel, grayscale */
+    LUMA8(1);
+
+    companion object {
+        /**
+         * Look up a pixel format by its native code, or null for an unknown code
+         */
+        fun fromCode(code: Int): PixelFormat? = entries.firstOrNull { it.code == code }
+    }
+}
@@ -0,0 +1,52 @@
+package app.rkz.airgap
+
+/**
+ * Rendering settings for [AirgapEncoder]. The JNI layer reads these fields
+ * by name, so renaming one needs the matching change in airgap-jni.
+ *
+ * @property ecLevel Error correction level
+ * @property qrSize Minimum edge length of the image in pixels; ignored when [moduleSize] is set
+ * @property foreground ARGB color of dark modules
+ * @property background ARGB color of light modules and the quiet zone
+ * @property quietZone Quiet zone width in modules
+ * @property moduleSize Exact pixels per module, or 0 to scale the symbol to [qrSize]
+ * @property invert Swap foreground and background, e.g. for dark-mode screens
+ * @property caption Draw a "7/32" frame counter below the symbol; UR fountain frames read "+1", "+2", ...
+ * @property sessionTag Append a 4-digit hex tag derived from the session ID to the caption
+ * @property progressBar Draw a bar filled in proportion to the frame position
+ * @property logo Image drawn over the centre of the symbol; pair it with [EcLevel.H]
+ */
+data class QrConfig(
+    val ecLevel: EcLevel = EcLevel.M,
+    val qrSize: Int = 400,
+    val foreground: Int = 0xFF000000.toInt(),
+    val background: Int = 0xFFFFFFFF.toInt(),
+    val quietZone: Int = 4,
+    val moduleSize: Int = 0,
+    val invert: Boolean = false,
+    val caption: Boolean = false,
+    val sessionTag: Boolean = false,
+    val progressBar: Boolean = false,
+    val logo: QrLogo? = null
+) {
+    init {
+        require(qrSize >= 0 && quietZone >= 0 && moduleSize >= 0) {
+            "Sizes must not be negative"
+        }
+    }
+}
+
+/**
+ * Logo for [QrConfig.logo]
+ *
+ * @property width Width in pixels
+ * @property height Height in pixels
+ * @property rgba Row-major pixels, 4 bytes each (R G B A)
+ * @property sizeRatio Logo edge length as a fraction of the symbol edge
+ */
+class QrLogo(
+    val width: Int,
+    val height: Int,
+    val rgba: ByteArray,
+    val sizeRatio: Float = 0.2f
+)
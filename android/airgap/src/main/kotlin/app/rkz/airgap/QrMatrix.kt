@@ -0,0 +1,17 @@
+package app.rkz.airgap
+
+/**
+ * Module grid of a QR symbol, quiet zone excluded
+ *
+ * @property width Number of modules per side
+ * @property modules `width * width` bytes, row-major: 1 for dark, 0 for light
+ */
+class QrMatrix(
+    val width: Int,
+    val modules: ByteArray
+) {
+    /**
+     * Whether the module at column [x], row [y] is dark
+     */
+    fun isDark(x: Int, y: Int): Boolean = modules[y * width + x].toInt() != 0
+}
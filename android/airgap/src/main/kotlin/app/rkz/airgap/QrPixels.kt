@@ -0,0 +1,38 @@
+package app.rkz.airgap
+
+import java.nio.ByteBuffer
+
+/**
+ * Uncompressed pixels of a rendered frame in a direct [ByteBuffer]
+ *
+ * @property width Image width in pixels
+ * @property height Image height in pixels
+ * @property formatCode The native code of the pixel layout
+ * @property buffer Row-major pixel data
+ */
+class QrPixels(
+    val width: Int,
+    val height: Int,
+    val formatCode: Int,
+    val buffer: ByteBuffer
+) {
+    /**
+     * The pixel layout of [buffer]
+     */
+    val format: PixelFormat?
+        get() = PixelFormat.fromCode(formatCode)
+}
+
+/**
+ * A rendered frame as packed ARGB colors, ready for
+ * `Bitmap.createBitmap(pixels, width, height, Bitmap.Config.ARGB_8888)`
+ *
+ * @property width Image width in pixels
+ * @property height Image height in pixels
+ * @property pixels Row-major ARGB colors
+ */
+class QrBitmap(
+    val width: Int,
+    val height: Int,
+    val pixels: IntArray
+)
@@ -0,0 +1,15 @@
+package app.rkz.airgap
+
+/**
+ * Frame to display, returned by [AirgapDisplayScheduler.advance]
+ *
+ * @property index Frame index for [AirgapEncoder.getQRString] / [AirgapEncoder.generatePng];
+ *                 indices at or past the chunk count are UR fountain frames
+ * @property changed Whether the frame differs from the previous call's frame
+ * @property nextChangeMs Milliseconds until the frame is due to change
+ */
+data class ScheduledFrame(
+    val index: Int,
+    val changed: Boolean,
+    val nextChangeMs: Long
+)
@@ -228,13 +228,361 @@ class AirgapTests {
         decoder2.close()
     }
 
+    @Test
+    fun testUrRoundtrip() {
+        println("\n=== Testing UR Roundtrip ===")
+
+        val psbt = ByteArray(900) { (it * 7 % 256).toByte() }
+
+        val encoder = AirgapEncoder(psbt, chunkSize = 200, format = FrameFormat.UR_CRYPTO_PSBT)
+        val decoder = AirgapDecoder(FrameFormat.UR_CRYPTO_PSBT)
+
+        val chunkCount = encoder.chunkCount
+        assertTrue(chunkCount > 1, "Should have multiple UR parts")
+
+        for (i in 0 until chunkCount) {
+            val qrString = encoder.getQRString(i)
+            assertTrue(qrString.startsWith("ur:crypto-psbt/${i + 1}-$chunkCount/"), "Unexpected UR: $qrString")
+            decoder.processQrString(qrString.uppercase())
+        }
+
+        assertTrue(decoder.isComplete, "Decoder should be complete")
+        assertContentEquals(psbt, decoder.getData(), "Decoded PSBT does not match original")
+
+        println("Successfully roundtripped ${psbt.size} bytes in $chunkCount UR parts")
+
+        encoder.close()
+        decoder.close()
+    }
+
+    @Test
+    fun testBbqrRoundtrip() {
+        println("\n=== Testing BBQr Roundtrip ===")
+
+        val psbt = "70736274ff".repeat(400).toByteArray(Charsets.UTF_8)
+
+        val encoder = AirgapEncoder(psbt, chunkSize = 200, format = FrameFormat.BBQR_PSBT)
+        val decoder = AirgapDecoder(FrameFormat.BBQR_PSBT)
+
+        for (i in 0 until encoder.chunkCount) {
+            val qrString = encoder.getQRString(i)
+            assertTrue(qrString.startsWith("B\$ZP"), "Unexpected BBQr header: $qrString")
+            decoder.processQrString(qrString)
+        }
+
+        assertTrue(decoder.isComplete, "Decoder should be complete")
+        assertContentEquals(psbt, decoder.getData(), "Decoded PSBT does not match original")
+
+        println("Successfully roundtripped ${psbt.size} bytes in ${encoder.chunkCount} BBQr frames")
+
+        encoder.close()
+        decoder.close()
+    }
+
+    @Test
+    fun testAutoDecoder() {
+        println("\n=== Testing Auto Decoder ===")
+
+        val data = "Format detected from the frames".repeat(20).toByteArray(Charsets.UTF_8)
+
+        for (format in listOf(FrameFormat.AIRGAP, FrameFormat.UR_BYTES, FrameFormat.BBQR_BINARY)) {
+            val encoder = AirgapEncoder(data, chunkSize = 100, format = format)
+            val decoder = AirgapAutoDecoder()
+
+            for (i in 0 until encoder.chunkCount) {
+                val result = decoder.processQrString(encoder.getQRString(i))
+                assertEquals(format, result.format, "Unexpected detected format")
+            }
+
+            assertTrue(decoder.isComplete, "Decoder should be complete")
+            assertContentEquals(data, decoder.getData(), "Decoded data does not match original")
+
+            encoder.close()
+            decoder.close()
+        }
+
+        val decoder = AirgapAutoDecoder()
+        val result = decoder.processQrString("bitcoin:bc1qexample")
+        assertEquals(FrameFormat.RAW, result.format)
+        assertTrue(decoder.isComplete, "Raw text should complete immediately")
+        decoder.close()
+
+        println("Detected native, UR, BBQr and raw frames")
+    }
+
+    @Test
+    fun testMatrixAndPixels() {
+        println("\n=== Testing Matrix and Pixel Buffers ===")
+
+        val encoder = AirgapEncoder("Pixels without PNG".toByteArray(Charsets.UTF_8), chunkSize = 100)
+
+        val matrix = encoder.getMatrix(0)
+        assertEquals(matrix.width * matrix.width, matrix.modules.size)
+        assertTrue(matrix.isDark(0, 0), "Finder pattern corner should be dark")
+
+        val pixels = encoder.renderPixels(0)
+        assertEquals(pixels.width * pixels.height * 4, pixels.buffer.capacity())
+        assertTrue(pixels.buffer.isDirect, "Pixel buffer should be direct")
+
+        val luma = encoder.renderPixels(0, PixelFormat.LUMA8)
+        assertEquals(PixelFormat.LUMA8, luma.format)
+        assertEquals(pixels.width * pixels.height, luma.buffer.capacity())
+
+        val bitmap = encoder.renderArgb(0)
+        assertEquals(pixels.width * pixels.height, bitmap.pixels.size)
+        assertEquals(0xFFFFFFFF.toInt(), bitmap.pixels[0], "Quiet zone should be opaque white")
+
+        println("Rendered ${pixels.width}x${pixels.height} frame with ${matrix.width}x${matrix.width} modules")
+
+        encoder.close()
+    }
+
+    @Test
+    fun testQrConfig() {
+        println("\n=== Testing QR Config ===")
+
+        val data = "Configured rendering".toByteArray(Charsets.UTF_8)
+        val config = QrConfig(
+            ecLevel = EcLevel.H,
+            background = 0xFFFF0000.toInt(),
+            quietZone = 1,
+            moduleSize = 2,
+            logo = QrLogo(4, 4, ByteArray(4 * 4 * 4) { 0x80.toByte() })
+        )
+        val encoder = AirgapEncoder(data, chunkSize = 100, config = config)
+
+        val matrix = encoder.getMatrix(0)
+        val bitmap = encoder.renderArgb(0)
+        assertEquals((matrix.width + 2) * 2, bitmap.width, "Module size and quiet zone should set the width")
+        assertEquals(0xFFFF0000.toInt(), bitmap.pixels[0], "Quiet zone should use the background color")
+
+        // Rendering settings don't change the frames themselves
+        val decoder = AirgapDecoder()
+        for (i in 0 until encoder.chunkCount) {
+            decoder.processQrString(encoder.getQRString(i))
+        }
+        assertContentEquals(data, decoder.getData())
+        decoder.close()
+        encoder.close()
+
+        assertFailsWith<AirgapException> {
+            AirgapEncoder(data, chunkSize = 100, config = QrConfig(logo = QrLogo(4, 4, ByteArray(3))))
+        }
+        val fixedUr = assertFailsWith<AirgapException> {
+            AirgapEncoder(data, chunkSize = 100, format = FrameFormat.UR_BYTES, fixedSessionId = 1)
+        }
+        assertEquals("InvalidConfig", fixedUr.kind)
+        val oversized = assertFailsWith<AirgapException>("Should reject images past the size cap") {
+            AirgapEncoder(data, chunkSize = 100, config = QrConfig(moduleSize = Int.MAX_VALUE / 2))
+        }
+        assertEquals("InvalidConfig", oversized.kind)
+        println("Rendered ${bitmap.width}px frame at EC level H")
+    }
+
+    @Test
+    fun testDisplayScheduler() {
+        println("\n=== Testing Display Scheduler ===")
+
+        val encoder = AirgapEncoder(ByteArray(1000) { it.toByte() }, chunkSize = 250)
+        val scheduler = AirgapDisplayScheduler(encoder, framesPerSecond = 10f)
+
+        assertEquals(0, scheduler.advance(0).index)
+        assertFalse(scheduler.advance(50).changed, "Frame should stay for 100 ms")
+        assertEquals(1, scheduler.advance(50).index)
+
+        scheduler.reportMissing(intArrayOf(3))
+        assertEquals(3, scheduler.advance(100).index)
+        assertEquals(2, scheduler.advance(100).index)
+
+        println("Scheduled frames at 10 fps with missing chunk priority")
+
+        scheduler.close()
+        encoder.close()
+    }
+
+    @Test
+    fun testDecoderListener() {
+        println("\n=== Testing Decoder Listener ===")
+
+        val encoder = AirgapEncoder(ByteArray(300) { it.toByte() }, chunkSize = 100)
+        val decoder = AirgapDecoder()
+        val events = mutableListOf<String>()
+
+        decoder.setListener(object : DecoderListener {
+            override fun onSessionStart(sessionId: Int, totalChunks: Int) { events.add("start") }
+            override fun onChunk(chunkIndex: Int, received: Int, total: Int) { events.add("chunk $chunkIndex") }
+            override fun onDuplicate(chunkIndex: Int) { events.add("duplicate $chunkIndex") }
+            override fun onComplete() { events.add("complete") }
+        })
+
+        decoder.processQrString(encoder.getQRString(0))
+        decoder.processQrString(encoder.getQRString(0))
+        decoder.processQrString(encoder.getQRString(1))
+        decoder.processQrString(encoder.getQRString(2))
+
+        assertEquals(
+            listOf("start", "chunk 0", "duplicate 0", "chunk 1", "chunk 2", "complete"),
+            events
+        )
+
+        println("Received events: $events")
+
+        encoder.close()
+        decoder.close()
+    }
+
+    @Test
+    fun testConcurrentDecoding() {
+        println("\n=== Testing Concurrent Decoding ===")
+
+        val testData = ByteArray(5000) { (it % 251).toByte() }
+        val encoder = AirgapEncoder(testData, chunkSize = 100)
+        val frames = (0 until encoder.chunkCount).map { encoder.getQRString(it) }
+        val decoder = AirgapDecoder()
+
+        // Camera thread feeds frames while this thread polls progress
+        val scanner = Thread { frames.forEach { decoder.processQrString(it) } }
+        scanner.start()
+
+        var lastReceived = 0
+        while (!decoder.isComplete) {
+            val received = decoder.receivedChunks
+            assertTrue(received >= lastReceived, "Progress should never go backwards")
+            assertTrue(received <= frames.size, "Received count out of range")
+            lastReceived = received
+        }
+        scanner.join()
+
+        assertEquals(frames.size, decoder.receivedChunks)
+        assertContentEquals(testData, decoder.getData())
+        println("Decoded ${frames.size} chunks while polling progress")
+
+        decoder.close()
+        assertFailsWith<IllegalStateException> { decoder.receivedChunks }
+        encoder.close()
+    }
+
+    @Test
+    fun testSessionTimeouts() {
+        println("\n=== Testing Session Timeouts ===")
+
+        val encoder = AirgapEncoder(ByteArray(300) { it.toByte() }, chunkSize = 100)
+        val decoder = AirgapDecoder()
+        decoder.setTimeouts(idleMs = 50, totalMs = 150)
+        assertEquals(DecoderStatus.IDLE, decoder.status)
+
+        decoder.processQrString(encoder.getQRString(0))
+        assertEquals(DecoderStatus.RECEIVING, decoder.status)
+
+        Thread.sleep(80)
+        assertEquals(DecoderStatus.STALLED, decoder.status)
+        decoder.processQrString(encoder.getQRString(1))
+        assertEquals(DecoderStatus.RECEIVING, decoder.status)
+
+        Thread.sleep(120)
+        assertEquals(DecoderStatus.EXPIRED, decoder.status)
+        assertFailsWith<AirgapException> { decoder.processQrString(encoder.getQRString(2)) }
+
+        assertTrue(decoder.expireStale(), "Expired session should be reset")
+        assertEquals(DecoderStatus.IDLE, decoder.status)
+        println("Session stalled, expired and was reset")
+
+        encoder.close()
+        decoder.close()
+    }
+
+    @Test
+    fun testDecoderLimits() {
+        println("\n=== Testing Decoder Limits ===")
+
+        val encoder = AirgapEncoder(ByteArray(1000) { it.toByte() }, chunkSize = 100)
+        val decoder = AirgapDecoder()
+
+        // New decoders are already capped
+        val defaultCap = assertFailsWith<AirgapException>("Should reject frames over the default length") {
+            decoder.processQrString("A".repeat(5000))
+        }
+        assertEquals(4296L, defaultCap.limit)
+
+        decoder.setLimits(DecoderLimits(maxTotalChunks = 5))
+        assertFailsWith<AirgapException>("Should reject frames announcing too many chunks") {
+            decoder.processQrString(encoder.getQRString(0))
+        }
+        assertEquals(DecoderStatus.IDLE, decoder.status)
+
+        decoder.setLimits(DecoderLimits(maxFrameLength = 20))
+        val tooLong = assertFailsWith<AirgapException>("Should reject long frames") {
+            decoder.processQrString(encoder.getQRString(0))
+        }
+        assertEquals(-29, tooLong.code)
+        assertEquals("FrameTooLong", tooLong.kind)
+        assertEquals(20L, tooLong.limit)
+        assertNull(tooLong.chunkIndex)
+
+        decoder.setLimits(DecoderLimits(maxPayloadBytes = 250))
+        decoder.processQrString(encoder.getQRString(0))
+        decoder.processQrString(encoder.getQRString(1))
+        assertFailsWith<AirgapException>("Should reject chunks past the byte limit") {
+            decoder.processQrString(encoder.getQRString(2))
+        }
+        assertEquals(2, decoder.receivedChunks)
+        println("Limits rejected hostile frames")
+
+        encoder.close()
+        decoder.close()
+    }
+
+    @Test
+    fun testGoldenVectors() {
+        println("\n=== Testing Golden Vectors ===")
+
+        // test-kotlin.sh runs from android/, gradle from android/airgap/
+        val vectors = listOf("../vectors", "../../vectors")
+            .map { java.io.File(it, "chunk_format.tsv") }
+            .first { it.exists() }
+
+        var encoder: AirgapEncoder? = null
+        var errorRows = 0
+        for (line in vectors.readLines()) {
+            if (line.isEmpty() || line.startsWith("#")) continue
+            val fields = line.split("\t")
+            when (fields[0]) {
+                "encode" -> {
+                    encoder?.close()
+                    val data = fields[4].chunked(2).map { it.toInt(16).toByte() }.toByteArray()
+                    val sessionId = fields[2].toLong().toInt()
+                    encoder = AirgapEncoder(data, chunkSize = fields[3].toInt(), fixedSessionId = sessionId)
+                    assertEquals(sessionId, encoder.sessionId, "Session ID mismatch for ${fields[1]}")
+                }
+                "frame" -> {
+                    val frame = encoder!!.getQRString(fields[1].toInt())
+                    assertEquals(fields[3], frame, "Frame ${fields[1]} differs from vector")
+                }
+                "error" -> {
+                    AirgapDecoder().use { decoder ->
+                        val error = assertFailsWith<AirgapException>("Vector ${fields[1]} should be rejected") {
+                            decoder.processQrString(fields[4])
+                        }
+                        assertEquals(fields[2].toInt(), error.code, fields[1])
+                        assertEquals(fields[3], error.kind, fields[1])
+                    }
+                    errorRows++
+                }
+            }
+        }
+        encoder?.close()
+        assertTrue(errorRows > 0, "Vectors should include malformed frames")
+    }
+
     @Test
     fun testEmptyDataThrowsException() {
         println("\n=== Testing Empty Data Throws Exception ===")
 
-        assertFailsWith<AirgapException>("Should throw exception for empty data") {
+        val empty = assertFailsWith<AirgapException>("Should throw exception for empty data") {
             AirgapEncoder(ByteArray(0), chunkSize = 100)
         }
+        assertEquals(-22, empty.code)
+        assertEquals("EmptyData", empty.kind)
 
         println("Empty data correctly throws exception")
     }
@@ -245,9 +593,12 @@ class AirgapTests {
 
         val testData = "Test".toByteArray(Charsets.UTF_8)
 
-        assertFailsWith<AirgapException>("Should throw exception for chunk size too small") {
+        val tooSmall = assertFailsWith<AirgapException>("Should throw exception for chunk size too small") {
             AirgapEncoder(testData, chunkSize = 10)
         }
+        assertEquals("ChunkSizeTooSmall", tooSmall.kind)
+        assertEquals(10L, tooSmall.value)
+        assertEquals(AirgapEncoder.MIN_CHUNK_SIZE.toLong(), tooSmall.limit)
 
         assertFailsWith<AirgapException>("Should throw exception for chunk size too large") {
             AirgapEncoder(testData, chunkSize = 2000)
@@ -269,6 +620,17 @@ fun main() {
         "testOutOfOrderChunks" to { tests.testOutOfOrderChunks() },
         "testDecoderReset" to { tests.testDecoderReset() },
         "testMultipleEncoders" to { tests.testMultipleEncoders() },
+        "testUrRoundtrip" to { tests.testUrRoundtrip() },
+        "testBbqrRoundtrip" to { tests.testBbqrRoundtrip() },
+        "testAutoDecoder" to { tests.testAutoDecoder() },
+        "testMatrixAndPixels" to { tests.testMatrixAndPixels() },
+        "testQrConfig" to { tests.testQrConfig() },
+        "testDisplayScheduler" to { tests.testDisplayScheduler() },
+        "testDecoderListener" to { tests.testDecoderListener() },
+        "testConcurrentDecoding" to { tests.testConcurrentDecoding() },
+        "testSessionTimeouts" to { tests.testSessionTimeouts() },
+        "testDecoderLimits" to { tests.testDecoderLimits() },
+        "testGoldenVectors" to { tests.testGoldenVectors() },
         "testEmptyDataThrowsException" to { tests.testEmptyDataThrowsException() },
         "testInvalidChunkSizeThrowsException" to { tests.testInvalidChunkSizeThrowsException() }
     )
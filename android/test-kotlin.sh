@@ -19,16 +19,16 @@ fi
 # Build the library for macOS (JNI now works on all JVM targets!)
 echo "Building native library for macOS ($TARGET)..."
 cd ..
-cargo build --release --target $TARGET
+cargo build --release -p airgap-jni --target $TARGET
 cd android
 
 # Create jniLibs directory for tests
 JNILIBS_DIR="airgap/src/test/jniLibs"
 mkdir -p "$JNILIBS_DIR"
 
-# Copy the dylib to jniLibs (JVM will look for libairgap.dylib)
+# Copy the dylib to jniLibs (JVM will look for libairgap_jni.dylib)
 echo "Copying native library to test jniLibs..."
-cp "../target/$TARGET/release/libairgap.dylib" "$JNILIBS_DIR/"
+cp "../target/$TARGET/release/libairgap_jni.dylib" "$JNILIBS_DIR/"
 
 # Compile classes using gradle
 echo "Compiling Kotlin classes with gradle..."
@@ -69,26 +69,26 @@ fi
 # --------------------------------------------------------
 echo ""
 echo "Building arm64-v8a..."
-cargo build --release --target aarch64-linux-android
+cargo build --release -p airgap-jni --target aarch64-linux-android
 
 echo "Building armeabi-v7a..."
-cargo build --release --target armv7-linux-androideabi
+cargo build --release -p airgap-jni --target armv7-linux-androideabi
 
 echo "Building x86..."
-cargo build --release --target i686-linux-android
+cargo build --release -p airgap-jni --target i686-linux-android
 
 echo "Building x86_64..."
-cargo build --release --target x86_64-linux-android
+cargo build --release -p airgap-jni --target x86_64-linux-android
 
 # --------------------------------------------------------
 # Copy to jniLibs
 # --------------------------------------------------------
 mkdir -p android/airgap/src/main/jniLibs/{arm64-v8a,armeabi-v7a,x86,x86_64}
 
-cp target/aarch64-linux-android/release/libairgap.so android/airgap/src/main/jniLibs/arm64-v8a/
-cp target/armv7-linux-androideabi/release/libairgap.so android/airgap/src/main/jniLibs/armeabi-v7a/
-cp target/i686-linux-android/release/libairgap.so android/airgap/src/main/jniLibs/x86/
-cp target/x86_64-linux-android/release/libairgap.so android/airgap/src/main/jniLibs/x86_64/
+cp target/aarch64-linux-android/release/libairgap_jni.so android/airgap/src/main/jniLibs/arm64-v8a/
+cp target/armv7-linux-androideabi/release/libairgap_jni.so android/airgap/src/main/jniLibs/armeabi-v7a/
+cp target/i686-linux-android/release/libairgap_jni.so android/airgap/src/main/jniLibs/x86/
+cp target/x86_64-linux-android/release/libairgap_jni.so android/airgap/src/main/jniLibs/x86_64/
 
 # --------------------------------------------------------
 # Verify 16KB alignment (arm64)
@@ -96,7 +96,7 @@ cp target/x86_64-linux-android/release/libairgap.so android/airgap/src/main/jniL
 if [ -f "$CLANG/llvm-readelf" ]; then
     echo ""
     echo "Checking 16KB alignment (arm64)..."
-    "$CLANG/llvm-readelf" -l android/airgap/src/main/jniLibs/arm64-v8a/libairgap.so | grep -i align || true
+    "$CLANG/llvm-readelf" -l android/airgap/src/main/jniLibs/arm64-v8a/libairgap_jni.so | grep -i align || true
 fi
 
 echo ""
@@ -11,9 +11,9 @@ echo "Building dynamic Airgap framework..."
 rustup target add aarch64-apple-ios aarch64-apple-ios-sim x86_64-apple-ios 2>/dev/null || true
 
 # Build Rust dylibs
-cargo build --release --target aarch64-apple-ios
-cargo build --release --target aarch64-apple-ios-sim
-cargo build --release --target x86_64-apple-ios
+cargo build --release -p airgap-ffi --target aarch64-apple-ios
+cargo build --release -p airgap-ffi --target aarch64-apple-ios-sim
+cargo build --release -p airgap-ffi --target x86_64-apple-ios
 
 build_dynamic() {
     local arch=$1
@@ -23,7 +23,7 @@ rustup target add wasm32-unknown-unknown 2>/dev/null || true
 # --------------------------------------------------------
 echo ""
 echo "Building WASM package..."
-wasm-pack build --release --target web --out-dir pkg
+wasm-pack build crates/airgap-wasm --release --target web --out-dir ../../pkg --out-name airgap
 
 # --------------------------------------------------------
 # Output
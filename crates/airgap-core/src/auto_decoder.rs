@@ -0,0 +1,157 @@
+// src/auto_decoder - format-sniffing front-end over `Decoder`
+
+use alloc::vec::Vec;
+use crate::bbqr::BbqrFrame;
+use crate::decoder::Decoder;
+use crate::error::AirgapError;
+use crate::protocol::*;
+
+/// Format of a scanned frame as recognised by `detect_format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectedFormat {
+    /// A frame in one of the multipart formats `Decoder` understands.
+    Frames(FrameFormat),
+    /// Any other text, treated as a complete single-QR payload.
+    Raw,
+}
+
+// Characters of the Base45 alphabet (RFC 9285)
+const BASE45_CHARS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// Classify a scanned QR string: `ur:` prefix, `B$` prefix, Base45 with the
+/// `0x19F7` magic, otherwise raw text.
+pub fn detect_format(qr_data: &str) -> Result<DetectedFormat, AirgapError> {
+    let trimmed = qr_data.trim();
+
+    if trimmed.get(..3).is_some_and(|prefix| prefix.eq_ignore_ascii_case("ur:")) {
+        let ur_type = trimmed[3..].split('/').next().unwrap_or_default();
+        let ur_type = ur_type.to_ascii_lowercase().parse()?;
+        return Ok(DetectedFormat::Frames(FrameFormat::Ur(ur_type)));
+    }
+
+    if trimmed.starts_with("B$") {
+        let frame = BbqrFrame::parse(trimmed)?;
+        return Ok(DetectedFormat::Frames(FrameFormat::Bbqr(frame.file_type)));
+    }
+
+    // Only the first Base45 group, which holds the magic, is decoded here;
+    // `Decoder` decodes the whole frame
+    let magic = qr_data.get(..3).and_then(|prefix| base45::decode(prefix).ok());
+    if magic.is_some_and(|bytes| bytes == MAGIC) && qr_data.bytes().all(|c| BASE45_CHARS.contains(&c)) {
+        return Ok(DetectedFormat::Frames(FrameFormat::Airgap));
+    }
+
+    Ok(DetectedFormat::Raw)
+}
+
+/// A processed frame together with the format it was detected as.
+#[derive(Debug, Clone)]
+pub struct DetectedFrame {
+    pub format: DetectedFormat,
+    pub chunk: Chunk,
+}
+
+/// Decoder for scanners that may see native, UR, BBQr or plain QR codes.
+///
+/// The first successfully processed frame fixes the format of the session;
+/// frames in any other format are rejected with `SessionMismatch` until
+/// `reset` is called.
+#[derive(Default)]
+pub struct AutoDecoder {
+    format: Option<DetectedFormat>,
+    decoder: Option<Decoder>,
+    raw: Option<Vec<u8>>,
+}
+
+impl AutoDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Format of the current session, `None` until a frame has been accepted.
+    pub fn format(&self) -> Option<DetectedFormat> {
+        self.format
+    }
+
+    pub fn process_qr_string(&mut self, qr_data: &str) -> Result<DetectedFrame, AirgapError> {
+        let detected = detect_format(qr_data)?;
+
+        if self.format.is_some_and(|format| format != detected) {
+            return Err(AirgapError::SessionMismatch);
+        }
+
+        let chunk = match detected {
+            DetectedFormat::Raw => {
+                let data = qr_data.as_bytes().to_vec();
+                if self.raw.as_ref().is_some_and(|raw| *raw != data) {
+                    return Err(AirgapError::SessionMismatch);
+                }
+                // Drop a decoder left behind by frames that were all rejected
+                self.decoder = None;
+                self.raw = Some(data.clone());
+                Chunk {
+                    total_chunks: 1,
+                    chunk_index: 0,
+                    session_id: 0,
+                    data,
+                }
+            }
+            DetectedFormat::Frames(format) => {
+                // Start over if only rejected frames have been seen so far
+                let decoder = match &mut self.decoder {
+                    Some(decoder) if self.format.is_some() => decoder,
+                    slot => slot.insert(Decoder::with_format(format)),
+                };
+                decoder.process_qr_string(qr_data)?
+            }
+        };
+
+        self.format = Some(detected);
+        Ok(DetectedFrame { format: detected, chunk })
+    }
+
+    // The decoder of a frame session; a stale one from rejected frames is ignored
+    fn frames(&self) -> Option<&Decoder> {
+        match self.format {
+            Some(DetectedFormat::Frames(_)) => self.decoder.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        match self.frames() {
+            Some(decoder) => decoder.is_complete(),
+            None => self.raw.is_some(),
+        }
+    }
+
+    pub fn session_id(&self) -> Option<u32> {
+        self.frames().and_then(Decoder::session_id)
+    }
+
+    pub fn received_count(&self) -> usize {
+        match self.frames() {
+            Some(decoder) => decoder.received_count(),
+            None => self.raw.is_some() as usize,
+        }
+    }
+
+    pub fn total_count(&self) -> usize {
+        match self.frames() {
+            Some(decoder) => decoder.total_count(),
+            None => self.raw.is_some() as usize,
+        }
+    }
+
+    pub fn get_data(&self) -> Result<Vec<u8>, AirgapError> {
+        match (self.frames(), &self.raw) {
+            (Some(decoder), _) => decoder.get_data(),
+            (None, Some(raw)) => Ok(raw.clone()),
+            (None, None) => Err(AirgapError::EncodingError("Incomplete: 0/0 chunks".into())),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
@@ -0,0 +1,116 @@
+// src/batch - batch rendering helpers, parallel with the `parallel` feature
+
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::Waker;
+use crate::error::AirgapError;
+
+/// Shared flag to abort a running batch or async decode from another thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<CancelState>);
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    // Tasks to wake on cancel, see `register`
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::Release);
+        let wakers = std::mem::take(&mut *self.lock_wakers());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Wake `waker` once when the token is cancelled. A task registers again
+    /// on every poll; wakers of the same task are only stored once.
+    #[cfg_attr(not(feature = "async"), allow(dead_code))]
+    pub(crate) fn register(&self, waker: &Waker) {
+        let mut wakers = self.lock_wakers();
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+        drop(wakers);
+        // Cancelled between the caller's check and the push: nobody will drain it
+        if self.is_cancelled() {
+            waker.wake_by_ref();
+        }
+    }
+
+    fn lock_wakers(&self) -> MutexGuard<'_, Vec<Waker>> {
+        self.0.wakers.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BatchOptions {
+    /// Worker threads for the batch; 0 runs on rayon's global pool. A
+    /// bounded pool is built on the first batch and reused by later ones
+    /// (and by clones), so set this before the first batch. Ignored without
+    /// the `parallel` feature, where batches render serially.
+    pub threads: usize,
+    /// Checked before each item; a cancelled batch fails with `Cancelled`.
+    pub cancel: CancelToken,
+    // Bounded pool for `threads > 0`, built on first use and shared by clones
+    #[cfg(feature = "parallel")]
+    pool: Arc<std::sync::OnceLock<rayon::ThreadPool>>,
+}
+
+impl BatchOptions {
+    pub fn with_threads(threads: usize) -> Self {
+        Self {
+            threads,
+            ..Self::default()
+        }
+    }
+}
+
+/// Run `f` for every index in `0..count`, keeping results in index order.
+pub fn map_batch<T, F>(count: usize, options: &BatchOptions, f: F) -> Result<Vec<T>, AirgapError>
+where
+    T: Send,
+    F: Fn(usize) -> Result<T, AirgapError> + Sync,
+{
+    let run = |index: usize| {
+        if options.cancel.is_cancelled() {
+            return Err(AirgapError::Cancelled);
+        }
+        f(index)
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+
+        if options.threads == 0 {
+            return (0..count).into_par_iter().map(run).collect();
+        }
+        let pool = match options.pool.get() {
+            Some(pool) => pool,
+            None => {
+                let built = rayon::ThreadPoolBuilder::new()
+                    .num_threads(options.threads)
+                    .build()
+                    .map_err(|e| AirgapError::EncodingError(e.to_string()))?;
+                // Another batch sharing these options may have won the race
+                let _ = options.pool.set(built);
+                options.pool.get().unwrap_or_else(|| unreachable!())
+            }
+        };
+        pool.install(|| (0..count).into_par_iter().map(run).collect())
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    (0..count).map(run).collect()
+}
@@ -0,0 +1,387 @@
+// src/bbqr - Coinkite BBQr ("Better Bitcoin QR") multipart frames
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use data_encoding::{BASE32_NOPAD, HEXUPPER};
+use miniz_oxide::inflate::{decompress_to_vec_with_limit, TINFLStatus};
+use crate::error::AirgapError;
+use crate::limits::DecoderLimits;
+
+pub const HEADER_LEN: usize = 8;
+pub const MAX_PARTS: usize = 1295; // "ZZ" in base36
+
+/// BBQr file type code (6th header character).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BbqrFileType {
+    Psbt,
+    Transaction,
+    Json,
+    Cbor,
+    UnicodeText,
+    Binary,
+    Executable,
+}
+
+impl BbqrFileType {
+    pub fn code(&self) -> char {
+        match self {
+            BbqrFileType::Psbt => 'P',
+            BbqrFileType::Transaction => 'T',
+            BbqrFileType::Json => 'J',
+            BbqrFileType::Cbor => 'C',
+            BbqrFileType::UnicodeText => 'U',
+            BbqrFileType::Binary => 'B',
+            BbqrFileType::Executable => 'X',
+        }
+    }
+
+    pub fn from_code(code: char) -> Result<Self, AirgapError> {
+        match code {
+            'P' => Ok(BbqrFileType::Psbt),
+            'T' => Ok(BbqrFileType::Transaction),
+            'J' => Ok(BbqrFileType::Json),
+            'C' => Ok(BbqrFileType::Cbor),
+            'U' => Ok(BbqrFileType::UnicodeText),
+            'B' => Ok(BbqrFileType::Binary),
+            'X' => Ok(BbqrFileType::Executable),
+            other => Err(AirgapError::InvalidBbqr(format!("unknown file type '{}'", other))),
+        }
+    }
+}
+
+/// BBQr payload encoding (5th header character).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BbqrEncoding {
+    /// Uppercase hex.
+    Hex,
+    /// RFC 4648 Base32 without padding.
+    Base32,
+    /// Raw DEFLATE with a 1 KiB window, then Base32.
+    Zlib,
+}
+
+impl BbqrEncoding {
+    pub fn code(&self) -> char {
+        match self {
+            BbqrEncoding::Hex => 'H',
+            BbqrEncoding::Base32 => '2',
+            BbqrEncoding::Zlib => 'Z',
+        }
+    }
+
+    pub fn from_code(code: char) -> Result<Self, AirgapError> {
+        match code {
+            'H' => Ok(BbqrEncoding::Hex),
+            '2' => Ok(BbqrEncoding::Base32),
+            'Z' => Ok(BbqrEncoding::Zlib),
+            other => Err(AirgapError::InvalidBbqr(format!("unknown encoding '{}'", other))),
+        }
+    }
+
+    // Part lengths must be a multiple of this so each part decodes on its own
+    fn char_alignment(&self) -> usize {
+        match self {
+            BbqrEncoding::Hex => 2,
+            BbqrEncoding::Base32 | BbqrEncoding::Zlib => 8,
+        }
+    }
+
+    fn encode(&self, bytes: &[u8]) -> String {
+        match self {
+            BbqrEncoding::Hex => HEXUPPER.encode(bytes),
+            BbqrEncoding::Base32 | BbqrEncoding::Zlib => BASE32_NOPAD.encode(bytes),
+        }
+    }
+
+    fn decode(&self, text: &str) -> Result<Vec<u8>, AirgapError> {
+        let decoded = match self {
+            BbqrEncoding::Hex => HEXUPPER.decode(text.as_bytes()),
+            BbqrEncoding::Base32 | BbqrEncoding::Zlib => BASE32_NOPAD.decode(text.as_bytes()),
+        };
+        decoded.map_err(|e| AirgapError::InvalidBbqr(e.to_string()))
+    }
+}
+
+/// Header and payload of a single scanned BBQr frame.
+#[derive(Debug, Clone)]
+pub struct BbqrFrame {
+    pub encoding: BbqrEncoding,
+    pub file_type: BbqrFileType,
+    pub total: usize,
+    pub index: usize,
+    /// Part payload after hex/Base32 decoding (still compressed for `Zlib`).
+    pub data: Vec<u8>,
+}
+
+impl BbqrFrame {
+    pub fn parse(text: &str) -> Result<Self, AirgapError> {
+        let text = text.trim();
+        if text.len() < HEADER_LEN || !text.is_ascii() || !text.starts_with("B$") {
+            return Err(AirgapError::InvalidBbqr("missing 'B$' header".into()));
+        }
+
+        let header = text.as_bytes();
+        let encoding = BbqrEncoding::from_code(header[2] as char)?;
+        let file_type = BbqrFileType::from_code(header[3] as char)?;
+        let total = parse_base36(&text[4..6])?;
+        let index = parse_base36(&text[6..8])?;
+
+        if total == 0 {
+            return Err(AirgapError::InvalidBbqr("zero part count".into()));
+        }
+        if index >= total {
+            return Err(AirgapError::ChunkOutOfBounds(index as u16));
+        }
+
+        let data = encoding.decode(&text[HEADER_LEN..])?;
+
+        Ok(Self { encoding, file_type, total, index, data })
+    }
+}
+
+// Two uppercase base36 digits; `from_str_radix` alone would also take '+' and lowercase
+fn parse_base36(digits: &str) -> Result<usize, AirgapError> {
+    let valid = digits.len() == 2 && digits.bytes().all(|c| c.is_ascii_digit() || c.is_ascii_uppercase());
+    match valid {
+        true => usize::from_str_radix(digits, 36)
+            .map_err(|_| AirgapError::InvalidBbqr(format!("invalid part number '{}'", digits))),
+        false => Err(AirgapError::InvalidBbqr(format!("invalid part number '{}'", digits))),
+    }
+}
+
+fn to_base36(value: usize) -> String {
+    const DIGITS: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let (high, low) = (value / 36, value % 36);
+    format!("{}{}", DIGITS[high] as char, DIGITS[low] as char)
+}
+
+// Compression needs a 1 KiB window, which only the `std` build's zlib offers
+#[cfg(feature = "std")]
+fn deflate(data: &[u8]) -> Result<Vec<u8>, AirgapError> {
+    use flate2::{Compress, Compression, FlushCompress, Status};
+
+    // wbits=10, no zlib header: what Coldcard's decompressor expects
+    let mut compress = Compress::new_with_window_bits(Compression::best(), false, 10);
+    let mut out = Vec::with_capacity(data.len() + 64);
+    loop {
+        let consumed = compress.total_in() as usize;
+        let status = compress
+            .compress_vec(&data[consumed..], &mut out, FlushCompress::Finish)
+            .map_err(|e| AirgapError::EncodingError(e.to_string()))?;
+        match status {
+            Status::StreamEnd => return Ok(out),
+            _ => out.reserve(out.capacity().max(64)),
+        }
+    }
+}
+
+// `limits` caps the inflated size, so a small zlib bomb cannot exhaust memory
+fn inflate(data: &[u8], limits: &DecoderLimits) -> Result<Vec<u8>, AirgapError> {
+    let max = match limits.max_payload_bytes {
+        0 => usize::MAX,
+        max => max,
+    };
+    decompress_to_vec_with_limit(data, max).map_err(|e| match e.status {
+        TINFLStatus::HasMoreOutput => AirgapError::PayloadTooLarge(max),
+        TINFLStatus::FailedCannotMakeProgress => AirgapError::InvalidBbqr("truncated zlib stream".into()),
+        status => AirgapError::InvalidBbqr(format!("invalid zlib stream ({:?})", status)),
+    })
+}
+
+// ============================================================================
+// ENCODER
+// ============================================================================
+
+/// Splits a payload into `B$...` frames.
+#[derive(Debug, Clone)]
+pub struct BbqrEncoder {
+    file_type: BbqrFileType,
+    encoding: BbqrEncoding,
+    parts: Vec<String>,
+}
+
+impl BbqrEncoder {
+    /// Encode with `Zlib` when it makes the payload smaller, `Base32` otherwise.
+    /// Without `std` frames are never compressed.
+    pub fn new(file_type: BbqrFileType, data: &[u8], max_part_bytes: usize) -> Result<Self, AirgapError> {
+        Self::with_encoding(file_type, BbqrEncoding::Zlib, data, max_part_bytes)
+    }
+
+    pub fn with_encoding(
+        file_type: BbqrFileType,
+        encoding: BbqrEncoding,
+        data: &[u8],
+        max_part_bytes: usize,
+    ) -> Result<Self, AirgapError> {
+        if data.is_empty() {
+            return Err(AirgapError::EmptyData);
+        }
+
+        let (encoding, body) = match encoding {
+            #[cfg(feature = "std")]
+            BbqrEncoding::Zlib => {
+                let compressed = deflate(data)?;
+                if compressed.len() < data.len() {
+                    (BbqrEncoding::Zlib, BbqrEncoding::Zlib.encode(&compressed))
+                } else {
+                    (BbqrEncoding::Base32, BbqrEncoding::Base32.encode(data))
+                }
+            }
+            #[cfg(not(feature = "std"))]
+            BbqrEncoding::Zlib => (BbqrEncoding::Base32, BbqrEncoding::Base32.encode(data)),
+            other => (other, other.encode(data)),
+        };
+
+        let align = encoding.char_alignment();
+        let max_chars = match encoding {
+            BbqrEncoding::Hex => max_part_bytes * 2,
+            BbqrEncoding::Base32 | BbqrEncoding::Zlib => max_part_bytes / 5 * 8,
+        };
+        let per_part = (max_chars - max_chars % align).max(align);
+
+        let total = body.len().div_ceil(per_part);
+        if total > MAX_PARTS {
+            return Err(AirgapError::TooManyChunks(total));
+        }
+
+        let prefix = format!("B${}{}{}", encoding.code(), file_type.code(), to_base36(total));
+        let parts = body
+            .as_bytes()
+            .chunks(per_part)
+            .enumerate()
+            .map(|(index, chars)| {
+                // Base32/hex output is ASCII so every chunk is valid UTF-8
+                format!("{}{}{}", prefix, to_base36(index), core::str::from_utf8(chars).unwrap())
+            })
+            .collect();
+
+        Ok(Self { file_type, encoding, parts })
+    }
+
+    pub fn file_type(&self) -> BbqrFileType {
+        self.file_type
+    }
+
+    pub fn encoding(&self) -> BbqrEncoding {
+        self.encoding
+    }
+
+    pub fn part_count(&self) -> usize {
+        self.parts.len()
+    }
+
+    pub fn part(&self, index: usize) -> Option<&str> {
+        self.parts.get(index).map(String::as_str)
+    }
+}
+
+// ============================================================================
+// DECODER
+// ============================================================================
+
+/// Collects the frames of one BBQr transfer of an expected file type.
+#[derive(Debug)]
+pub struct BbqrDecoder {
+    file_type: BbqrFileType,
+    header: Option<(BbqrEncoding, usize)>,
+    parts: BTreeMap<usize, Vec<u8>>,
+    buffered_bytes: usize,
+    limits: DecoderLimits,
+}
+
+impl BbqrDecoder {
+    pub fn new(file_type: BbqrFileType) -> Self {
+        Self {
+            file_type,
+            header: None,
+            parts: BTreeMap::new(),
+            buffered_bytes: 0,
+            limits: DecoderLimits::default(),
+        }
+    }
+
+    pub fn set_limits(&mut self, limits: DecoderLimits) {
+        self.limits = limits;
+    }
+
+    pub fn file_type(&self) -> BbqrFileType {
+        self.file_type
+    }
+
+    /// Store one frame and return its part index. The part's payload moves
+    /// into the decoder; borrow it back with `part`.
+    pub fn receive(&mut self, text: &str) -> Result<usize, AirgapError> {
+        let frame = BbqrFrame::parse(text)?;
+
+        if frame.file_type != self.file_type {
+            return Err(AirgapError::InvalidBbqr(format!(
+                "expected file type '{}', got '{}'", self.file_type.code(), frame.file_type.code()
+            )));
+        }
+
+        self.limits.check_total_chunks(frame.total)?;
+        match self.header {
+            None => {}
+            Some(header) if header != (frame.encoding, frame.total) => {
+                return Err(AirgapError::MetadataMismatch);
+            }
+            Some(_) => {}
+        }
+
+        let replaced = self.parts.get(&frame.index).map_or(0, Vec::len);
+        let buffered = self.buffered_bytes - replaced + frame.data.len();
+        self.limits.check_payload_bytes(buffered)?;
+
+        self.header = Some((frame.encoding, frame.total));
+        self.buffered_bytes = buffered;
+        self.parts.insert(frame.index, frame.data);
+        Ok(frame.index)
+    }
+
+    /// Payload of part `index` as received (still compressed for `Zlib`).
+    pub fn part(&self, index: usize) -> Option<&[u8]> {
+        self.parts.get(&index).map(Vec::as_slice)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        matches!(self.header, Some((_, total)) if self.parts.len() == total)
+    }
+
+    pub fn received_count(&self) -> usize {
+        self.parts.len()
+    }
+
+    pub fn total_count(&self) -> usize {
+        self.header.map(|(_, total)| total).unwrap_or(0)
+    }
+
+    pub fn get_data(&self) -> Result<Vec<u8>, AirgapError> {
+        let Some((encoding, total)) = self.header.filter(|_| self.is_complete()) else {
+            return Err(AirgapError::EncodingError(
+                format!("Incomplete: {}/{} parts", self.received_count(), self.total_count())
+            ));
+        };
+
+        let mut body = Vec::new();
+        for index in 0..total {
+            let part = self.parts.get(&index)
+                .ok_or(AirgapError::MissingChunk(index as u16))?;
+            body.extend_from_slice(part);
+        }
+
+        match encoding {
+            BbqrEncoding::Zlib => inflate(&body, &self.limits),
+            BbqrEncoding::Hex | BbqrEncoding::Base32 => Ok(body),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.header = None;
+        self.parts.clear();
+        self.buffered_bytes = 0;
+    }
+}
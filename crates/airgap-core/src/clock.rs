@@ -0,0 +1,80 @@
+// src/clock - time source for decoder session timeouts
+
+use alloc::boxed::Box;
+#[cfg(target_has_atomic = "64")]
+use alloc::sync::Arc;
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// Monotonic millisecond time source. Only differences between readings
+/// matter, so the origin is arbitrary.
+pub trait Clock: Send {
+    fn now_ms(&self) -> u64;
+}
+
+/// `Instant`-based time, counted from the first reading in the process.
+/// Not built for wasm32-unknown-unknown, where `Instant::now` panics;
+/// decoders there start on the stopped clock until the bindings install
+/// their own.
+#[cfg(all(feature = "std", not(all(target_arch = "wasm32", target_os = "unknown"))))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[cfg(all(feature = "std", not(all(target_arch = "wasm32", target_os = "unknown"))))]
+impl Clock for SystemClock {
+    fn now_ms(&self) -> u64 {
+        use std::sync::OnceLock;
+        use std::time::Instant;
+
+        static ORIGIN: OnceLock<Instant> = OnceLock::new();
+        ORIGIN.get_or_init(Instant::now).elapsed().as_millis() as u64
+    }
+}
+
+// Without std (or on bare wasm32) there is no portable time source: time
+// stands still, so timeouts never fire until a real clock is installed with
+// `set_clock`.
+#[cfg(not(all(feature = "std", not(all(target_arch = "wasm32", target_os = "unknown")))))]
+struct StoppedClock;
+
+#[cfg(not(all(feature = "std", not(all(target_arch = "wasm32", target_os = "unknown")))))]
+impl Clock for StoppedClock {
+    fn now_ms(&self) -> u64 {
+        0
+    }
+}
+
+/// Clock of a new decoder.
+pub(crate) fn default_clock() -> Box<dyn Clock> {
+    #[cfg(all(feature = "std", not(all(target_arch = "wasm32", target_os = "unknown"))))]
+    return Box::new(SystemClock);
+    #[cfg(not(all(feature = "std", not(all(target_arch = "wasm32", target_os = "unknown")))))]
+    return Box::new(StoppedClock);
+}
+
+/// Clock that only moves when told to, for tests. Clones share the same time.
+#[cfg(target_has_atomic = "64")]
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock(Arc<AtomicU64>);
+
+#[cfg(target_has_atomic = "64")]
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, ms: u64) {
+        self.0.fetch_add(ms, Ordering::Relaxed);
+    }
+
+    pub fn set(&self, ms: u64) {
+        self.0.store(ms, Ordering::Relaxed);
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl Clock for ManualClock {
+    fn now_ms(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
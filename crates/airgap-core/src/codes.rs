@@ -0,0 +1,76 @@
+// src/codes - integer codes shared by the C, JNI and wasm bindings
+
+use core::ffi::c_int;
+#[cfg(not(cbindgen))]
+use crate::{BbqrFileType, DecoderStatus, DetectedFormat, FrameFormat, UrType};
+
+pub const AIRGAP_FORMAT_AIRGAP: c_int = 0;
+pub const AIRGAP_FORMAT_UR_BYTES: c_int = 1;
+pub const AIRGAP_FORMAT_UR_CRYPTO_PSBT: c_int = 2;
+pub const AIRGAP_FORMAT_BBQR_PSBT: c_int = 3;
+pub const AIRGAP_FORMAT_BBQR_TRANSACTION: c_int = 4;
+pub const AIRGAP_FORMAT_BBQR_JSON: c_int = 5;
+pub const AIRGAP_FORMAT_BBQR_CBOR: c_int = 6;
+pub const AIRGAP_FORMAT_BBQR_UNICODE_TEXT: c_int = 7;
+pub const AIRGAP_FORMAT_BBQR_BINARY: c_int = 8;
+pub const AIRGAP_FORMAT_BBQR_EXECUTABLE: c_int = 9;
+/// Plain single-QR text; only reported by the auto-detecting decoder.
+pub const AIRGAP_FORMAT_RAW: c_int = 10;
+/// No frame accepted yet.
+pub const AIRGAP_FORMAT_NONE: c_int = -1;
+
+#[cfg(not(cbindgen))]
+pub fn frame_format_from_code(code: c_int) -> Option<FrameFormat> {
+    match code {
+        AIRGAP_FORMAT_AIRGAP => Some(FrameFormat::Airgap),
+        AIRGAP_FORMAT_UR_BYTES => Some(FrameFormat::Ur(UrType::Bytes)),
+        AIRGAP_FORMAT_UR_CRYPTO_PSBT => Some(FrameFormat::Ur(UrType::CryptoPsbt)),
+        AIRGAP_FORMAT_BBQR_PSBT => Some(FrameFormat::Bbqr(BbqrFileType::Psbt)),
+        AIRGAP_FORMAT_BBQR_TRANSACTION => Some(FrameFormat::Bbqr(BbqrFileType::Transaction)),
+        AIRGAP_FORMAT_BBQR_JSON => Some(FrameFormat::Bbqr(BbqrFileType::Json)),
+        AIRGAP_FORMAT_BBQR_CBOR => Some(FrameFormat::Bbqr(BbqrFileType::Cbor)),
+        AIRGAP_FORMAT_BBQR_UNICODE_TEXT => Some(FrameFormat::Bbqr(BbqrFileType::UnicodeText)),
+        AIRGAP_FORMAT_BBQR_BINARY => Some(FrameFormat::Bbqr(BbqrFileType::Binary)),
+        AIRGAP_FORMAT_BBQR_EXECUTABLE => Some(FrameFormat::Bbqr(BbqrFileType::Executable)),
+        _ => None,
+    }
+}
+
+#[cfg(not(cbindgen))]
+pub fn detected_format_to_code(format: Option<DetectedFormat>) -> c_int {
+    let format = match format {
+        None => return AIRGAP_FORMAT_NONE,
+        Some(DetectedFormat::Raw) => return AIRGAP_FORMAT_RAW,
+        Some(DetectedFormat::Frames(format)) => format,
+    };
+    match format {
+        FrameFormat::Airgap => AIRGAP_FORMAT_AIRGAP,
+        FrameFormat::Ur(UrType::Bytes) => AIRGAP_FORMAT_UR_BYTES,
+        FrameFormat::Ur(UrType::CryptoPsbt) => AIRGAP_FORMAT_UR_CRYPTO_PSBT,
+        FrameFormat::Bbqr(BbqrFileType::Psbt) => AIRGAP_FORMAT_BBQR_PSBT,
+        FrameFormat::Bbqr(BbqrFileType::Transaction) => AIRGAP_FORMAT_BBQR_TRANSACTION,
+        FrameFormat::Bbqr(BbqrFileType::Json) => AIRGAP_FORMAT_BBQR_JSON,
+        FrameFormat::Bbqr(BbqrFileType::Cbor) => AIRGAP_FORMAT_BBQR_CBOR,
+        FrameFormat::Bbqr(BbqrFileType::UnicodeText) => AIRGAP_FORMAT_BBQR_UNICODE_TEXT,
+        FrameFormat::Bbqr(BbqrFileType::Binary) => AIRGAP_FORMAT_BBQR_BINARY,
+        FrameFormat::Bbqr(BbqrFileType::Executable) => AIRGAP_FORMAT_BBQR_EXECUTABLE,
+    }
+}
+
+/// Decoder status codes returned by `airgap_decoder_get_status`.
+pub const AIRGAP_STATUS_IDLE: c_int = 0;
+pub const AIRGAP_STATUS_RECEIVING: c_int = 1;
+pub const AIRGAP_STATUS_STALLED: c_int = 2;
+pub const AIRGAP_STATUS_COMPLETE: c_int = 3;
+pub const AIRGAP_STATUS_EXPIRED: c_int = 4;
+
+#[cfg(not(cbindgen))]
+pub fn decoder_status_to_code(status: DecoderStatus) -> c_int {
+    match status {
+        DecoderStatus::Idle => AIRGAP_STATUS_IDLE,
+        DecoderStatus::Receiving => AIRGAP_STATUS_RECEIVING,
+        DecoderStatus::Stalled => AIRGAP_STATUS_STALLED,
+        DecoderStatus::Complete => AIRGAP_STATUS_COMPLETE,
+        DecoderStatus::Expired => AIRGAP_STATUS_EXPIRED,
+    }
+}
@@ -0,0 +1,597 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use crate::protocol::*;
+use crate::ur::UrDecoder;
+use crate::bbqr::BbqrDecoder;
+use crate::error::AirgapError;
+use crate::observer::{DecoderEvent, DecoderObserver};
+use crate::clock::{default_clock, Clock};
+use crate::limits::DecoderLimits;
+
+// Reassembly state for the non-native formats; native chunks live on `Decoder`
+enum Assembler {
+    Airgap,
+    Ur(UrDecoder),
+    Bbqr(BbqrDecoder),
+}
+
+/// Where a decoding session stands, see `Decoder::status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DecoderStatus {
+    /// No frame accepted yet.
+    Idle,
+    /// A session is in progress.
+    Receiving,
+    /// No new chunk within the idle timeout; the session resumes if one arrives.
+    Stalled,
+    /// All chunks received.
+    Complete,
+    /// The session outlived the total timeout and accepts no more frames.
+    Expired,
+}
+
+/// Session time limits, in milliseconds of the decoder's clock. The default
+/// disables both limits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecoderTimeouts {
+    /// Report `Stalled` after this long without a new chunk; 0 disables.
+    pub idle_ms: u64,
+    /// Report `Expired` this long after a session's first frame; 0 disables.
+    pub total_ms: u64,
+    /// Drop stale sessions as frames arrive: an expired session is reset
+    /// before the frame is processed, and a stalled one gives way to a frame
+    /// from another session instead of failing with a mismatch.
+    pub auto_expire: bool,
+}
+
+impl DecoderTimeouts {
+    pub fn is_enabled(&self) -> bool {
+        self.idle_ms > 0 || self.total_ms > 0
+    }
+}
+
+/// Progress and settings of a decoder at one point in time, see
+/// `Decoder::snapshot`. Native snapshots carry the received payloads and can
+/// be restored with `Decoder::from_snapshot`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecoderSnapshot {
+    pub format: FrameFormat,
+    pub status: DecoderStatus,
+    pub session_id: Option<u32>,
+    pub received: usize,
+    pub total: usize,
+    /// Native chunk payloads by index; empty for UR and BBQr, whose
+    /// reassembly state is not captured.
+    pub chunks: BTreeMap<u16, Vec<u8>>,
+    pub timeouts: DecoderTimeouts,
+    pub limits: DecoderLimits,
+}
+
+// Position of a frame `decode_frame` accepted; see `Decoder::chunk_ref`
+#[derive(Debug, Clone, Copy)]
+struct Accepted {
+    total_chunks: u16,
+    chunk_index: u16,
+    session_id: u32,
+}
+
+// When the current session started and last made progress
+#[derive(Debug, Clone, Copy)]
+struct SessionTimes {
+    started_ms: u64,
+    progress_ms: u64,
+}
+
+pub struct Decoder {
+    received_chunks: BTreeMap<u16, Vec<u8>>,
+    session_id: Option<u32>,
+    total_chunks: Option<u16>,
+    assembler: Assembler,
+    observer: Option<Box<dyn DecoderObserver>>,
+    timeouts: DecoderTimeouts,
+    clock: Box<dyn Clock>,
+    times: Option<SessionTimes>,
+    limits: DecoderLimits,
+    // Native chunk bytes held in `received_chunks`
+    buffered_bytes: usize,
+    sessions_started: usize,
+    // Payload of the last UR frame; BBQr parts are borrowed from the assembler
+    frame_data: Vec<u8>,
+}
+
+impl Decoder {
+    pub fn new() -> Self {
+        Self::with_format(FrameFormat::Airgap)
+    }
+
+    /// Create a decoder accepting frames in `format`.
+    pub fn with_format(format: FrameFormat) -> Self {
+        Self {
+            received_chunks: BTreeMap::new(),
+            session_id: None,
+            total_chunks: None,
+            assembler: match format {
+                FrameFormat::Airgap => Assembler::Airgap,
+                FrameFormat::Ur(ur_type) => Assembler::Ur(UrDecoder::new(ur_type)),
+                FrameFormat::Bbqr(file_type) => Assembler::Bbqr(BbqrDecoder::new(file_type)),
+            },
+            observer: None,
+            timeouts: DecoderTimeouts::default(),
+            clock: default_clock(),
+            times: None,
+            limits: DecoderLimits::default(),
+            buffered_bytes: 0,
+            sessions_started: 0,
+            frame_data: Vec::new(),
+        }
+    }
+
+    /// Enforce `limits` on every frame from now on. Sessions started so far
+    /// count towards `max_sessions`.
+    pub fn set_limits(&mut self, limits: DecoderLimits) {
+        self.limits = limits;
+        match &mut self.assembler {
+            Assembler::Airgap => {}
+            Assembler::Ur(ur) => ur.set_limits(limits),
+            Assembler::Bbqr(bbqr) => bbqr.set_limits(limits),
+        }
+    }
+
+    pub fn limits(&self) -> DecoderLimits {
+        self.limits
+    }
+
+    /// Notify `observer` of every event from now on, replacing any previous one.
+    pub fn set_observer(&mut self, observer: Box<dyn DecoderObserver>) {
+        self.observer = Some(observer);
+    }
+
+    pub fn clear_observer(&mut self) {
+        self.observer = None;
+    }
+
+    /// Apply `timeouts` from now on. A session already in progress is timed
+    /// from this call if it was not being timed before.
+    pub fn set_timeouts(&mut self, timeouts: DecoderTimeouts) {
+        self.timeouts = timeouts;
+        if !timeouts.is_enabled() {
+            self.times = None;
+        } else if self.times.is_none() && self.session_id().is_some() {
+            let now = self.clock.now_ms();
+            self.times = Some(SessionTimes { started_ms: now, progress_ms: now });
+        }
+    }
+
+    pub fn timeouts(&self) -> DecoderTimeouts {
+        self.timeouts
+    }
+
+    /// Replace the time source used for timeouts, e.g. with a `ManualClock`
+    /// in tests. Timestamps of a session in progress are kept as they are.
+    /// Without `std` the default clock never advances, so timeouts need a
+    /// clock from the platform.
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// The clock is only read while timeouts are enabled.
+    pub fn status(&self) -> DecoderStatus {
+        let now = match self.timeouts.is_enabled() {
+            true => self.clock.now_ms(),
+            false => 0,
+        };
+        self.status_at(now)
+    }
+
+    /// Reset the session if it is stalled or expired; returns whether it did.
+    pub fn expire_stale(&mut self) -> bool {
+        let stale = matches!(self.status(), DecoderStatus::Stalled | DecoderStatus::Expired);
+        if stale {
+            self.clear_session();
+        }
+        stale
+    }
+
+    fn status_at(&self, now_ms: u64) -> DecoderStatus {
+        if self.is_complete() {
+            return DecoderStatus::Complete;
+        }
+        if self.session_id().is_none() {
+            return DecoderStatus::Idle;
+        }
+        let Some(times) = self.times else {
+            return DecoderStatus::Receiving;
+        };
+        let DecoderTimeouts { idle_ms, total_ms, .. } = self.timeouts;
+        if total_ms > 0 && now_ms.saturating_sub(times.started_ms) >= total_ms {
+            DecoderStatus::Expired
+        } else if idle_ms > 0 && now_ms.saturating_sub(times.progress_ms) >= idle_ms {
+            DecoderStatus::Stalled
+        } else {
+            DecoderStatus::Receiving
+        }
+    }
+
+    /// Capture the session so far, e.g. for logging or to hand it to
+    /// another process.
+    pub fn snapshot(&self) -> DecoderSnapshot {
+        DecoderSnapshot {
+            format: self.format(),
+            status: self.status(),
+            session_id: self.session_id(),
+            received: self.received_count(),
+            total: self.total_count(),
+            chunks: self.received_chunks.clone(),
+            timeouts: self.timeouts,
+            limits: self.limits,
+        }
+    }
+
+    /// Rebuild a decoder from a snapshot of a native session, checking the
+    /// chunks against the snapshot's limits. Session timing restarts from
+    /// this call, with the default clock. UR and BBQr snapshots can only be
+    /// restored before any frame was received.
+    pub fn from_snapshot(snapshot: DecoderSnapshot) -> Result<Self, AirgapError> {
+        let mut decoder = Self::with_format(snapshot.format);
+        decoder.set_limits(snapshot.limits);
+        decoder.timeouts = snapshot.timeouts;
+
+        if snapshot.format != FrameFormat::Airgap {
+            if snapshot.received > 0 {
+                return Err(AirgapError::InvalidConfig(
+                    "only native snapshots can carry received chunks".into()
+                ));
+            }
+            return Ok(decoder);
+        }
+
+        let Some(session_id) = snapshot.session_id else {
+            if !snapshot.chunks.is_empty() {
+                return Err(AirgapError::InvalidConfig("chunks without a session".into()));
+            }
+            return Ok(decoder);
+        };
+        // A live session always knows its chunk count; 0 would read as complete
+        let total_chunks = match u16::try_from(snapshot.total) {
+            Ok(0) | Err(_) => return Err(AirgapError::TooManyChunks(snapshot.total)),
+            Ok(total) => total,
+        };
+        decoder.limits.check_total_chunks(snapshot.total)?;
+
+        let mut buffered = 0;
+        for (&index, data) in &snapshot.chunks {
+            if index >= total_chunks {
+                return Err(AirgapError::ChunkOutOfBounds(index));
+            }
+            if data.len() > MAX_CHUNK_SIZE {
+                return Err(AirgapError::ChunkSizeTooLarge(data.len(), MAX_CHUNK_SIZE));
+            }
+            buffered += data.len();
+        }
+        decoder.limits.check_payload_bytes(buffered)?;
+
+        decoder.session_id = Some(session_id);
+        decoder.total_chunks = Some(total_chunks);
+        decoder.received_chunks = snapshot.chunks;
+        decoder.buffered_bytes = buffered;
+        decoder.sessions_started = 1;
+        if decoder.timeouts.is_enabled() {
+            let now = decoder.clock.now_ms();
+            decoder.times = Some(SessionTimes { started_ms: now, progress_ms: now });
+        }
+        Ok(decoder)
+    }
+
+    pub fn format(&self) -> FrameFormat {
+        match &self.assembler {
+            Assembler::Airgap => FrameFormat::Airgap,
+            Assembler::Ur(ur) => FrameFormat::Ur(ur.ur_type()),
+            Assembler::Bbqr(bbqr) => FrameFormat::Bbqr(bbqr.file_type()),
+        }
+    }
+
+    /// Process a scanned QR code string
+    ///
+    /// For UR frames the returned chunk describes the scanned part: its index
+    /// is the sequence number minus one (fountain parts land past
+    /// `total_chunks`) and its session id is the message checksum. BBQr frames
+    /// carry no session id, so it is reported as 0.
+    ///
+    /// With timeouts enabled, frames for an expired session fail with
+    /// `SessionExpired` unless `auto_expire` is set.
+    pub fn process_qr_string(&mut self, qr_data: &str) -> Result<Chunk, AirgapError> {
+        self.process_qr_string_ref(qr_data).map(|chunk| chunk.to_chunk())
+    }
+
+    /// Like `process_qr_string`, but the returned chunk borrows its payload
+    /// from the decoder instead of copying it. A native payload is moved
+    /// once, within the buffer its frame was decoded into, and that buffer
+    /// is kept as the chunk's storage; a BBQr part is stored as decoded.
+    /// UR parts are copied once, since the fountain decoder XORs the parts
+    /// it keeps and the scanned fragment has to survive for the chunk.
+    pub fn process_qr_string_ref(&mut self, qr_data: &str) -> Result<ChunkRef<'_>, AirgapError> {
+        let accepted = self.process_timed(qr_data)?;
+        Ok(self.chunk_ref(accepted))
+    }
+
+    fn process_timed(&mut self, qr_data: &str) -> Result<Accepted, AirgapError> {
+        if !self.timeouts.is_enabled() {
+            return self.observe_frame(qr_data);
+        }
+
+        let now = self.clock.now_ms();
+        let status = self.status_at(now);
+        if status == DecoderStatus::Expired {
+            if !self.timeouts.auto_expire {
+                let error = AirgapError::SessionExpired;
+                Self::notify(&mut self.observer, &[DecoderEvent::Error(&error)]);
+                return Err(error);
+            }
+            self.clear_session();
+        }
+
+        let received = self.received_count();
+        let mut result = self.observe_frame(qr_data);
+        if status == DecoderStatus::Stalled
+            && self.timeouts.auto_expire
+            && matches!(result, Err(AirgapError::SessionMismatch | AirgapError::MetadataMismatch))
+        {
+            self.clear_session();
+            result = self.observe_frame(qr_data);
+        }
+
+        if result.is_ok() {
+            let progressed = self.received_count() > received;
+            match self.times.as_mut() {
+                Some(times) if progressed => times.progress_ms = now,
+                Some(_) => {}
+                None => self.times = Some(SessionTimes { started_ms: now, progress_ms: now }),
+            }
+        }
+        result
+    }
+
+    // Process a frame, reporting what happened to the observer if there is one
+    fn observe_frame(&mut self, qr_data: &str) -> Result<Accepted, AirgapError> {
+        if self.observer.is_none() {
+            return self.process_frame(qr_data);
+        }
+
+        let started = self.session_id().is_some();
+        let received = self.received_count();
+        let complete = self.is_complete();
+
+        let result = self.process_frame(qr_data);
+
+        // Events borrow the stored payload, so the observer is moved out while notified
+        let mut observer = self.observer.take();
+        let mut events = Vec::new();
+        match &result {
+            Ok(accepted) => {
+                let chunk = self.chunk_ref(*accepted);
+                if !started && let Some(session_id) = self.session_id() {
+                    events.push(DecoderEvent::SessionStarted { session_id, total_chunks: self.total_count() });
+                }
+                if self.received_count() > received {
+                    events.push(DecoderEvent::ChunkReceived {
+                        chunk,
+                        received: self.received_count(),
+                        total: self.total_count(),
+                    });
+                } else {
+                    events.push(DecoderEvent::Duplicate { chunk });
+                }
+                if !complete && self.is_complete() {
+                    events.push(DecoderEvent::Completed);
+                }
+            }
+            Err(error @ (AirgapError::SessionMismatch | AirgapError::MetadataMismatch)) => {
+                events.push(DecoderEvent::Conflict(error));
+            }
+            Err(error) => events.push(DecoderEvent::Error(error)),
+        }
+
+        Self::notify(&mut observer, &events);
+        self.observer = observer;
+        result
+    }
+
+    fn notify(observer: &mut Option<Box<dyn DecoderObserver>>, events: &[DecoderEvent]) {
+        if let Some(observer) = observer.as_mut() {
+            for event in events {
+                observer.on_event(event);
+            }
+        }
+    }
+
+    // Apply the limits around `decode_frame`, counting new sessions
+    fn process_frame(&mut self, qr_data: &str) -> Result<Accepted, AirgapError> {
+        self.limits.check_frame_len(qr_data.len())?;
+        let new_session = self.session_id().is_none();
+        if new_session {
+            self.limits.check_sessions(self.sessions_started)?;
+        }
+
+        let accepted = self.decode_frame(qr_data)?;
+        if new_session && self.session_id().is_some() {
+            self.sessions_started += 1;
+        }
+        Ok(accepted)
+    }
+
+    fn decode_frame(&mut self, qr_data: &str) -> Result<Accepted, AirgapError> {
+        match &mut self.assembler {
+            Assembler::Airgap => {}
+            Assembler::Ur(ur) => {
+                let frame = ur.receive(qr_data)?;
+                self.frame_data = frame.data;
+                return Ok(Accepted {
+                    total_chunks: frame.seq_len as u16,
+                    chunk_index: u16::try_from(frame.seq_num - 1).unwrap_or(u16::MAX),
+                    session_id: frame.checksum,
+                });
+            }
+            Assembler::Bbqr(bbqr) => {
+                let index = bbqr.receive(qr_data)?;
+                return Ok(Accepted {
+                    total_chunks: bbqr.total_count() as u16,
+                    chunk_index: index as u16,
+                    session_id: 0,
+                });
+            }
+        }
+
+        // Decode Base45
+        let mut bytes = base45::decode(qr_data).map_err(|e| {
+            AirgapError::EncodingError(e.to_string())
+        })?;
+
+        // Parse chunk in place
+        let chunk = ChunkRef::from_bytes(&bytes)?;
+        let accepted = Accepted {
+            total_chunks: chunk.total_chunks,
+            chunk_index: chunk.chunk_index,
+            session_id: chunk.session_id,
+        };
+        let data_len = chunk.data.len();
+        self.limits.check_total_chunks(accepted.total_chunks as usize)?;
+
+        if self.session_id.is_some() {
+            if self.total_chunks != Some(accepted.total_chunks) {
+                return Err(AirgapError::MetadataMismatch)
+            }
+
+            if Some(accepted.session_id) != self.session_id {
+                return Err(AirgapError::SessionMismatch);
+            }
+        }
+
+        let replaced = self.received_chunks.get(&accepted.chunk_index).map_or(0, Vec::len);
+        let buffered = self.buffered_bytes - replaced + data_len;
+        self.limits.check_payload_bytes(buffered)?;
+
+        // Initialize session on first chunk
+        self.session_id = Some(accepted.session_id);
+        self.total_chunks = Some(accepted.total_chunks);
+
+        // Store chunk data: drop the CRC and shift the payload over the header
+        bytes.truncate(HEADER_SIZE + data_len);
+        bytes.drain(..HEADER_SIZE);
+        self.buffered_bytes = buffered;
+        self.received_chunks.insert(accepted.chunk_index, bytes);
+
+        Ok(accepted)
+    }
+
+    // The chunk accepted by the last `decode_frame`, with its stored payload
+    fn chunk_ref(&self, accepted: Accepted) -> ChunkRef<'_> {
+        let data = match &self.assembler {
+            Assembler::Airgap => &self.received_chunks[&accepted.chunk_index],
+            Assembler::Ur(_) => &self.frame_data,
+            Assembler::Bbqr(bbqr) => bbqr.part(accepted.chunk_index as usize).unwrap_or_default(),
+        };
+        ChunkRef {
+            total_chunks: accepted.total_chunks,
+            chunk_index: accepted.chunk_index,
+            session_id: accepted.session_id,
+            data,
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        match &self.assembler {
+            Assembler::Ur(ur) => return ur.is_complete(),
+            Assembler::Bbqr(bbqr) => return bbqr.is_complete(),
+            Assembler::Airgap => {}
+        }
+        match self.total_chunks {
+            Some(total) => self.received_chunks.len() == total as usize,
+            None => false,
+        }
+    }
+
+    pub fn session_id(&self) -> Option<u32> {
+        match &self.assembler {
+            Assembler::Airgap => self.session_id,
+            Assembler::Ur(ur) => ur.checksum(),
+            Assembler::Bbqr(bbqr) => (bbqr.total_count() > 0).then_some(0),
+        }
+    }
+
+    pub fn received_count(&self) -> usize {
+        match &self.assembler {
+            Assembler::Airgap => self.received_chunks.len(),
+            Assembler::Ur(ur) => ur.recovered_count(),
+            Assembler::Bbqr(bbqr) => bbqr.received_count(),
+        }
+    }
+
+    pub fn total_count(&self) -> usize {
+        match &self.assembler {
+            Assembler::Airgap => self.total_chunks.unwrap_or(0) as usize,
+            Assembler::Ur(ur) => ur.seq_len(),
+            Assembler::Bbqr(bbqr) => bbqr.total_count(),
+        }
+    }
+
+    /// Get reassembled data
+    pub fn get_data(&self) -> Result<Vec<u8>, AirgapError> {
+        match &self.assembler {
+            Assembler::Ur(ur) => return ur.get_data(),
+            Assembler::Bbqr(bbqr) => return bbqr.get_data(),
+            Assembler::Airgap => {}
+        }
+
+        if !self.is_complete() {
+            return Err(AirgapError::EncodingError(
+                format!("Incomplete: {}/{} chunks", self.received_count(), self.total_count())
+            ));
+        }
+
+        let total_chunks = self.total_chunks.unwrap();
+
+        // Reassemble in order
+        let mut result = Vec::new();
+        for i in 0..total_chunks {
+            let chunk_data = self.received_chunks.get(&i)
+                .ok_or(AirgapError::MissingChunk(i))?;
+            result.extend_from_slice(chunk_data);
+        }
+
+        Ok(result)
+    }
+    /// Drop the current session and start counting sessions against
+    /// `max_sessions` from zero again.
+    pub fn reset(&mut self) {
+        self.clear_session();
+        self.sessions_started = 0;
+    }
+
+    // Drop the current session; expiry does this without touching the
+    // session count, so a stream of stalled sessions still hits the limit
+    fn clear_session(&mut self) {
+        self.received_chunks.clear();
+        self.session_id = None;
+        self.total_chunks = None;
+        self.times = None;
+        self.buffered_bytes = 0;
+        self.frame_data.clear();
+        match &mut self.assembler {
+            Assembler::Airgap => {}
+            Assembler::Ur(ur) => ur.reset(),
+            Assembler::Bbqr(bbqr) => bbqr.reset(),
+        }
+    }
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
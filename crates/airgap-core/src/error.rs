@@ -0,0 +1,225 @@
+#[cfg(not(cbindgen))]
+use alloc::string::String;
+
+#[cfg(not(cbindgen))]
+#[derive(Debug, thiserror::Error)]
+pub enum AirgapError {
+    #[error("Unknown error")]
+    UnknownError,
+    #[error("Invalid magic bytes")]
+    InvalidMagic,
+    #[error("Unsupported version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("CRC mismatch")]
+    CrcMismatch,
+    #[error("Session ID mismatch")]
+    SessionMismatch,
+    #[error("Metadata mismatch")]
+    MetadataMismatch,
+    #[error("Chunk index {0} out of bounds")]
+    ChunkOutOfBounds(u16),
+    #[error("Too many chunks: {0} (max 65535)")]
+    TooManyChunks(usize),
+    #[error("Chunk size {0} exceeds maximum {1}")]
+    ChunkSizeTooLarge(usize, usize),
+    #[error("Chunk size {0} below minimum {1}")]
+    ChunkSizeTooSmall(usize, usize),
+    #[error("Missing chunk {0}")]
+    MissingChunk(u16),
+    #[error("Encoding error: {0}")]
+    EncodingError(String),
+    #[error("Empty data for encoder")]
+    EmptyData,
+    #[error("Invalid UR: {0}")]
+    InvalidUr(String),
+    #[error("Invalid BBQr: {0}")]
+    InvalidBbqr(String),
+    #[error("Invalid QR config: {0}")]
+    InvalidConfig(String),
+    #[error("Operation cancelled")]
+    Cancelled,
+    #[error("Operation timed out")]
+    TimedOut,
+    #[error("Decoding session expired")]
+    SessionExpired,
+    #[error("Frame length {0} exceeds limit {1}")]
+    FrameTooLong(usize, usize),
+    #[error("Frame announces {0} chunks, limit is {1}")]
+    ChunkLimitExceeded(usize, usize),
+    #[error("Payload exceeds limit of {0} bytes")]
+    PayloadTooLarge(usize),
+    #[error("Session limit of {0} reached")]
+    SessionLimitReached(usize),
+    #[error("Internal panic: {0}")]
+    Panic(String),
+    #[error("Null pointer: {0}")]
+    NullPointer(&'static str),
+    #[error("Invalid UTF-8 string")]
+    InvalidUtf8,
+    #[error("Unknown {0} code {1}")]
+    UnknownCode(&'static str, i32),
+    #[error("Binding error: {0}")]
+    BindingError(String),
+}
+
+pub const AIRGAP_UNKNOWN_ERR: i32 = -10;
+pub const AIRGAP_ERR_INVALID_MAGIC: i32 = -11;
+pub const AIRGAP_ERR_UNSUPPORTED_VERSION: i32 = -12;
+pub const AIRGAP_ERR_CRC_MISMATCH: i32 = -13;
+pub const AIRGAP_ERR_SESSION_MISMATCH: i32 = -14;
+pub const AIRGAP_ERR_METADATA_MISMATCH: i32 = -15;
+pub const AIRGAP_ERR_CHUNK_OUT_OF_BOUNDS: i32 = -16;
+pub const AIRGAP_ERR_TOO_MANY_CHUNKS: i32 = -17;
+pub const AIRGAP_ERR_CHUNK_SIZE_TOO_LARGE: i32 = -18;
+pub const AIRGAP_ERR_CHUNK_SIZE_TOO_SMALL: i32 = -19;
+pub const AIRGAP_ERR_MISSING_CHUNK: i32 = -20;
+pub const AIRGAP_ERR_ENCODING: i32 = -21;
+pub const AIRGAP_ERR_EMPTY_DATA: i32 = -22;
+pub const AIRGAP_ERR_INVALID_UR: i32 = -23;
+pub const AIRGAP_ERR_INVALID_BBQR: i32 = -24;
+pub const AIRGAP_ERR_INVALID_CONFIG: i32 = -25;
+pub const AIRGAP_ERR_CANCELLED: i32 = -26;
+pub const AIRGAP_ERR_TIMED_OUT: i32 = -27;
+pub const AIRGAP_ERR_SESSION_EXPIRED: i32 = -28;
+pub const AIRGAP_ERR_FRAME_TOO_LONG: i32 = -29;
+pub const AIRGAP_ERR_CHUNK_LIMIT: i32 = -30;
+pub const AIRGAP_ERR_PAYLOAD_TOO_LARGE: i32 = -31;
+pub const AIRGAP_ERR_SESSION_LIMIT: i32 = -32;
+/// A binding caught a panic instead of letting it unwind into the host.
+pub const AIRGAP_ERR_PANIC: i32 = -33;
+/// A handle or argument was null.
+pub const AIRGAP_ERR_NULL_POINTER: i32 = -34;
+/// A string from the host was not valid UTF-8.
+pub const AIRGAP_ERR_INVALID_UTF8: i32 = -35;
+/// A format, pixel format or EC level code is not one this library knows.
+pub const AIRGAP_ERR_UNKNOWN_CODE: i32 = -36;
+/// The host runtime failed, e.g. a JVM or JS object could not be created.
+pub const AIRGAP_ERR_BINDING: i32 = -37;
+
+/// Every `AIRGAP_ERR_*` code with the kind name the bindings report for it.
+#[cfg(not(cbindgen))]
+pub const ERROR_KINDS: &[(i32, &str)] = &[
+    (AIRGAP_UNKNOWN_ERR, "UnknownError"),
+    (AIRGAP_ERR_INVALID_MAGIC, "InvalidMagic"),
+    (AIRGAP_ERR_UNSUPPORTED_VERSION, "UnsupportedVersion"),
+    (AIRGAP_ERR_CRC_MISMATCH, "CrcMismatch"),
+    (AIRGAP_ERR_SESSION_MISMATCH, "SessionMismatch"),
+    (AIRGAP_ERR_METADATA_MISMATCH, "MetadataMismatch"),
+    (AIRGAP_ERR_CHUNK_OUT_OF_BOUNDS, "ChunkOutOfBounds"),
+    (AIRGAP_ERR_TOO_MANY_CHUNKS, "TooManyChunks"),
+    (AIRGAP_ERR_CHUNK_SIZE_TOO_LARGE, "ChunkSizeTooLarge"),
+    (AIRGAP_ERR_CHUNK_SIZE_TOO_SMALL, "ChunkSizeTooSmall"),
+    (AIRGAP_ERR_MISSING_CHUNK, "MissingChunk"),
+    (AIRGAP_ERR_ENCODING, "EncodingError"),
+    (AIRGAP_ERR_EMPTY_DATA, "EmptyData"),
+    (AIRGAP_ERR_INVALID_UR, "InvalidUr"),
+    (AIRGAP_ERR_INVALID_BBQR, "InvalidBbqr"),
+    (AIRGAP_ERR_INVALID_CONFIG, "InvalidConfig"),
+    (AIRGAP_ERR_CANCELLED, "Cancelled"),
+    (AIRGAP_ERR_TIMED_OUT, "TimedOut"),
+    (AIRGAP_ERR_SESSION_EXPIRED, "SessionExpired"),
+    (AIRGAP_ERR_FRAME_TOO_LONG, "FrameTooLong"),
+    (AIRGAP_ERR_CHUNK_LIMIT, "ChunkLimitExceeded"),
+    (AIRGAP_ERR_PAYLOAD_TOO_LARGE, "PayloadTooLarge"),
+    (AIRGAP_ERR_SESSION_LIMIT, "SessionLimitReached"),
+    (AIRGAP_ERR_PANIC, "Panic"),
+    (AIRGAP_ERR_NULL_POINTER, "NullPointer"),
+    (AIRGAP_ERR_INVALID_UTF8, "InvalidUtf8"),
+    (AIRGAP_ERR_UNKNOWN_CODE, "UnknownCode"),
+    (AIRGAP_ERR_BINDING, "BindingError"),
+];
+
+/// The kind name of an `AIRGAP_ERR_*` code, or `None` for an unknown code.
+#[cfg(not(cbindgen))]
+pub fn error_kind(code: i32) -> Option<&'static str> {
+    ERROR_KINDS.iter().find(|(known, _)| *known == code).map(|(_, kind)| *kind)
+}
+
+#[cfg(not(cbindgen))]
+impl AirgapError {
+    /// The `AIRGAP_ERR_*` code reported for this error by the bindings.
+    pub fn to_code(&self) -> i32 {
+        match self {
+            AirgapError::UnknownError => AIRGAP_UNKNOWN_ERR,
+            AirgapError::InvalidMagic => AIRGAP_ERR_INVALID_MAGIC,
+            AirgapError::UnsupportedVersion(_) => AIRGAP_ERR_UNSUPPORTED_VERSION,
+            AirgapError::CrcMismatch => AIRGAP_ERR_CRC_MISMATCH,
+            AirgapError::MetadataMismatch => AIRGAP_ERR_METADATA_MISMATCH,
+            AirgapError::SessionMismatch => AIRGAP_ERR_SESSION_MISMATCH,
+            AirgapError::ChunkOutOfBounds(_) => AIRGAP_ERR_CHUNK_OUT_OF_BOUNDS,
+            AirgapError::TooManyChunks(_) => AIRGAP_ERR_TOO_MANY_CHUNKS,
+            AirgapError::ChunkSizeTooLarge(_, _) => AIRGAP_ERR_CHUNK_SIZE_TOO_LARGE,
+            AirgapError::ChunkSizeTooSmall(_, _) => AIRGAP_ERR_CHUNK_SIZE_TOO_SMALL,
+            AirgapError::MissingChunk(_) => AIRGAP_ERR_MISSING_CHUNK,
+            AirgapError::EncodingError(_) => AIRGAP_ERR_ENCODING,
+            AirgapError::EmptyData => AIRGAP_ERR_EMPTY_DATA,
+            AirgapError::InvalidUr(_) => AIRGAP_ERR_INVALID_UR,
+            AirgapError::InvalidBbqr(_) => AIRGAP_ERR_INVALID_BBQR,
+            AirgapError::InvalidConfig(_) => AIRGAP_ERR_INVALID_CONFIG,
+            AirgapError::Cancelled => AIRGAP_ERR_CANCELLED,
+            AirgapError::TimedOut => AIRGAP_ERR_TIMED_OUT,
+            AirgapError::SessionExpired => AIRGAP_ERR_SESSION_EXPIRED,
+            AirgapError::FrameTooLong(_, _) => AIRGAP_ERR_FRAME_TOO_LONG,
+            AirgapError::ChunkLimitExceeded(_, _) => AIRGAP_ERR_CHUNK_LIMIT,
+            AirgapError::PayloadTooLarge(_) => AIRGAP_ERR_PAYLOAD_TOO_LARGE,
+            AirgapError::SessionLimitReached(_) => AIRGAP_ERR_SESSION_LIMIT,
+            AirgapError::Panic(_) => AIRGAP_ERR_PANIC,
+            AirgapError::NullPointer(_) => AIRGAP_ERR_NULL_POINTER,
+            AirgapError::InvalidUtf8 => AIRGAP_ERR_INVALID_UTF8,
+            AirgapError::UnknownCode(_, _) => AIRGAP_ERR_UNKNOWN_CODE,
+            AirgapError::BindingError(_) => AIRGAP_ERR_BINDING,
+        }
+    }
+
+    /// The kind name from `ERROR_KINDS`, e.g. `"CrcMismatch"`.
+    pub fn kind(&self) -> &'static str {
+        error_kind(self.to_code()).unwrap_or("UnknownError")
+    }
+
+    /// The chunk an error is about, for `ChunkOutOfBounds` and `MissingChunk`.
+    pub fn chunk_index(&self) -> Option<u16> {
+        match self {
+            AirgapError::ChunkOutOfBounds(index) | AirgapError::MissingChunk(index) => Some(*index),
+            _ => None,
+        }
+    }
+
+    /// The offending size, count or version for errors that carry one.
+    pub fn value(&self) -> Option<usize> {
+        match self {
+            AirgapError::UnsupportedVersion(version) => Some(*version as usize),
+            AirgapError::TooManyChunks(value)
+            | AirgapError::ChunkSizeTooLarge(value, _)
+            | AirgapError::ChunkSizeTooSmall(value, _)
+            | AirgapError::FrameTooLong(value, _)
+            | AirgapError::ChunkLimitExceeded(value, _) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The limit that was exceeded (or, for `ChunkSizeTooSmall`, not reached).
+    pub fn limit(&self) -> Option<usize> {
+        match self {
+            AirgapError::ChunkSizeTooLarge(_, limit)
+            | AirgapError::ChunkSizeTooSmall(_, limit)
+            | AirgapError::FrameTooLong(_, limit)
+            | AirgapError::ChunkLimitExceeded(_, limit)
+            | AirgapError::PayloadTooLarge(limit)
+            | AirgapError::SessionLimitReached(limit) => Some(*limit),
+            _ => None,
+        }
+    }
+
+    /// Wrap the payload of a panic caught by `catch_unwind` at a binding boundary.
+    pub fn from_panic(payload: &(dyn core::any::Any + Send)) -> Self {
+        let message = match payload.downcast_ref::<&str>() {
+            Some(message) => String::from(*message),
+            None => match payload.downcast_ref::<String>() {
+                Some(message) => message.clone(),
+                None => String::from("unknown panic payload"),
+            },
+        };
+        AirgapError::Panic(message)
+    }
+
+}
\ No newline at end of file
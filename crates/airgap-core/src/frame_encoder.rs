@@ -0,0 +1,174 @@
+// src/frame_encoder - splits a payload into QR strings, without rendering
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::bbqr::BbqrEncoder;
+use crate::error::AirgapError;
+use crate::protocol::*;
+use crate::ur::UrEncoder;
+
+enum Frames {
+    Airgap(Vec<Chunk>),
+    Ur(UrEncoder),
+    Bbqr(BbqrEncoder),
+}
+
+/// Produces the QR strings of a transfer in any `FrameFormat`.
+///
+/// This is the part of `airgap_render::Encoder` that works without `std`;
+/// the renderer adds QR symbols and a PNG cache on top.
+pub struct FrameEncoder {
+    frames: Frames,
+    session_id: u32,
+}
+
+impl FrameEncoder {
+    pub fn new(data: &[u8], chunk_size: usize) -> Result<Self, AirgapError> {
+        Self::with_session_id(data, chunk_size, new_session_id(data))
+    }
+
+    /// Create an encoder emitting frames in `format`. For UR, `chunk_size` is
+    /// the maximum fragment length and the session id is the message checksum.
+    /// For BBQr, `chunk_size` bounds the (compressed) bytes per frame; BBQr
+    /// has no session id, so it is reported as 0.
+    pub fn with_format(data: &[u8], chunk_size: usize, format: FrameFormat) -> Result<Self, AirgapError> {
+        match format {
+            FrameFormat::Airgap => Self::new(data, chunk_size),
+            FrameFormat::Ur(ur_type) => {
+                validate_chunk_size(chunk_size)?;
+                let encoder = UrEncoder::new(ur_type, data, chunk_size)?;
+                let session_id = encoder.checksum();
+                Ok(Self { frames: Frames::Ur(encoder), session_id })
+            }
+            FrameFormat::Bbqr(file_type) => {
+                validate_chunk_size(chunk_size)?;
+                let encoder = BbqrEncoder::new(file_type, data, chunk_size)?;
+                Ok(Self { frames: Frames::Bbqr(encoder), session_id: 0 })
+            }
+        }
+    }
+
+    /// Create a native-format encoder with a fixed session id instead of a
+    /// generated one, so the frames are fully determined by the inputs.
+    pub fn with_session_id(data: &[u8], chunk_size: usize, session_id: u32) -> Result<Self, AirgapError> {
+
+        if data.is_empty() {
+            return Err(AirgapError::EmptyData);
+        }
+
+        validate_chunk_size(chunk_size)?;
+
+        let total_chunks = data.len().div_ceil(chunk_size);
+
+        if total_chunks > 65535 {
+            return Err(AirgapError::TooManyChunks(total_chunks));
+        }
+
+        let mut chunks = Vec::with_capacity(total_chunks);
+
+        for i in 0..total_chunks {
+            let start = i * chunk_size;
+            let end = (start + chunk_size).min(data.len());
+            let chunk_data = data[start..end].to_vec();
+
+            let chunk = Chunk::new(
+                total_chunks as u16,
+                i as u16,
+                session_id,
+                chunk_data,
+            )?;
+
+            chunks.push(chunk);
+        }
+
+        Ok(Self { frames: Frames::Airgap(chunks), session_id })
+    }
+
+    /// QR text of frame `index`. UR encoders also accept indices past
+    /// `chunk_count`, which yield additional fountain-mixed frames.
+    pub fn get_qr_string(&self, index: usize) -> Result<String, AirgapError> {
+        let fountain = matches!(self.frames, Frames::Ur(_)) && index < u32::MAX as usize;
+        if index >= self.chunk_count() && !fountain {
+            return Err(AirgapError::ChunkOutOfBounds(index as u16))
+        }
+        match &self.frames {
+            Frames::Airgap(chunks) => Ok(base45::encode(chunks[index].to_bytes())),
+            Frames::Ur(encoder) => Ok(encoder.part(index as u32 + 1)),
+            Frames::Bbqr(encoder) => encoder.part(index)
+                .map(str::to_string)
+                .ok_or(AirgapError::ChunkOutOfBounds(index as u16)),
+        }
+    }
+
+    /// Stream of the QR strings of every frame, in order; see `FrameStream`.
+    #[cfg(feature = "async")]
+    pub fn frame_stream(&self) -> crate::stream::FrameStream<'_> {
+        crate::stream::FrameStream::new(self)
+    }
+
+    pub fn session_id(&self) -> u32 {
+        self.session_id
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        match &self.frames {
+            Frames::Airgap(chunks) => chunks.len(),
+            Frames::Ur(encoder) => encoder.seq_len(),
+            Frames::Bbqr(encoder) => encoder.part_count(),
+        }
+    }
+
+    pub fn format(&self) -> FrameFormat {
+        match &self.frames {
+            Frames::Airgap(_) => FrameFormat::Airgap,
+            Frames::Ur(encoder) => FrameFormat::Ur(encoder.ur_type()),
+            Frames::Bbqr(encoder) => FrameFormat::Bbqr(encoder.file_type()),
+        }
+    }
+}
+
+// Random with `std`. Without it there is no portable entropy source, so the
+// id is derived from the payload and a counter: distinct for consecutive
+// transfers but predictable. Devices with a hardware RNG should pass its
+// output to `with_session_id` instead.
+#[cfg(feature = "std")]
+fn new_session_id(_data: &[u8]) -> u32 {
+    rand::random::<u32>()
+}
+
+#[cfg(not(feature = "std"))]
+fn new_session_id(data: &[u8]) -> u32 {
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    static TRANSFERS: AtomicU32 = AtomicU32::new(0);
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(&TRANSFERS.fetch_add(1, Ordering::Relaxed).to_be_bytes());
+    hasher.update(data);
+    hasher.finalize()
+}
+
+fn validate_chunk_size(chunk_size: usize) -> Result<(), AirgapError> {
+    if chunk_size < MIN_CHUNK_SIZE {
+        return Err(AirgapError::ChunkSizeTooSmall(chunk_size, MIN_CHUNK_SIZE));
+    }
+
+    if chunk_size > MAX_CHUNK_SIZE {
+        return Err(AirgapError::ChunkSizeTooLarge(
+            chunk_size,
+            MAX_CHUNK_SIZE,
+        ));
+    }
+
+    // Warn if using very large chunk size (won't scan well); there is
+    // nowhere to print to without std
+    #[cfg(feature = "std")]
+    if chunk_size > RECOMMENDED_MAX_CHUNK_SIZE {
+        std::eprintln!(
+            "Warning: chunk size {} exceeds recommended maximum {}. \
+             QR codes may be difficult to scan.",
+            chunk_size, RECOMMENDED_MAX_CHUNK_SIZE
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,934 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod protocol;
+pub mod frame_encoder;
+pub mod decoder;
+pub mod clock;
+pub mod limits;
+#[cfg(feature = "std")]
+pub mod shared_decoder;
+pub mod auto_decoder;
+pub mod ur;
+pub mod bbqr;
+#[cfg(feature = "std")]
+pub mod batch;
+pub mod observer;
+#[cfg(feature = "async")]
+pub mod stream;
+pub mod codes;
+pub mod error;
+
+pub use protocol::{Chunk, ChunkRef, FrameFormat, FrameHeader};
+pub use frame_encoder::FrameEncoder;
+pub use error::AirgapError;
+#[cfg(feature = "std")]
+pub use batch::{BatchOptions, CancelToken};
+pub use decoder::{Decoder, DecoderSnapshot, DecoderStatus, DecoderTimeouts};
+pub use clock::Clock;
+#[cfg(target_has_atomic = "64")]
+pub use clock::ManualClock;
+#[cfg(all(feature = "std", not(all(target_arch = "wasm32", target_os = "unknown"))))]
+pub use clock::SystemClock;
+pub use limits::DecoderLimits;
+#[cfg(feature = "std")]
+pub use shared_decoder::SharedDecoder;
+pub use observer::{DecoderEvent, DecoderObserver};
+#[cfg(feature = "async")]
+pub use stream::{decode_sink, DecodePayload, DecodeSink, FrameStream};
+pub use auto_decoder::{AutoDecoder, DetectedFormat, DetectedFrame};
+pub use ur::UrType;
+pub use bbqr::{BbqrEncoding, BbqrFileType};
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_round_trip() {
+        // Arbitrary data
+        let data = vec![0x42; 2000]; // 2KB of data
+
+        // Encode
+        let encoder = FrameEncoder::new(&data, 500).unwrap();
+        println!("Chunks: {}", encoder.chunk_count());
+
+        // Simulate scanning
+        let mut decoder = Decoder::new();
+
+        // Get raw encoded strings (simulate QR scanning)
+        for i in 0..encoder.chunk_count() {
+            let qr_string = encoder.get_qr_string(i).unwrap();
+            decoder.process_qr_string(&qr_string).unwrap();
+        }
+
+        // Decode
+        assert!(decoder.is_complete());
+        let decoded = decoder.get_data().unwrap();
+
+        assert_eq!(data, decoded);
+    }
+
+    #[test]
+    fn test_ur_round_trip() {
+        let psbt: Vec<u8> = (0..1500u32).map(|i| (i * 31 % 251) as u8).collect();
+
+        let encoder = FrameEncoder::with_format(&psbt, 200, FrameFormat::Ur(UrType::CryptoPsbt)).unwrap();
+        let mut decoder = Decoder::with_format(FrameFormat::Ur(UrType::CryptoPsbt));
+
+        for i in 0..encoder.chunk_count() {
+            let ur = encoder.get_qr_string(i).unwrap();
+            assert!(ur.starts_with(&format!("ur:crypto-psbt/{}-{}/", i + 1, encoder.chunk_count())));
+            // Scanners usually hand back the uppercase form rendered into the QR
+            decoder.process_qr_string(&ur.to_ascii_uppercase()).unwrap();
+        }
+
+        assert!(decoder.is_complete());
+        assert_eq!(decoder.session_id(), Some(encoder.session_id()));
+        assert_eq!(decoder.get_data().unwrap(), psbt);
+    }
+
+    #[test]
+    fn test_ur_fountain_recovers_dropped_parts() {
+        let data = vec![0x5A; 1000];
+        let encoder = ur::UrEncoder::new(UrType::Bytes, &data, 100).unwrap();
+        let mut decoder = Decoder::with_format(FrameFormat::Ur(UrType::Bytes));
+
+        // Skip the first two simple parts and rely on fountain parts instead
+        let mut seq_num = 3;
+        while !decoder.is_complete() {
+            decoder.process_qr_string(&encoder.part(seq_num)).unwrap();
+            seq_num += 1;
+            assert!(seq_num < 200, "fountain decoder did not converge");
+        }
+
+        assert_eq!(decoder.get_data().unwrap(), data);
+    }
+
+    #[test]
+    fn test_ur_fountain_mixed_parts_bounded() {
+        use ur::fountain::{FountainDecoder, FountainEncoder, choose_fragments};
+
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 7 % 256) as u8).collect();
+        let encoder = FountainEncoder::new(&data, 100);
+        let seq_len = encoder.seq_len();
+        let mut decoder = FountainDecoder::new();
+
+        // Mixed parts never peel without a simple one, so a flood of them
+        // would pile up; only the newest `seq_len` are kept
+        let mut flooded = 0;
+        for seq_num in seq_len as u32 + 1.. {
+            if choose_fragments(seq_num, seq_len, encoder.checksum()).len() > 1 {
+                decoder.receive(&encoder.part(seq_num)).unwrap();
+                flooded += 1;
+            }
+            if flooded == 5000 {
+                break;
+            }
+        }
+        assert!(decoder.buffered_bytes() <= data.len());
+
+        for seq_num in 1..=seq_len as u32 {
+            decoder.receive(&encoder.part(seq_num)).unwrap();
+        }
+        assert_eq!(decoder.message(), Some(&data[..]));
+    }
+
+    #[test]
+    fn test_ur_fountain_crc_restart() {
+        use ur::fountain::{FountainDecoder, FountainEncoder};
+
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 13 % 256) as u8).collect();
+        let encoder = FountainEncoder::new(&data, 100);
+        let seq_len = encoder.seq_len();
+
+        // A corrupted fragment fails the message CRC and clears the state,
+        // so the next pass over the parts can still complete
+        let mut decoder = FountainDecoder::new();
+        let mut corrupted = encoder.part(1);
+        corrupted.data[0] ^= 0xFF;
+        decoder.receive(&corrupted).unwrap();
+        for seq_num in 2..seq_len as u32 {
+            decoder.receive(&encoder.part(seq_num)).unwrap();
+        }
+        let last = encoder.part(seq_len as u32);
+        assert!(matches!(decoder.receive(&last), Err(AirgapError::CrcMismatch)));
+        assert_eq!((decoder.recovered_count(), decoder.seq_len()), (0, 0));
+        for seq_num in 1..=seq_len as u32 {
+            decoder.receive(&encoder.part(seq_num)).unwrap();
+        }
+        assert_eq!(decoder.message(), Some(&data[..]));
+    }
+
+    #[test]
+    fn test_ur_single_part() {
+        let encoder = FrameEncoder::with_format(b"hello", 100, FrameFormat::Ur(UrType::Bytes)).unwrap();
+        assert_eq!(encoder.chunk_count(), 1);
+
+        let ur = encoder.get_qr_string(0).unwrap();
+        assert!(ur.starts_with("ur:bytes/") && !ur.contains('-'));
+
+        let mut decoder = Decoder::with_format(FrameFormat::Ur(UrType::Bytes));
+        decoder.process_qr_string(&ur).unwrap();
+        assert_eq!(decoder.get_data().unwrap(), b"hello");
+
+        // A UR of another type is rejected
+        let mut psbt_decoder = Decoder::with_format(FrameFormat::Ur(UrType::CryptoPsbt));
+        assert!(psbt_decoder.process_qr_string(&ur).is_err());
+    }
+
+    #[test]
+    fn test_bbqr_round_trip() {
+        // Repetitive payload so the encoder picks zlib
+        let psbt: Vec<u8> = b"psbt\xff".iter().copied().cycle().take(3000).collect();
+
+        let encoder = FrameEncoder::with_format(&psbt, 200, FrameFormat::Bbqr(BbqrFileType::Psbt)).unwrap();
+        let total = encoder.chunk_count();
+
+        let mut decoder = Decoder::with_format(FrameFormat::Bbqr(BbqrFileType::Psbt));
+        for i in (0..total).rev() {
+            let frame = encoder.get_qr_string(i).unwrap();
+            assert!(frame.starts_with("B$ZP"));
+            let chunk = decoder.process_qr_string(&frame).unwrap();
+            assert_eq!(chunk.chunk_index as usize, i);
+        }
+
+        assert!(decoder.is_complete());
+        assert_eq!(decoder.get_data().unwrap(), psbt);
+    }
+
+    #[test]
+    fn test_bbqr_encodings() {
+        // Incompressible payload falls back to Base32
+        let data: Vec<u8> = (0..400u32).map(|i| (i.wrapping_mul(2654435761) >> 24) as u8).collect();
+        let encoder = FrameEncoder::with_format(&data, 100, FrameFormat::Bbqr(BbqrFileType::Binary)).unwrap();
+        assert!(encoder.get_qr_string(0).unwrap().starts_with("B$2B"));
+
+        // Hex frames, e.g. from older senders
+        let hex = bbqr::BbqrEncoder::with_encoding(BbqrFileType::Transaction, BbqrEncoding::Hex, &data, 100).unwrap();
+        assert_eq!(hex.part(0).unwrap()[..8], format!("B$HT{:0>2}00", hex.part_count()));
+
+        let mut decoder = Decoder::with_format(FrameFormat::Bbqr(BbqrFileType::Transaction));
+        for i in 0..hex.part_count() {
+            decoder.process_qr_string(hex.part(i).unwrap()).unwrap();
+        }
+        assert_eq!(decoder.get_data().unwrap(), data);
+
+        // Frames with a different header cannot join the transfer
+        let mut decoder = Decoder::with_format(FrameFormat::Bbqr(BbqrFileType::Binary));
+        decoder.process_qr_string(&encoder.get_qr_string(0).unwrap()).unwrap();
+        assert!(matches!(
+            decoder.process_qr_string("B$HB0100AB"),
+            Err(error::AirgapError::MetadataMismatch)
+        ));
+
+        // Part numbers are two uppercase base36 digits
+        for header in ["B$HB+100AB", "B$HB01+0AB", "B$HB0a00AB", "B$HB01 0AB"] {
+            assert!(matches!(bbqr::BbqrFrame::parse(header), Err(error::AirgapError::InvalidBbqr(_))), "{}", header);
+        }
+        assert!(bbqr::BbqrFrame::parse("B$HB0100AB").is_ok());
+    }
+
+    #[test]
+    fn test_auto_decoder() {
+        let data = b"Detected without telling the decoder the format".repeat(10);
+
+        for format in [
+            FrameFormat::Airgap,
+            FrameFormat::Ur(UrType::CryptoPsbt),
+            FrameFormat::Bbqr(BbqrFileType::Psbt),
+        ] {
+            let encoder = FrameEncoder::with_format(&data, 100, format).unwrap();
+            let mut decoder = AutoDecoder::new();
+            for i in 0..encoder.chunk_count() {
+                let frame = decoder.process_qr_string(&encoder.get_qr_string(i).unwrap()).unwrap();
+                assert_eq!(frame.format, DetectedFormat::Frames(format));
+            }
+            assert!(decoder.is_complete());
+            assert_eq!(decoder.get_data().unwrap(), data);
+        }
+
+        // Plain text is a complete single-frame payload
+        let mut decoder = AutoDecoder::new();
+        let frame = decoder.process_qr_string("bitcoin:bc1qexample").unwrap();
+        assert_eq!(frame.format, DetectedFormat::Raw);
+        assert!(decoder.is_complete());
+        assert_eq!(decoder.get_data().unwrap(), b"bitcoin:bc1qexample");
+
+        // Once a session has started, other formats are rejected
+        let ur = FrameEncoder::with_format(&data, 100, FrameFormat::Ur(UrType::Bytes)).unwrap();
+        assert!(matches!(
+            decoder.process_qr_string(&ur.get_qr_string(0).unwrap()),
+            Err(error::AirgapError::SessionMismatch)
+        ));
+        decoder.reset();
+        assert!(decoder.process_qr_string(&ur.get_qr_string(0).unwrap()).is_ok());
+
+        // A rejected native frame doesn't shadow a raw session started after it
+        let native = FrameEncoder::new(&data, 100).unwrap().get_qr_string(0).unwrap();
+        let mut bytes = base45::decode(&native).unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        let mut decoder = AutoDecoder::new();
+        assert!(matches!(
+            decoder.process_qr_string(&base45::encode(&bytes)),
+            Err(error::AirgapError::CrcMismatch)
+        ));
+        assert_eq!(decoder.process_qr_string("plain text").unwrap().format, DetectedFormat::Raw);
+        assert!(decoder.is_complete());
+        assert_eq!((decoder.received_count(), decoder.total_count()), (1, 1));
+        assert_eq!(decoder.get_data().unwrap(), b"plain text");
+
+        // Base45-looking text with the magic but other characters stays raw
+        let mut prefixed = native[..3].to_string();
+        prefixed.push_str("lowercase");
+        assert_eq!(auto_decoder::detect_format(&prefixed).unwrap(), DetectedFormat::Raw);
+    }
+
+    #[test]
+    fn test_decoder_observer() {
+        use std::sync::{Arc, Mutex};
+
+        let encoder = FrameEncoder::new(&[0x24; 300], 100).unwrap();
+        let other = FrameEncoder::new(&[0x24; 300], 100).unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let mut decoder = Decoder::new();
+        decoder.set_observer(Box::new(move |event: &DecoderEvent| {
+            let name = match event {
+                DecoderEvent::SessionStarted { total_chunks, .. } => format!("start/{}", total_chunks),
+                DecoderEvent::ChunkReceived { chunk, received, .. } => format!("chunk {} ({})", chunk.chunk_index, received),
+                DecoderEvent::Duplicate { chunk } => format!("duplicate {}", chunk.chunk_index),
+                DecoderEvent::Conflict(_) => "conflict".to_string(),
+                DecoderEvent::Error(_) => "error".to_string(),
+                DecoderEvent::Completed => "complete".to_string(),
+            };
+            sink.lock().unwrap().push(name);
+        }));
+
+        decoder.process_qr_string(&encoder.get_qr_string(0).unwrap()).unwrap();
+        decoder.process_qr_string(&encoder.get_qr_string(0).unwrap()).unwrap();
+        assert!(decoder.process_qr_string(&other.get_qr_string(1).unwrap()).is_err());
+        assert!(decoder.process_qr_string("not a frame").is_err());
+        decoder.process_qr_string(&encoder.get_qr_string(1).unwrap()).unwrap();
+        decoder.process_qr_string(&encoder.get_qr_string(2).unwrap()).unwrap();
+
+        assert_eq!(*events.lock().unwrap(), [
+            "start/3", "chunk 0 (1)", "duplicate 0", "conflict", "error",
+            "chunk 1 (2)", "chunk 2 (3)", "complete",
+        ]);
+    }
+
+    #[test]
+    fn test_shared_decoder() {
+        use std::sync::Arc;
+        use std::thread;
+
+        let data: Vec<u8> = (0..5000).map(|i| (i % 251) as u8).collect();
+        let encoder = FrameEncoder::new(&data, 100).unwrap();
+        let frames: Vec<String> = (0..encoder.chunk_count())
+            .map(|i| encoder.get_qr_string(i).unwrap())
+            .collect();
+        let decoder = Arc::new(SharedDecoder::new());
+
+        // Camera thread feeds frames while this thread polls progress
+        let scanner = {
+            let decoder = decoder.clone();
+            let frames = frames.clone();
+            thread::spawn(move || {
+                for frame in &frames {
+                    decoder.process_qr_string(frame).unwrap();
+                }
+            })
+        };
+
+        let mut last_received = 0;
+        while !decoder.is_complete() {
+            let received = decoder.received_count();
+            assert!(received >= last_received);
+            assert!(received <= frames.len());
+            last_received = received;
+            thread::yield_now();
+        }
+        scanner.join().unwrap();
+
+        assert_eq!(decoder.received_count(), frames.len());
+        assert_eq!(decoder.total_count(), frames.len());
+        assert_eq!(decoder.session_id(), Some(encoder.session_id()));
+        assert_eq!(decoder.get_data().unwrap(), data);
+
+        decoder.reset();
+        assert!(!decoder.is_complete());
+        assert_eq!(decoder.received_count(), 0);
+        assert_eq!(decoder.session_id(), None);
+    }
+
+    #[test]
+    fn test_session_timeouts() {
+        let encoder = FrameEncoder::new(&[0x42; 300], 100).unwrap();
+        let other = FrameEncoder::new(&[0x24; 300], 100).unwrap();
+        let clock = ManualClock::new();
+
+        let mut decoder = Decoder::new();
+        decoder.set_clock(Box::new(clock.clone()));
+        decoder.set_timeouts(DecoderTimeouts { idle_ms: 1000, total_ms: 5000, auto_expire: false });
+        assert_eq!(decoder.status(), DecoderStatus::Idle);
+
+        decoder.process_qr_string(&encoder.get_qr_string(0).unwrap()).unwrap();
+        assert_eq!(decoder.status(), DecoderStatus::Receiving);
+
+        // Duplicates do not count as progress
+        clock.advance(600);
+        decoder.process_qr_string(&encoder.get_qr_string(0).unwrap()).unwrap();
+        clock.advance(600);
+        assert_eq!(decoder.status(), DecoderStatus::Stalled);
+        assert!(matches!(
+            decoder.process_qr_string(&other.get_qr_string(0).unwrap()),
+            Err(error::AirgapError::SessionMismatch)
+        ));
+
+        // A new chunk resumes the session
+        decoder.process_qr_string(&encoder.get_qr_string(1).unwrap()).unwrap();
+        assert_eq!(decoder.status(), DecoderStatus::Receiving);
+
+        clock.advance(4000);
+        assert_eq!(decoder.status(), DecoderStatus::Expired);
+        assert!(matches!(
+            decoder.process_qr_string(&encoder.get_qr_string(2).unwrap()),
+            Err(error::AirgapError::SessionExpired)
+        ));
+        assert!(decoder.expire_stale());
+        assert_eq!(decoder.status(), DecoderStatus::Idle);
+        assert!(!decoder.expire_stale());
+
+        // Automatic expiry: a stalled session gives way to another one
+        decoder.set_timeouts(DecoderTimeouts { idle_ms: 1000, total_ms: 5000, auto_expire: true });
+        decoder.process_qr_string(&encoder.get_qr_string(0).unwrap()).unwrap();
+        clock.advance(1500);
+        decoder.process_qr_string(&other.get_qr_string(0).unwrap()).unwrap();
+        assert_eq!(decoder.session_id(), Some(other.session_id()));
+        assert_eq!(decoder.received_count(), 1);
+
+        // ... and an expired one restarts with the next frame
+        clock.advance(5000);
+        decoder.process_qr_string(&other.get_qr_string(1).unwrap()).unwrap();
+        assert_eq!(decoder.status(), DecoderStatus::Receiving);
+        assert_eq!(decoder.received_count(), 1);
+
+        // Complete sessions never expire
+        decoder.process_qr_string(&other.get_qr_string(0).unwrap()).unwrap();
+        decoder.process_qr_string(&other.get_qr_string(2).unwrap()).unwrap();
+        clock.advance(10_000);
+        assert_eq!(decoder.status(), DecoderStatus::Complete);
+        assert_eq!(decoder.get_data().unwrap(), vec![0x24; 300]);
+    }
+
+    #[test]
+    fn test_error_table() {
+        use error::{error_kind, AirgapError, ERROR_KINDS};
+
+        for (i, (code, kind)) in ERROR_KINDS.iter().enumerate() {
+            assert!(ERROR_KINDS[i + 1..].iter().all(|(other, _)| other != code), "duplicate code {}", code);
+            assert_eq!(error_kind(*code), Some(*kind));
+        }
+        assert_eq!(error_kind(0), None);
+
+        let error = AirgapError::MissingChunk(4);
+        assert_eq!((error.to_code(), error.kind(), error.chunk_index()), (error::AIRGAP_ERR_MISSING_CHUNK, "MissingChunk", Some(4)));
+        let error = AirgapError::FrameTooLong(5000, 4096);
+        assert_eq!((error.kind(), error.value(), error.limit()), ("FrameTooLong", Some(5000), Some(4096)));
+        assert_eq!(AirgapError::NullPointer("decoder").kind(), "NullPointer");
+        assert_eq!(AirgapError::UnknownCode("frame format", 99).to_string(), "Unknown frame format code 99");
+    }
+
+    #[test]
+    fn test_decoder_limits() {
+        use error::AirgapError;
+
+        let data = vec![0x42; 1000];
+        let encoder = FrameEncoder::new(&data, 100).unwrap();
+        let frame = |i: usize| encoder.get_qr_string(i).unwrap();
+
+        // New decoders are capped out of the box; `unlimited` opts out
+        let mut decoder = Decoder::new();
+        assert_eq!(decoder.limits(), DecoderLimits::default());
+        assert!(matches!(
+            decoder.process_qr_string(&"A".repeat(5000)),
+            Err(AirgapError::FrameTooLong(5000, DecoderLimits::DEFAULT_MAX_FRAME_LEN))
+        ));
+        decoder.set_limits(DecoderLimits::unlimited());
+        assert!(!matches!(decoder.process_qr_string(&"A".repeat(5000)), Err(AirgapError::FrameTooLong(..))));
+
+        // Hostile chunk counts are rejected before a session starts
+        decoder.set_limits(DecoderLimits { max_total_chunks: 5, ..DecoderLimits::default() });
+        assert!(matches!(decoder.process_qr_string(&frame(0)), Err(AirgapError::ChunkLimitExceeded(10, 5))));
+        assert_eq!(decoder.session_id(), None);
+
+        // Oversized strings never reach the Base45 decoder
+        decoder.set_limits(DecoderLimits { max_frame_len: 64, ..DecoderLimits::default() });
+        assert!(matches!(
+            decoder.process_qr_string(&"A".repeat(1 << 20)),
+            Err(AirgapError::FrameTooLong(_, 64))
+        ));
+
+        // Buffered bytes are capped; duplicates do not count twice
+        decoder.set_limits(DecoderLimits { max_payload_bytes: 250, ..DecoderLimits::default() });
+        decoder.process_qr_string(&frame(0)).unwrap();
+        decoder.process_qr_string(&frame(1)).unwrap();
+        decoder.process_qr_string(&frame(1)).unwrap();
+        assert!(matches!(decoder.process_qr_string(&frame(2)), Err(AirgapError::PayloadTooLarge(250))));
+        assert_eq!(decoder.received_count(), 2);
+
+        // Sessions count across expiry; only `reset` starts the count over
+        let clock = ManualClock::new();
+        let mut decoder = Decoder::new();
+        decoder.set_clock(Box::new(clock.clone()));
+        decoder.set_timeouts(DecoderTimeouts { idle_ms: 100, total_ms: 0, auto_expire: false });
+        decoder.set_limits(DecoderLimits { max_sessions: 2, ..DecoderLimits::default() });
+        for _ in 0..2 {
+            decoder.process_qr_string(&frame(0)).unwrap();
+            clock.advance(200);
+            assert!(decoder.expire_stale());
+        }
+        assert!(matches!(decoder.process_qr_string(&frame(0)), Err(AirgapError::SessionLimitReached(2))));
+        decoder.reset();
+        decoder.process_qr_string(&frame(0)).unwrap();
+        decoder.process_qr_string(&frame(1)).unwrap();
+
+        // UR frames announce the message length up front
+        let ur = FrameEncoder::with_format(&data, 100, FrameFormat::Ur(UrType::Bytes)).unwrap();
+        let mut decoder = Decoder::with_format(FrameFormat::Ur(UrType::Bytes));
+        decoder.set_limits(DecoderLimits { max_payload_bytes: 500, ..DecoderLimits::default() });
+        assert!(matches!(
+            decoder.process_qr_string(&ur.get_qr_string(0).unwrap()),
+            Err(AirgapError::PayloadTooLarge(500))
+        ));
+
+        // A zlib bomb is stopped while inflating
+        let bomb = vec![0u8; 200_000];
+        let bbqr = bbqr::BbqrEncoder::with_encoding(BbqrFileType::Binary, BbqrEncoding::Zlib, &bomb, 1000).unwrap();
+        let mut decoder = Decoder::with_format(FrameFormat::Bbqr(BbqrFileType::Binary));
+        decoder.set_limits(DecoderLimits { max_payload_bytes: 10_000, ..DecoderLimits::default() });
+        for i in 0..bbqr.part_count() {
+            decoder.process_qr_string(bbqr.part(i).unwrap()).unwrap();
+        }
+        assert!(decoder.is_complete());
+        assert!(matches!(decoder.get_data(), Err(AirgapError::PayloadTooLarge(10_000))));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_batch_pool_reuse() {
+        use std::collections::HashSet;
+        use std::sync::Mutex;
+
+        let squares = batch::map_batch(100, &BatchOptions::default(), |i| Ok(i * i)).unwrap();
+        assert_eq!(squares[9], 81);
+
+        // Clones share the bounded pool, so two batches stay on the same two workers
+        let options = BatchOptions::with_threads(2);
+        let workers = Mutex::new(HashSet::new());
+        for opts in [options.clone(), options] {
+            batch::map_batch(200, &opts, |_| {
+                workers.lock().unwrap().insert(std::thread::current().id());
+                Ok(())
+            })
+            .unwrap();
+        }
+        assert!(workers.lock().unwrap().len() <= 2);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_async_adapters() {
+        use futures::executor::block_on;
+        use futures::{SinkExt, StreamExt};
+        use std::time::Duration;
+
+        let data: Vec<u8> = (0..3000).map(|i| (i % 251) as u8).collect();
+        let encoder = FrameEncoder::new(&data, 100).unwrap();
+
+        // Stream into the sink, with an unrelated code in the middle
+        let (mut sink, payload) = decode_sink(Decoder::new());
+        let frames = encoder.frame_stream().take(5)
+            .chain(futures::stream::iter([Ok("not a frame".to_string())]))
+            .chain(encoder.frame_stream().skip(5));
+        block_on(frames.forward(&mut sink)).unwrap();
+        assert_eq!(sink.received_count(), encoder.chunk_count());
+        assert_eq!(block_on(payload.with_timeout(Duration::from_secs(5))).unwrap(), data);
+
+        // The sink stops accepting frames once the payload has resolved
+        assert!(matches!(block_on(sink.send(encoder.get_qr_string(0).unwrap())), Err(error::AirgapError::Cancelled)));
+
+        // Closing the sink early resolves the payload with the missing chunks
+        let (mut sink, payload) = decode_sink(Decoder::new());
+        block_on(sink.send(encoder.get_qr_string(0).unwrap())).unwrap();
+        block_on(sink.close()).unwrap();
+        assert!(block_on(payload).is_err());
+
+        // Timeout and cancellation
+        let (_sink, payload) = decode_sink(Decoder::new());
+        let result = block_on(payload.with_timeout(Duration::from_millis(20)));
+        assert!(matches!(result, Err(error::AirgapError::TimedOut)));
+
+        let cancel = CancelToken::new();
+        let (_sink, payload) = decode_sink(Decoder::new());
+        let payload = payload.with_cancel(cancel.clone());
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            cancel.cancel();
+        });
+        assert!(matches!(block_on(payload), Err(error::AirgapError::Cancelled)));
+        canceller.join().unwrap();
+
+        // Looping streams start over; cancelled streams end
+        let cancel = CancelToken::new();
+        let mut looping = encoder.frame_stream().looping().with_cancel(cancel.clone());
+        for _ in 0..encoder.chunk_count() {
+            block_on(looping.next()).unwrap().unwrap();
+        }
+        assert_eq!(block_on(looping.next()).unwrap().unwrap(), encoder.get_qr_string(0).unwrap());
+        cancel.cancel();
+        assert!(block_on(looping.next()).is_none());
+    }
+
+    const GOLDEN_VECTORS: &str = include_str!("../../../vectors/chunk_format.tsv");
+
+    const GOLDEN_HEADER: &str = "\
+# Golden vectors for the native chunk format, shared by the Rust, Kotlin,
+# Objective-C and wasm test suites. Tab-separated; regenerate with
+#     cargo test regenerate_golden_vectors -- --ignored
+#
+# encode <name> <session id> <chunk size> <data hex>
+#     followed by one row per frame the encoder must produce:
+# frame <index> <chunk bytes hex> <QR string>
+# error <name> <AIRGAP_ERR code> <error kind> <QR string>
+#     a frame a fresh native decoder must reject with that error
+";
+
+    // Encoder inputs: name, session id, chunk size, data
+    fn golden_inputs() -> Vec<(&'static str, u32, usize, Vec<u8>)> {
+        vec![
+            ("single_chunk", 0x0000_0001, 100, b"Hello, Airgap!".to_vec()),
+            ("multi_chunk", 0xDEAD_BEEF, 32, (0..100u32).map(|i| (i * 37 % 256) as u8).collect()),
+            ("min_chunk_size", 0x7FFF_FFFF, 16, (0..40u8).collect()),
+            ("exact_multiple", 0x1234_5678, 20, vec![0xA5; 60]),
+        ]
+    }
+
+    // Malformed frames, mostly a valid chunk with one header field corrupted
+    fn golden_malformed() -> Vec<(&'static str, String)> {
+        let valid = Chunk::new(2, 0, 1, vec![0x55; 16]).unwrap().to_bytes();
+        let corrupt = |offset: usize, bytes: &[u8]| {
+            let mut chunk = valid.clone();
+            chunk[offset..offset + bytes.len()].copy_from_slice(bytes);
+            base45::encode(chunk)
+        };
+        vec![
+            ("empty", String::new()),
+            ("invalid_base45", "AIRGAP~FRAME".to_string()),
+            ("too_short", base45::encode(&valid[..protocol::HEADER_SIZE + 3])),
+            ("bad_magic", corrupt(0, &[0x00, 0x00])),
+            ("bad_version", corrupt(2, &[2])),
+            ("zero_total_chunks", corrupt(3, &[0, 0])),
+            ("index_out_of_bounds", corrupt(5, &[0, 2])),
+            ("data_too_large", corrupt(11, &1921u16.to_be_bytes())),
+            ("truncated", corrupt(11, &17u16.to_be_bytes())),
+            ("crc_mismatch", corrupt(valid.len() - 1, &[valid[valid.len() - 1] ^ 0xFF])),
+        ]
+    }
+
+    fn render_golden_vectors() -> String {
+        let hex = data_encoding::HEXLOWER;
+        let mut out = GOLDEN_HEADER.to_string();
+        for (name, session_id, chunk_size, data) in golden_inputs() {
+            let encoder = FrameEncoder::with_session_id(&data, chunk_size, session_id).unwrap();
+            out += &format!("\nencode\t{}\t{}\t{}\t{}\n", name, session_id, chunk_size, hex.encode(&data));
+            for index in 0..encoder.chunk_count() {
+                let frame = encoder.get_qr_string(index).unwrap();
+                let chunk = base45::decode(&frame).unwrap();
+                out += &format!("frame\t{}\t{}\t{}\n", index, hex.encode(&chunk), frame);
+            }
+        }
+        out += "\n";
+        for (name, frame) in golden_malformed() {
+            let err = Decoder::new().process_qr_string(&frame).unwrap_err();
+            out += &format!("error\t{}\t{}\t{}\t{}\n", name, err.to_code(), err.kind(), frame);
+        }
+        out
+    }
+
+    #[test]
+    #[ignore = "rewrites vectors/chunk_format.tsv"]
+    fn regenerate_golden_vectors() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../vectors/chunk_format.tsv");
+        std::fs::write(path, render_golden_vectors()).unwrap();
+    }
+
+    #[test]
+    fn test_golden_vectors() {
+        let hex = data_encoding::HEXLOWER;
+        let mut encoder: Option<(FrameEncoder, Vec<u8>, u32)> = None;
+        let mut frames: Vec<String> = Vec::new();
+        let mut encodings = 0;
+        let mut errors = 0;
+
+        // Check the frames collected for the previous `encode` row
+        let mut finish = |encoder: Option<(FrameEncoder, Vec<u8>, u32)>, frames: &mut Vec<String>| {
+            let Some((encoder, data, session_id)) = encoder else { return };
+            assert_eq!(encoder.chunk_count(), frames.len());
+            let mut decoder = Decoder::new();
+            for frame in frames.iter().rev() {
+                decoder.process_qr_string(frame).unwrap();
+            }
+            assert_eq!(decoder.session_id(), Some(session_id));
+            assert_eq!(decoder.get_data().unwrap(), data);
+            frames.clear();
+            encodings += 1;
+        };
+
+        for line in GOLDEN_VECTORS.lines().filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            let fields: Vec<&str> = line.split('\t').collect();
+            match fields.as_slice() {
+                ["encode", _name, session_id, chunk_size, data] => {
+                    finish(encoder.take(), &mut frames);
+                    let data = hex.decode(data.as_bytes()).unwrap();
+                    let session_id: u32 = session_id.parse().unwrap();
+                    let chunk_size = chunk_size.parse().unwrap();
+                    let built = FrameEncoder::with_session_id(&data, chunk_size, session_id).unwrap();
+                    encoder = Some((built, data, session_id));
+                }
+                ["frame", index, chunk_hex, frame] => {
+                    let (built, _, _) = encoder.as_ref().expect("frame row before any encode row");
+                    let index: usize = index.parse().unwrap();
+                    let expected = hex.decode(chunk_hex.as_bytes()).unwrap();
+                    assert_eq!(index, frames.len());
+                    assert_eq!(built.get_qr_string(index).unwrap(), *frame);
+                    assert_eq!(base45::decode(frame).unwrap(), expected);
+                    let chunk = Chunk::from_bytes(&expected).unwrap();
+                    assert_eq!(chunk.chunk_index as usize, index);
+                    assert_eq!(chunk.to_bytes(), expected);
+                    frames.push(frame.to_string());
+                }
+                ["error", name, code, kind, frame] => {
+                    let err = Decoder::new().process_qr_string(frame).unwrap_err();
+                    assert_eq!(err.to_code().to_string(), *code, "vector {}", name);
+                    assert_eq!(err.kind(), *kind, "vector {}", name);
+                    errors += 1;
+                }
+                _ => panic!("malformed vector row: {:?}", line),
+            }
+        }
+        finish(encoder.take(), &mut frames);
+
+        assert_eq!(encodings, golden_inputs().len());
+        assert_eq!(errors, golden_malformed().len());
+        assert_eq!(GOLDEN_VECTORS, render_golden_vectors());
+    }
+
+    #[test]
+    fn test_chunk_ref() {
+        let data: Vec<u8> = (0..1200u32).map(|i| (i % 241) as u8).collect();
+        let encoder = FrameEncoder::new(&data, 500).unwrap();
+
+        // Parsing in place borrows the payload from the frame bytes
+        let bytes = base45::decode(encoder.get_qr_string(1).unwrap()).unwrap();
+        let chunk = ChunkRef::from_bytes(&bytes).unwrap();
+        assert_eq!(chunk.data.as_ptr(), bytes[protocol::HEADER_SIZE..].as_ptr());
+        assert_eq!(chunk.header(), FrameHeader::from_bytes(&bytes).unwrap());
+        let owned = Chunk::from_bytes(&bytes).unwrap();
+        assert_eq!(chunk.to_chunk(), owned);
+        assert_eq!(owned.as_chunk_ref(), chunk);
+
+        let mut corrupted = bytes.clone();
+        *corrupted.last_mut().unwrap() ^= 1;
+        assert!(matches!(ChunkRef::from_bytes(&corrupted), Err(AirgapError::CrcMismatch)));
+        assert!(matches!(ChunkRef::from_bytes(&bytes[..bytes.len() - 1]), Err(AirgapError::EncodingError(_))));
+
+        // The borrowed decoder path returns the stored payload
+        for format in [FrameFormat::Airgap, FrameFormat::Ur(UrType::Bytes), FrameFormat::Bbqr(BbqrFileType::Binary)] {
+            let encoder = FrameEncoder::with_format(&data, 300, format).unwrap();
+            let mut decoder = Decoder::with_format(format);
+            for i in (0..encoder.chunk_count()).rev() {
+                let qr_string = encoder.get_qr_string(i).unwrap();
+                let expected = Decoder::with_format(format).process_qr_string(&qr_string).unwrap();
+                let chunk = decoder.process_qr_string_ref(&qr_string).unwrap();
+                assert_eq!(chunk, expected.as_chunk_ref());
+            }
+            assert_eq!(decoder.get_data().unwrap(), data);
+        }
+
+        // BBQr parts move into the assembler and are borrowed back
+        let bbqr = bbqr::BbqrEncoder::new(BbqrFileType::Binary, &data, 50).unwrap();
+        let mut decoder = bbqr::BbqrDecoder::new(BbqrFileType::Binary);
+        let index = decoder.receive(bbqr.part(1).unwrap()).unwrap();
+        let part = decoder.part(index).unwrap();
+        assert_eq!(part, bbqr::BbqrFrame::parse(bbqr.part(1).unwrap()).unwrap().data);
+        assert_eq!(decoder.part(0), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_snapshot() {
+        let data: Vec<u8> = (0..3000u32).map(|i| (i % 253) as u8).collect();
+        let encoder = FrameEncoder::new(&data, 400).unwrap();
+        let mut decoder = Decoder::new();
+        decoder.set_limits(DecoderLimits { max_payload_bytes: 4096, ..Default::default() });
+        for i in (0..encoder.chunk_count()).step_by(2) {
+            decoder.process_qr_string(&encoder.get_qr_string(i).unwrap()).unwrap();
+        }
+
+        // Chunks and headers survive JSON unchanged
+        let bytes = base45::decode(encoder.get_qr_string(1).unwrap()).unwrap();
+        let chunk = Chunk::from_bytes(&bytes).unwrap();
+        let header = FrameHeader::from_bytes(&bytes).unwrap();
+        assert_eq!(header, chunk.header());
+        assert_eq!(header.to_bytes()[..], bytes[..protocol::HEADER_SIZE]);
+        let json = serde_json::to_string(&chunk).unwrap();
+        assert_eq!(serde_json::from_str::<Chunk>(&json).unwrap(), chunk);
+        let json = serde_json::to_string(&header).unwrap();
+        assert_eq!(serde_json::from_str::<FrameHeader>(&json).unwrap(), header);
+
+        // A snapshot restored elsewhere picks up where the decoder left off
+        let snapshot = decoder.snapshot();
+        assert_eq!(snapshot.status, DecoderStatus::Receiving);
+        assert_eq!(snapshot.received, encoder.chunk_count().div_ceil(2));
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let restored: DecoderSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, snapshot);
+        let mut resumed = Decoder::from_snapshot(restored).unwrap();
+        assert_eq!(resumed.limits(), decoder.limits());
+        for i in (1..encoder.chunk_count()).step_by(2) {
+            resumed.process_qr_string(&encoder.get_qr_string(i).unwrap()).unwrap();
+        }
+        assert_eq!(resumed.get_data().unwrap(), data);
+
+        // Restoring re-checks the snapshot against its limits
+        let mut tight = decoder.snapshot();
+        tight.limits.max_payload_bytes = 100;
+        assert!(matches!(Decoder::from_snapshot(tight), Err(AirgapError::PayloadTooLarge(100))));
+        let mut foreign = decoder.snapshot();
+        foreign.chunks.insert(999, vec![0]);
+        assert!(matches!(Decoder::from_snapshot(foreign), Err(AirgapError::ChunkOutOfBounds(999))));
+        // A session with no chunk count would otherwise restore as complete
+        let mut hollow = decoder.snapshot();
+        hollow.total = 0;
+        hollow.chunks.clear();
+        let hollow: DecoderSnapshot = serde_json::from_str(&serde_json::to_string(&hollow).unwrap()).unwrap();
+        assert!(hollow.session_id.is_some());
+        assert!(matches!(Decoder::from_snapshot(hollow), Err(AirgapError::TooManyChunks(0))));
+
+        // Other formats report progress only
+        let format = FrameFormat::Ur(UrType::CryptoPsbt);
+        let ur = FrameEncoder::with_format(&data, 200, format).unwrap();
+        let mut decoder = Decoder::with_format(format);
+        decoder.process_qr_string(&ur.get_qr_string(0).unwrap()).unwrap();
+        let snapshot = decoder.snapshot();
+        assert!(snapshot.chunks.is_empty());
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(serde_json::from_str::<DecoderSnapshot>(&json).unwrap().format, format);
+        assert!(matches!(Decoder::from_snapshot(snapshot), Err(AirgapError::InvalidConfig(_))));
+    }
+
+    // Formats exercised by the property tests below
+    const PROPTEST_FORMATS: [FrameFormat; 3] = [
+        FrameFormat::Airgap,
+        FrameFormat::Ur(UrType::Bytes),
+        FrameFormat::Bbqr(BbqrFileType::Binary),
+    ];
+
+    // Scan order built from proptest indices: `swaps` shuffles the frames,
+    // `repeats` inserts duplicates and `dropped` leaves one frame out.
+    fn scan_order(
+        count: usize,
+        swaps: &[prop::sample::Index],
+        repeats: &[prop::sample::Index],
+        dropped: Option<prop::sample::Index>,
+    ) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..count).collect();
+        for (i, swap) in swaps.iter().enumerate() {
+            order.swap(i % count, swap.index(count));
+        }
+        if let Some(dropped) = dropped {
+            order.remove(dropped.index(count));
+        }
+        for repeat in repeats {
+            if !order.is_empty() {
+                let frame = order[repeat.index(order.len())];
+                order.insert(repeat.index(order.len() + 1), frame);
+            }
+        }
+        order
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(48))]
+
+        #[test]
+        fn prop_chunk_bytes_round_trip(
+            total_chunks in 1u16..,
+            index_seed: u16,
+            session_id: u32,
+            data in prop::collection::vec(any::<u8>(), 1..=protocol::MAX_CHUNK_SIZE),
+        ) {
+            let chunk = Chunk::new(total_chunks, index_seed % total_chunks, session_id, data).unwrap();
+            let parsed = Chunk::from_bytes(&chunk.to_bytes()).unwrap();
+            prop_assert_eq!(
+                (parsed.total_chunks, parsed.chunk_index, parsed.session_id, &parsed.data),
+                (chunk.total_chunks, chunk.chunk_index, chunk.session_id, &chunk.data)
+            );
+        }
+
+        #[test]
+        fn prop_round_trip_any_scan_order(
+            data in prop::collection::vec(any::<u8>(), 1..4096),
+            chunk_size in 32usize..400,
+            format in prop::sample::select(PROPTEST_FORMATS.to_vec()),
+            swaps in prop::collection::vec(any::<prop::sample::Index>(), 0..64),
+            repeats in prop::collection::vec(any::<prop::sample::Index>(), 0..8),
+        ) {
+            let encoder = FrameEncoder::with_format(&data, chunk_size, format).unwrap();
+            let mut decoder = Decoder::with_format(format);
+            for index in scan_order(encoder.chunk_count(), &swaps, &repeats, None) {
+                decoder.process_qr_string(&encoder.get_qr_string(index).unwrap()).unwrap();
+            }
+            prop_assert!(decoder.is_complete());
+            prop_assert_eq!(decoder.get_data().unwrap(), data);
+        }
+
+        #[test]
+        fn prop_dropped_frame_stays_incomplete(
+            data in prop::collection::vec(any::<u8>(), 64..4096),
+            chunk_size in 32usize..400,
+            format in prop::sample::select(PROPTEST_FORMATS.to_vec()),
+            swaps in prop::collection::vec(any::<prop::sample::Index>(), 0..64),
+            repeats in prop::collection::vec(any::<prop::sample::Index>(), 0..8),
+            dropped: prop::sample::Index,
+        ) {
+            let encoder = FrameEncoder::with_format(&data, chunk_size, format).unwrap();
+            let count = encoder.chunk_count();
+            prop_assume!(count > 1);
+
+            let mut decoder = Decoder::with_format(format);
+            for index in scan_order(count, &swaps, &repeats, Some(dropped)) {
+                decoder.process_qr_string(&encoder.get_qr_string(index).unwrap()).unwrap();
+            }
+            prop_assert!(!decoder.is_complete());
+            prop_assert!(decoder.get_data().is_err());
+        }
+
+        #[test]
+        fn prop_decoder_rejects_garbage_without_panicking(
+            frames in prop::collection::vec(".{0,300}", 1..8),
+            format in prop::sample::select(PROPTEST_FORMATS.to_vec()),
+        ) {
+            let mut decoder = Decoder::with_format(format);
+            for frame in &frames {
+                let _ = decoder.process_qr_string(frame);
+            }
+            let _ = decoder.get_data();
+        }
+    }
+}
@@ -0,0 +1,74 @@
+// src/limits - caps on what a decoder accepts from untrusted frames
+
+use crate::error::AirgapError;
+
+/// Resource limits enforced by `Decoder::process_qr_string`; 0 disables a
+/// limit. The default caps chunk count, payload size and frame length at
+/// values no real QR transfer reaches and leaves sessions unlimited;
+/// `unlimited` turns every cap off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecoderLimits {
+    /// Largest chunk count a frame may announce; checked before the frame
+    /// starts or joins a session.
+    pub max_total_chunks: usize,
+    /// Most bytes buffered for one session, which also caps the size of a
+    /// decompressed BBQr payload.
+    pub max_payload_bytes: usize,
+    /// Sessions the decoder will start between calls to `reset`, counting
+    /// sessions started after automatic expiry or `expire_stale`.
+    pub max_sessions: usize,
+    /// Longest QR string accepted, checked before any decoding.
+    pub max_frame_len: usize,
+}
+
+impl Default for DecoderLimits {
+    fn default() -> Self {
+        Self {
+            max_total_chunks: Self::DEFAULT_MAX_TOTAL_CHUNKS,
+            max_payload_bytes: Self::DEFAULT_MAX_PAYLOAD_BYTES,
+            max_sessions: 0,
+            max_frame_len: Self::DEFAULT_MAX_FRAME_LEN,
+        }
+    }
+}
+
+impl DecoderLimits {
+    pub const DEFAULT_MAX_TOTAL_CHUNKS: usize = 4096;
+    pub const DEFAULT_MAX_PAYLOAD_BYTES: usize = 16 << 20;
+    /// Alphanumeric capacity of a version 40 QR code at level L.
+    pub const DEFAULT_MAX_FRAME_LEN: usize = 4296;
+
+    /// No limits at all, for trusted input only.
+    pub const fn unlimited() -> Self {
+        Self { max_total_chunks: 0, max_payload_bytes: 0, max_sessions: 0, max_frame_len: 0 }
+    }
+
+    pub(crate) fn check_frame_len(&self, len: usize) -> Result<(), AirgapError> {
+        match self.max_frame_len {
+            max if max > 0 && len > max => Err(AirgapError::FrameTooLong(len, max)),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn check_total_chunks(&self, total: usize) -> Result<(), AirgapError> {
+        match self.max_total_chunks {
+            max if max > 0 && total > max => Err(AirgapError::ChunkLimitExceeded(total, max)),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn check_payload_bytes(&self, bytes: usize) -> Result<(), AirgapError> {
+        match self.max_payload_bytes {
+            max if max > 0 && bytes > max => Err(AirgapError::PayloadTooLarge(max)),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn check_sessions(&self, started: usize) -> Result<(), AirgapError> {
+        match self.max_sessions {
+            max if max > 0 && started >= max => Err(AirgapError::SessionLimitReached(max)),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+// src/observer - decoder progress notifications
+
+use crate::error::AirgapError;
+use crate::protocol::ChunkRef;
+
+/// Something that happened while a `Decoder` processed a frame.
+#[derive(Debug)]
+pub enum DecoderEvent<'a> {
+    /// The first frame of a transfer was accepted.
+    SessionStarted { session_id: u32, total_chunks: usize },
+    /// A frame added data the decoder did not have yet.
+    ChunkReceived { chunk: ChunkRef<'a>, received: usize, total: usize },
+    /// A frame was valid but added nothing new.
+    Duplicate { chunk: ChunkRef<'a> },
+    /// A frame belongs to another transfer (`SessionMismatch` or `MetadataMismatch`).
+    Conflict(&'a AirgapError),
+    /// Any other rejected frame.
+    Error(&'a AirgapError),
+    /// The payload can now be read with `get_data`.
+    Completed,
+}
+
+/// Receives `DecoderEvent`s synchronously, on the thread that called
+/// `Decoder::process_qr_string`, before that call returns.
+pub trait DecoderObserver: Send {
+    fn on_event(&mut self, event: &DecoderEvent);
+}
+
+impl<F> DecoderObserver for F
+where
+    F: FnMut(&DecoderEvent) + Send,
+{
+    fn on_event(&mut self, event: &DecoderEvent) {
+        self(event)
+    }
+}
@@ -0,0 +1,240 @@
+use alloc::vec::Vec;
+use crate::error::AirgapError;
+use crate::bbqr::BbqrFileType;
+use crate::ur::UrType;
+
+pub const MAGIC: [u8; 2] = [0x19, 0xF7];
+pub const VERSION: u8 = 1;
+pub const HEADER_SIZE: usize = 16;
+pub const MAX_CHUNK_SIZE: usize = 1920;
+pub const RECOMMENDED_MAX_CHUNK_SIZE: usize = 1100;
+pub const MIN_CHUNK_SIZE: usize = 16;
+
+/// Wire format of the frames making up a transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrameFormat {
+    /// Native chunks (`0x19F7` header) encoded as Base45.
+    #[default]
+    Airgap,
+    /// Blockchain Commons UR, fountain-coded when it spans several frames.
+    Ur(UrType),
+    /// Coinkite BBQr (`B$` header); the encoder compresses when it helps.
+    Bbqr(BbqrFileType),
+}
+
+/// The fixed header at the start of every native chunk, ahead of the payload
+/// and its trailing CRC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameHeader {
+    pub version: u8,
+    pub total_chunks: u16,
+    pub chunk_index: u16,
+    pub session_id: u32,
+    /// Payload length in bytes.
+    pub data_len: u16,
+}
+
+impl FrameHeader {
+    /// Parse the header at the start of `bytes`. Only the header itself is
+    /// checked; `Chunk::from_bytes` also verifies the payload and CRC.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, AirgapError> {
+        if bytes.len() < HEADER_SIZE {
+            return Err(AirgapError::EncodingError(
+                "Chunk too small".into()
+            ));
+        }
+
+        if bytes[0..2] != MAGIC {
+            return Err(AirgapError::InvalidMagic);
+        }
+
+        let version = bytes[2];
+        if version != VERSION {
+            return Err(AirgapError::UnsupportedVersion(version));
+        }
+
+        let total_chunks = u16::from_be_bytes([bytes[3], bytes[4]]);
+        let chunk_index = u16::from_be_bytes([bytes[5], bytes[6]]);
+        let session_id = u32::from_be_bytes([
+            bytes[7], bytes[8], bytes[9], bytes[10]
+        ]);
+        let data_len = u16::from_be_bytes([bytes[11], bytes[12]]);
+
+        if chunk_index >= total_chunks {
+            return Err(AirgapError::ChunkOutOfBounds(chunk_index));
+        }
+
+        // Validate data length
+        if data_len as usize > MAX_CHUNK_SIZE {
+            return Err(AirgapError::ChunkSizeTooLarge(
+                data_len as usize,
+                MAX_CHUNK_SIZE,
+            ));
+        }
+
+        Ok(Self {
+            version,
+            total_chunks,
+            chunk_index,
+            session_id,
+            data_len,
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut bytes = [0; HEADER_SIZE];
+        bytes[0..2].copy_from_slice(&MAGIC);
+        bytes[2] = self.version;
+        bytes[3..5].copy_from_slice(&self.total_chunks.to_be_bytes());
+        bytes[5..7].copy_from_slice(&self.chunk_index.to_be_bytes());
+        bytes[7..11].copy_from_slice(&self.session_id.to_be_bytes());
+        bytes[11..13].copy_from_slice(&self.data_len.to_be_bytes());
+        // bytes 13..16 are reserved and stay zero
+        bytes
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chunk {
+    pub total_chunks: u16,
+    pub chunk_index: u16,
+    pub session_id: u32,
+    pub data: Vec<u8>,
+}
+
+impl Chunk {
+    pub fn new(
+        total_chunks: u16,
+        chunk_index: u16,
+        session_id: u32,
+        data: Vec<u8>,
+    ) -> Result<Self, AirgapError> {
+        // Validate chunk data size
+        if data.len() > MAX_CHUNK_SIZE {
+            return Err(AirgapError::ChunkSizeTooLarge(
+                data.len(),
+                MAX_CHUNK_SIZE,
+            ));
+        }
+
+        if data.is_empty() {
+            return Err(AirgapError::ChunkSizeTooSmall(0, 1));
+        }
+
+        Ok(Self {
+            total_chunks,
+            chunk_index,
+            session_id,
+            data,
+        })
+    }
+
+    /// The header written in front of this chunk's payload.
+    pub fn header(&self) -> FrameHeader {
+        self.as_chunk_ref().header()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + self.data.len() + 4);
+
+        bytes.extend_from_slice(&self.header().to_bytes());
+
+        bytes.extend_from_slice(&self.data);
+
+        let crc = crc32fast::hash(&bytes);
+        bytes.extend_from_slice(&crc.to_be_bytes());
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, AirgapError> {
+        ChunkRef::from_bytes(bytes).map(|chunk| chunk.to_chunk())
+    }
+
+    pub fn as_chunk_ref(&self) -> ChunkRef<'_> {
+        ChunkRef {
+            total_chunks: self.total_chunks,
+            chunk_index: self.chunk_index,
+            session_id: self.session_id,
+            data: &self.data,
+        }
+    }
+}
+
+/// A chunk whose payload borrows from the buffer it was parsed from, or
+/// from a decoder's storage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkRef<'a> {
+    pub total_chunks: u16,
+    pub chunk_index: u16,
+    pub session_id: u32,
+    pub data: &'a [u8],
+}
+
+impl<'a> ChunkRef<'a> {
+    /// Validate a serialized chunk in place, CRC included, without copying
+    /// the payload.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, AirgapError> {
+
+        if bytes.len() < HEADER_SIZE + 4 {
+            return Err(AirgapError::EncodingError(
+                "Chunk too small".into()
+            ));
+        }
+
+        let header = FrameHeader::from_bytes(bytes)?;
+
+        let data_start = HEADER_SIZE;
+        let data_end = data_start + header.data_len as usize;
+
+        if bytes.len() < data_end + 4 {
+            return Err(AirgapError::EncodingError(
+                "Chunk truncated".into()
+            ));
+        }
+
+        // Verify CRC
+        let stored_crc = u32::from_be_bytes([
+            bytes[data_end],
+            bytes[data_end + 1],
+            bytes[data_end + 2],
+            bytes[data_end + 3],
+        ]);
+        let calculated_crc = crc32fast::hash(&bytes[..data_end]);
+
+        if stored_crc != calculated_crc {
+            return Err(AirgapError::CrcMismatch);
+        }
+
+        Ok(Self {
+            total_chunks: header.total_chunks,
+            chunk_index: header.chunk_index,
+            session_id: header.session_id,
+            data: &bytes[data_start..data_end],
+        })
+    }
+
+    /// The header written in front of this chunk's payload.
+    pub fn header(&self) -> FrameHeader {
+        FrameHeader {
+            version: VERSION,
+            total_chunks: self.total_chunks,
+            chunk_index: self.chunk_index,
+            session_id: self.session_id,
+            data_len: self.data.len() as u16,
+        }
+    }
+
+    /// Copy the payload into an owned `Chunk`.
+    pub fn to_chunk(&self) -> Chunk {
+        Chunk {
+            total_chunks: self.total_chunks,
+            chunk_index: self.chunk_index,
+            session_id: self.session_id,
+            data: self.data.to_vec(),
+        }
+    }
+}
@@ -0,0 +1,174 @@
+// src/shared_decoder - a decoder that can be fed and polled from different threads
+
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use crate::clock::Clock;
+use crate::decoder::{Decoder, DecoderStatus, DecoderTimeouts};
+use crate::limits::DecoderLimits;
+use crate::error::AirgapError;
+use crate::observer::DecoderObserver;
+use crate::protocol::{Chunk, FrameFormat};
+
+// `session` holds the session id plus one, so that 0 can mean "no session yet"
+const NO_SESSION: u64 = 0;
+
+/// A `Decoder` behind a mutex, safe to share between threads.
+///
+/// Every method takes `&self`. Calls that change or read the reassembly
+/// state (`process_qr_string`, `get_data`, `reset`, observer changes) are
+/// serialized by the lock. Progress queries (`is_complete`, `received_count`,
+/// `total_count`, `session_id`) read a snapshot published after each change
+/// and never block, so a UI thread can poll while a camera thread is busy
+/// decoding. The snapshot is only updated once a frame has been fully
+/// processed; once `is_complete` returns true the counts are final.
+///
+/// Observers run on the thread calling `process_qr_string` while the lock is
+/// held; they may query progress but must not feed or reset the same decoder.
+pub struct SharedDecoder {
+    decoder: Mutex<Decoder>,
+    complete: AtomicBool,
+    received: AtomicUsize,
+    total: AtomicUsize,
+    session: AtomicU64,
+}
+
+impl SharedDecoder {
+    pub fn new() -> Self {
+        Self::from_decoder(Decoder::new())
+    }
+
+    /// Create a decoder accepting frames in `format`.
+    pub fn with_format(format: FrameFormat) -> Self {
+        Self::from_decoder(Decoder::with_format(format))
+    }
+
+    pub fn from_decoder(decoder: Decoder) -> Self {
+        let shared = Self {
+            decoder: Mutex::new(decoder),
+            complete: AtomicBool::new(false),
+            received: AtomicUsize::new(0),
+            total: AtomicUsize::new(0),
+            session: AtomicU64::new(NO_SESSION),
+        };
+        shared.publish(&shared.lock());
+        shared
+    }
+
+    pub fn format(&self) -> FrameFormat {
+        self.lock().format()
+    }
+
+    /// Process a scanned QR code string; see `Decoder::process_qr_string`.
+    pub fn process_qr_string(&self, qr_data: &str) -> Result<Chunk, AirgapError> {
+        let mut decoder = self.lock();
+        let result = decoder.process_qr_string(qr_data);
+        self.publish(&decoder);
+        result
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.complete.load(Ordering::Acquire)
+    }
+
+    pub fn session_id(&self) -> Option<u32> {
+        match self.session.load(Ordering::Acquire) {
+            NO_SESSION => None,
+            session => Some((session - 1) as u32),
+        }
+    }
+
+    pub fn received_count(&self) -> usize {
+        self.received.load(Ordering::Acquire)
+    }
+
+    pub fn total_count(&self) -> usize {
+        self.total.load(Ordering::Acquire)
+    }
+
+    /// Get reassembled data
+    pub fn get_data(&self) -> Result<Vec<u8>, AirgapError> {
+        self.lock().get_data()
+    }
+
+    pub fn reset(&self) {
+        let mut decoder = self.lock();
+        decoder.reset();
+        self.publish(&decoder);
+    }
+
+    pub fn set_timeouts(&self, timeouts: DecoderTimeouts) {
+        self.lock().set_timeouts(timeouts);
+    }
+
+    pub fn timeouts(&self) -> DecoderTimeouts {
+        self.lock().timeouts()
+    }
+
+    pub fn set_limits(&self, limits: DecoderLimits) {
+        self.lock().set_limits(limits);
+    }
+
+    pub fn limits(&self) -> DecoderLimits {
+        self.lock().limits()
+    }
+
+    pub fn set_clock(&self, clock: Box<dyn Clock>) {
+        self.lock().set_clock(clock);
+    }
+
+    /// Unlike the progress queries this depends on the time, so it takes the lock.
+    pub fn status(&self) -> DecoderStatus {
+        self.lock().status()
+    }
+
+    /// Reset the session if it is stalled or expired; returns whether it did.
+    pub fn expire_stale(&self) -> bool {
+        let mut decoder = self.lock();
+        let expired = decoder.expire_stale();
+        self.publish(&decoder);
+        expired
+    }
+
+    /// Notify `observer` of every event from now on, replacing any previous one.
+    pub fn set_observer(&self, observer: Box<dyn DecoderObserver>) {
+        self.lock().set_observer(observer);
+    }
+
+    pub fn clear_observer(&self) {
+        self.lock().clear_observer();
+    }
+
+    /// Take back the inner decoder.
+    pub fn into_inner(self) -> Decoder {
+        self.decoder.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // A panicking observer leaves the decoder itself consistent, so a
+    // poisoned lock is still safe to use.
+    fn lock(&self) -> MutexGuard<'_, Decoder> {
+        self.decoder.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // Writers hold the lock, so the individual stores cannot interleave with
+    // another writer's. `complete` is stored last: a reader that sees it set
+    // also sees the final counts.
+    fn publish(&self, decoder: &Decoder) {
+        let session = decoder.session_id().map_or(NO_SESSION, |id| id as u64 + 1);
+        self.session.store(session, Ordering::Release);
+        self.total.store(decoder.total_count(), Ordering::Release);
+        self.received.store(decoder.received_count(), Ordering::Release);
+        self.complete.store(decoder.is_complete(), Ordering::Release);
+    }
+}
+
+impl Default for SharedDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<Decoder> for SharedDecoder {
+    fn from(decoder: Decoder) -> Self {
+        Self::from_decoder(decoder)
+    }
+}
@@ -0,0 +1,252 @@
+// src/stream - futures adapters for async camera and display pipelines (`async` feature)
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+use futures_core::Stream;
+use futures_sink::Sink;
+use futures_timer::Delay;
+use crate::batch::CancelToken;
+use crate::decoder::Decoder;
+use crate::frame_encoder::FrameEncoder;
+use crate::error::AirgapError;
+use crate::shared_decoder::SharedDecoder;
+
+/// Stream of an encoder's QR strings, from `FrameEncoder::frame_stream`
+/// or `airgap_render::Encoder::frame_stream`.
+///
+/// Frames are produced as fast as they are polled; pace them with the
+/// runtime's timer when displaying. The stream ends after the last chunk
+/// unless `looping` is set, and ends early once its cancel token fires.
+pub struct FrameStream<'a> {
+    encoder: &'a FrameEncoder,
+    next: usize,
+    looping: bool,
+    cancel: Option<CancelToken>,
+}
+
+impl<'a> FrameStream<'a> {
+    pub(crate) fn new(encoder: &'a FrameEncoder) -> Self {
+        Self { encoder, next: 0, looping: false, cancel: None }
+    }
+
+    /// Start over from the first chunk after the last one, forever.
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    /// End the stream once `cancel` is cancelled.
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+}
+
+impl Stream for FrameStream<'_> {
+    type Item = Result<String, AirgapError>;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.cancel.as_ref().is_some_and(CancelToken::is_cancelled) {
+            return Poll::Ready(None);
+        }
+        if this.next >= this.encoder.chunk_count() {
+            if !this.looping {
+                return Poll::Ready(None);
+            }
+            this.next = 0;
+        }
+        let frame = this.encoder.get_qr_string(this.next);
+        this.next += 1;
+        Poll::Ready(Some(frame))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.looping {
+            return (usize::MAX, None);
+        }
+        let remaining = self.encoder.chunk_count().saturating_sub(self.next);
+        (0, Some(remaining))
+    }
+}
+
+// State shared by a `DecodeSink` and its `DecodePayload`
+struct DecodeState {
+    decoder: SharedDecoder,
+    // No more frames will come: the sink was closed or dropped
+    closed: AtomicBool,
+    // The payload future has resolved or been dropped
+    finished: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl DecodeState {
+    fn wake(&self) {
+        let waker = self.waker.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    fn register(&self, waker: &Waker) {
+        let mut slot = self.waker.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match slot.as_ref() {
+            Some(current) if current.will_wake(waker) => {}
+            _ => *slot = Some(waker.clone()),
+        }
+    }
+}
+
+/// Split `decoder` into a sink for scanned QR strings and a future resolving
+/// to the reassembled payload.
+///
+/// Frames that fail to decode are skipped, as a camera routinely sees
+/// unrelated or damaged codes; install an observer on `decoder` to see them.
+/// The future resolves once the payload is complete or, if the sink is
+/// closed or dropped first, with the decoder's incomplete-data error.
+pub fn decode_sink(decoder: Decoder) -> (DecodeSink, DecodePayload) {
+    let state = Arc::new(DecodeState {
+        decoder: SharedDecoder::from_decoder(decoder),
+        closed: AtomicBool::new(false),
+        finished: AtomicBool::new(false),
+        waker: Mutex::new(None),
+    });
+    let sink = DecodeSink { state: state.clone() };
+    let payload = DecodePayload { state, timeout: None, cancel: None };
+    (sink, payload)
+}
+
+/// Feeds scanned QR strings to the decoder behind a `DecodePayload`.
+///
+/// Once the payload future has finished — successfully, by timeout or
+/// cancellation, or by being dropped — the sink fails with `Cancelled`,
+/// which also stops a `forward` feeding it.
+pub struct DecodeSink {
+    state: Arc<DecodeState>,
+}
+
+impl DecodeSink {
+    pub fn received_count(&self) -> usize {
+        self.state.decoder.received_count()
+    }
+
+    pub fn total_count(&self) -> usize {
+        self.state.decoder.total_count()
+    }
+
+    fn check_open(&self) -> Result<(), AirgapError> {
+        if self.state.finished.load(Ordering::Acquire) {
+            return Err(AirgapError::Cancelled);
+        }
+        Ok(())
+    }
+}
+
+impl Sink<String> for DecodeSink {
+    type Error = AirgapError;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(self.check_open())
+    }
+
+    fn start_send(self: Pin<&mut Self>, frame: String) -> Result<(), Self::Error> {
+        self.check_open()?;
+        let decoder = &self.state.decoder;
+        if decoder.is_complete() {
+            return Ok(());
+        }
+        // Undecodable frames are expected from a live camera
+        let _ = decoder.process_qr_string(&frame);
+        if decoder.is_complete() {
+            self.state.wake();
+        }
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(self.check_open())
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.state.closed.store(true, Ordering::Release);
+        self.state.wake();
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Drop for DecodeSink {
+    fn drop(&mut self) {
+        self.state.closed.store(true, Ordering::Release);
+        self.state.wake();
+    }
+}
+
+/// Resolves to the payload fed through the matching `DecodeSink`.
+pub struct DecodePayload {
+    state: Arc<DecodeState>,
+    timeout: Option<Delay>,
+    cancel: Option<CancelToken>,
+}
+
+impl DecodePayload {
+    /// Fail with `TimedOut` if the payload is not complete within `timeout`,
+    /// counted from this call.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(Delay::new(timeout));
+        self
+    }
+
+    /// Fail with `Cancelled` once `cancel` is cancelled.
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    fn finish(&self, result: Result<Vec<u8>, AirgapError>) -> Poll<Result<Vec<u8>, AirgapError>> {
+        self.state.finished.store(true, Ordering::Release);
+        Poll::Ready(result)
+    }
+}
+
+impl Future for DecodePayload {
+    type Output = Result<Vec<u8>, AirgapError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if this.state.finished.load(Ordering::Acquire) {
+            return Poll::Ready(Err(AirgapError::Cancelled));
+        }
+
+        // Register before checking, so a frame completing the payload in
+        // between still wakes this task
+        this.state.register(cx.waker());
+        let decoder = &this.state.decoder;
+        if decoder.is_complete() || this.state.closed.load(Ordering::Acquire) {
+            let result = decoder.get_data();
+            return this.finish(result);
+        }
+
+        if let Some(cancel) = &this.cancel {
+            cancel.register(cx.waker());
+            if cancel.is_cancelled() {
+                return this.finish(Err(AirgapError::Cancelled));
+            }
+        }
+        if let Some(timeout) = this.timeout.as_mut()
+            && Pin::new(timeout).poll(cx).is_ready()
+        {
+            return this.finish(Err(AirgapError::TimedOut));
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for DecodePayload {
+    fn drop(&mut self) {
+        self.state.finished.store(true, Ordering::Release);
+    }
+}
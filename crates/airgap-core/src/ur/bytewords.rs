@@ -0,0 +1,80 @@
+// src/ur/bytewords - Bytewords minimal encoding (BCR-2020-012)
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::error::AirgapError;
+
+const WORDS: [&str; 256] = [
+    "able", "acid", "also", "apex", "aqua", "arch", "atom", "aunt", "away", "axis", "back", "bald",
+    "barn", "belt", "beta", "bias", "blue", "body", "brag", "brew", "bulb", "buzz", "calm", "cash",
+    "cats", "chef", "city", "claw", "code", "cola", "cook", "cost", "crux", "curl", "cusp", "cyan",
+    "dark", "data", "days", "deli", "dice", "diet", "door", "down", "draw", "drop", "drum", "dull",
+    "duty", "each", "easy", "echo", "edge", "epic", "even", "exam", "exit", "eyes", "fact", "fair",
+    "fern", "figs", "film", "fish", "fizz", "flap", "flew", "flux", "foxy", "free", "frog", "fuel",
+    "fund", "gala", "game", "gear", "gems", "gift", "girl", "glow", "good", "gray", "grim", "guru",
+    "gush", "gyro", "half", "hang", "hard", "hawk", "heat", "help", "high", "hill", "holy", "hope",
+    "horn", "huts", "iced", "idea", "idle", "inch", "inky", "into", "iris", "iron", "item", "jade",
+    "jazz", "join", "jolt", "jowl", "judo", "jugs", "jump", "junk", "jury", "keep", "keno", "kept",
+    "keys", "kick", "kiln", "king", "kite", "kiwi", "knob", "lamb", "lava", "lazy", "leaf", "legs",
+    "liar", "limp", "lion", "list", "logo", "loud", "love", "luau", "luck", "lung", "main", "many",
+    "math", "maze", "memo", "menu", "meow", "mild", "mint", "miss", "monk", "nail", "navy", "need",
+    "news", "next", "noon", "note", "numb", "obey", "oboe", "omit", "onyx", "open", "oval", "owls",
+    "paid", "part", "peck", "play", "plus", "poem", "pool", "pose", "puff", "puma", "purr", "quad",
+    "quiz", "race", "ramp", "real", "redo", "rich", "road", "rock", "roof", "ruby", "ruin", "runs",
+    "rust", "safe", "saga", "scar", "sets", "silk", "skew", "slot", "soap", "solo", "song", "stub",
+    "surf", "swan", "taco", "task", "taxi", "tent", "tied", "time", "tiny", "toil", "tomb", "toys",
+    "trip", "tuna", "twin", "ugly", "undo", "unit", "urge", "user", "vast", "very", "veto", "vial",
+    "vibe", "view", "visa", "void", "vows", "wall", "wand", "warm", "wasp", "wave", "waxy", "webs",
+    "what", "when", "whiz", "wolf", "work", "yank", "yawn", "yell", "yoga", "yurt", "zaps", "zero",
+    "zest", "zinc", "zone", "zoom",
+];
+
+/// Encode `data` as minimal bytewords (first and last letter of each word)
+/// followed by the CRC32 of the data.
+pub fn encode_minimal(data: &[u8]) -> String {
+    let crc = crc32fast::hash(data).to_be_bytes();
+    let mut out = String::with_capacity((data.len() + 4) * 2);
+    for &byte in data.iter().chain(crc.iter()) {
+        let word = WORDS[byte as usize].as_bytes();
+        out.push(word[0] as char);
+        out.push(word[3] as char);
+    }
+    out
+}
+
+/// Decode minimal bytewords, verifying and stripping the trailing CRC32.
+pub fn decode_minimal(text: &str) -> Result<Vec<u8>, AirgapError> {
+    let text = text.as_bytes();
+    if !text.len().is_multiple_of(2) {
+        return Err(AirgapError::InvalidUr("odd bytewords length".into()));
+    }
+
+    let mut bytes = Vec::with_capacity(text.len() / 2);
+    for pair in text.chunks_exact(2) {
+        let (first, last) = (pair[0].to_ascii_lowercase(), pair[1].to_ascii_lowercase());
+        let value = WORDS
+            .iter()
+            .position(|w| w.as_bytes()[0] == first && w.as_bytes()[3] == last)
+            .ok_or_else(|| AirgapError::InvalidUr("invalid byteword".into()))?;
+        bytes.push(value as u8);
+    }
+
+    if bytes.len() < 5 {
+        return Err(AirgapError::InvalidUr("bytewords too short".into()));
+    }
+
+    let body_len = bytes.len() - 4;
+    let stored_crc = u32::from_be_bytes([
+        bytes[body_len],
+        bytes[body_len + 1],
+        bytes[body_len + 2],
+        bytes[body_len + 3],
+    ]);
+    bytes.truncate(body_len);
+
+    if crc32fast::hash(&bytes) != stored_crc {
+        return Err(AirgapError::CrcMismatch);
+    }
+
+    Ok(bytes)
+}
@@ -0,0 +1,96 @@
+// src/ur/cbor - the small subset of CBOR (RFC 8949) needed by UR
+
+use alloc::vec::Vec;
+use crate::error::AirgapError;
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_ARRAY: u8 = 4;
+
+pub fn write_header(out: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+    if value < 24 {
+        out.push(major | value as u8);
+    } else if value <= u8::MAX as u64 {
+        out.push(major | 24);
+        out.push(value as u8);
+    } else if value <= u16::MAX as u64 {
+        out.push(major | 25);
+        out.extend_from_slice(&(value as u16).to_be_bytes());
+    } else if value <= u32::MAX as u64 {
+        out.push(major | 26);
+        out.extend_from_slice(&(value as u32).to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+pub fn write_unsigned(out: &mut Vec<u8>, value: u64) {
+    write_header(out, MAJOR_UNSIGNED, value);
+}
+
+pub fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_header(out, MAJOR_BYTES, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+pub fn write_array_header(out: &mut Vec<u8>, len: usize) {
+    write_header(out, MAJOR_ARRAY, len as u64);
+}
+
+/// Sequential reader over a CBOR buffer.
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], AirgapError> {
+        let end = self.pos.checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| AirgapError::InvalidUr("truncated CBOR".into()))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn read_header(&mut self, expected_major: u8) -> Result<u64, AirgapError> {
+        let initial = self.take(1)?[0];
+        if initial >> 5 != expected_major {
+            return Err(AirgapError::InvalidUr("unexpected CBOR type".into()));
+        }
+        let value = match initial & 0x1F {
+            n @ 0..=23 => n as u64,
+            24 => self.take(1)?[0] as u64,
+            25 => u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as u64,
+            26 => u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as u64,
+            27 => u64::from_be_bytes(self.take(8)?.try_into().unwrap()),
+            _ => return Err(AirgapError::InvalidUr("unsupported CBOR length".into())),
+        };
+        Ok(value)
+    }
+
+    pub fn read_unsigned(&mut self) -> Result<u64, AirgapError> {
+        self.read_header(MAJOR_UNSIGNED)
+    }
+
+    pub fn read_bytes(&mut self) -> Result<&'a [u8], AirgapError> {
+        let len = self.read_header(MAJOR_BYTES)?;
+        let len = usize::try_from(len)
+            .map_err(|_| AirgapError::InvalidUr("CBOR byte string too long".into()))?;
+        self.take(len)
+    }
+
+    pub fn read_array_header(&mut self) -> Result<u64, AirgapError> {
+        self.read_header(MAJOR_ARRAY)
+    }
+}
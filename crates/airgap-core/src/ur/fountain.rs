@@ -0,0 +1,414 @@
+// src/ur/fountain - Luby-transform fountain code used by multipart UR (BCR-2024-001)
+
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+use alloc::vec;
+use alloc::vec::Vec;
+use sha2::{Digest, Sha256};
+use crate::error::AirgapError;
+use super::cbor;
+
+pub const MIN_FRAGMENT_LEN: usize = 10;
+
+// ============================================================================
+// PRNG
+// ============================================================================
+
+/// Xoshiro256** seeded from the SHA-256 of the input, as specified by UR.
+struct Xoshiro256 {
+    s: [u64; 4],
+}
+
+impl Xoshiro256 {
+    fn from_seed_bytes(seed: &[u8]) -> Self {
+        let hash = Sha256::digest(seed);
+        let mut s = [0u64; 4];
+        for (i, word) in s.iter_mut().enumerate() {
+            *word = u64::from_be_bytes(hash[i * 8..i * 8 + 8].try_into().unwrap());
+        }
+        Self { s }
+    }
+
+    fn next(&mut self) -> u64 {
+        let result = self.s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.s[1] << 17;
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+        result
+    }
+
+    fn next_double(&mut self) -> f64 {
+        self.next() as f64 / (u64::MAX as f64 + 1.0)
+    }
+
+    fn next_int(&mut self, low: u64, high: u64) -> u64 {
+        (self.next_double() * (high - low + 1) as f64) as u64 + low
+    }
+
+    fn shuffled(&mut self, mut items: Vec<usize>) -> Vec<usize> {
+        let mut shuffled = Vec::with_capacity(items.len());
+        while !items.is_empty() {
+            let index = self.next_int(0, (items.len() - 1) as u64) as usize;
+            shuffled.push(items.remove(index));
+        }
+        shuffled
+    }
+
+    /// Sample a fragment degree in `1..=seq_len` with probability ∝ 1/degree,
+    /// using Vose's alias method exactly as the reference implementation does.
+    fn choose_degree(&mut self, seq_len: usize) -> usize {
+        let total: f64 = (1..=seq_len).map(|i| 1.0 / i as f64).sum();
+        let mut weights: Vec<f64> = (1..=seq_len)
+            .map(|i| (1.0 / i as f64) * seq_len as f64 / total)
+            .collect();
+
+        let mut small = Vec::new();
+        let mut large = Vec::new();
+        for j in (0..seq_len).rev() {
+            if weights[j] < 1.0 { small.push(j) } else { large.push(j) }
+        }
+
+        let mut probs = vec![0.0; seq_len];
+        let mut aliases = vec![0usize; seq_len];
+        while let (Some(&a), Some(&g)) = (small.last(), large.last()) {
+            small.pop();
+            large.pop();
+            probs[a] = weights[a];
+            aliases[a] = g;
+            weights[g] += weights[a] - 1.0;
+            if weights[g] < 1.0 { small.push(g) } else { large.push(g) }
+        }
+        for g in large {
+            probs[g] = 1.0;
+        }
+        for a in small {
+            probs[a] = 1.0;
+        }
+
+        let r1 = self.next_double();
+        let r2 = self.next_double();
+        let i = (seq_len as f64 * r1) as usize;
+        let sample = if r2 < probs[i] { i } else { aliases[i] };
+        sample + 1
+    }
+}
+
+/// Indexes of the fragments XOR-ed together in part `seq_num` (1-based).
+pub fn choose_fragments(seq_num: u32, seq_len: usize, checksum: u32) -> BTreeSet<usize> {
+    if seq_num as usize <= seq_len {
+        return BTreeSet::from([seq_num as usize - 1]);
+    }
+
+    let mut seed = Vec::with_capacity(8);
+    seed.extend_from_slice(&seq_num.to_be_bytes());
+    seed.extend_from_slice(&checksum.to_be_bytes());
+    let mut rng = Xoshiro256::from_seed_bytes(&seed);
+
+    let degree = rng.choose_degree(seq_len);
+    let mut indexes = rng.shuffled((0..seq_len).collect());
+    indexes.truncate(degree);
+    indexes.into_iter().collect()
+}
+
+fn xor_into(target: &mut [u8], other: &[u8]) {
+    for (t, o) in target.iter_mut().zip(other) {
+        *t ^= o;
+    }
+}
+
+// ============================================================================
+// PART
+// ============================================================================
+
+/// One fountain-coded part as carried in a multipart UR.
+#[derive(Debug, Clone)]
+pub struct Part {
+    pub seq_num: u32,
+    pub seq_len: usize,
+    pub message_len: usize,
+    pub checksum: u32,
+    pub data: Vec<u8>,
+}
+
+impl Part {
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.data.len() + 24);
+        cbor::write_array_header(&mut out, 5);
+        cbor::write_unsigned(&mut out, self.seq_num as u64);
+        cbor::write_unsigned(&mut out, self.seq_len as u64);
+        cbor::write_unsigned(&mut out, self.message_len as u64);
+        cbor::write_unsigned(&mut out, self.checksum as u64);
+        cbor::write_bytes(&mut out, &self.data);
+        out
+    }
+
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, AirgapError> {
+        let mut reader = cbor::Reader::new(bytes);
+        if reader.read_array_header()? != 5 {
+            return Err(AirgapError::InvalidUr("malformed multipart header".into()));
+        }
+        let seq_num = reader.read_unsigned()?;
+        let seq_len = reader.read_unsigned()?;
+        let message_len = reader.read_unsigned()?;
+        let checksum = reader.read_unsigned()?;
+        let data = reader.read_bytes()?.to_vec();
+
+        if !reader.is_empty() {
+            return Err(AirgapError::InvalidUr("trailing bytes after part".into()));
+        }
+
+        let seq_num = u32::try_from(seq_num)
+            .map_err(|_| AirgapError::InvalidUr("sequence number out of range".into()))?;
+        let checksum = u32::try_from(checksum)
+            .map_err(|_| AirgapError::InvalidUr("checksum out of range".into()))?;
+
+        if seq_num == 0 || seq_len == 0 || data.is_empty() {
+            return Err(AirgapError::InvalidUr("empty multipart part".into()));
+        }
+
+        Ok(Self {
+            seq_num,
+            seq_len: seq_len as usize,
+            message_len: message_len as usize,
+            checksum,
+            data,
+        })
+    }
+}
+
+// ============================================================================
+// ENCODER
+// ============================================================================
+
+/// Splits a message into equal fragments and produces fountain parts.
+#[derive(Debug, Clone)]
+pub struct FountainEncoder {
+    fragments: Vec<Vec<u8>>,
+    message_len: usize,
+    checksum: u32,
+}
+
+impl FountainEncoder {
+    pub fn new(message: &[u8], max_fragment_len: usize) -> Self {
+        let fragment_len = nominal_fragment_len(message.len(), max_fragment_len);
+        let fragments = message
+            .chunks(fragment_len)
+            .map(|fragment| {
+                let mut fragment = fragment.to_vec();
+                fragment.resize(fragment_len, 0);
+                fragment
+            })
+            .collect();
+
+        Self {
+            fragments,
+            message_len: message.len(),
+            checksum: crc32fast::hash(message),
+        }
+    }
+
+    pub fn seq_len(&self) -> usize {
+        self.fragments.len()
+    }
+
+    pub fn checksum(&self) -> u32 {
+        self.checksum
+    }
+
+    /// Part `seq_num` (1-based). Parts beyond `seq_len` are fountain mixes.
+    pub fn part(&self, seq_num: u32) -> Part {
+        let indexes = choose_fragments(seq_num, self.seq_len(), self.checksum);
+        let mut data = vec![0u8; self.fragments[0].len()];
+        for index in indexes {
+            xor_into(&mut data, &self.fragments[index]);
+        }
+
+        Part {
+            seq_num,
+            seq_len: self.seq_len(),
+            message_len: self.message_len,
+            checksum: self.checksum,
+            data,
+        }
+    }
+}
+
+fn nominal_fragment_len(message_len: usize, max_fragment_len: usize) -> usize {
+    let max_fragment_count = (message_len / MIN_FRAGMENT_LEN).max(1);
+    let mut fragment_len = message_len;
+    for fragment_count in 1..=max_fragment_count {
+        fragment_len = message_len.div_ceil(fragment_count);
+        if fragment_len <= max_fragment_len {
+            break;
+        }
+    }
+    fragment_len
+}
+
+// ============================================================================
+// DECODER
+// ============================================================================
+
+/// Collects fountain parts and peels mixed parts until the message is whole.
+/// At most `seq_len` unresolved mixed parts are kept, the oldest dropped
+/// first, so buffered data stays within about twice the message length.
+#[derive(Debug, Default)]
+pub struct FountainDecoder {
+    expected: Option<(usize, usize, u32, usize)>,
+    simple: BTreeMap<usize, Vec<u8>>,
+    mixed: VecDeque<(BTreeSet<usize>, Vec<u8>)>,
+    message: Option<Vec<u8>>,
+}
+
+impl FountainDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.message.is_some()
+    }
+
+    pub fn message(&self) -> Option<&[u8]> {
+        self.message.as_deref()
+    }
+
+    pub fn checksum(&self) -> Option<u32> {
+        self.expected.map(|(_, _, checksum, _)| checksum)
+    }
+
+    pub fn seq_len(&self) -> usize {
+        self.expected.map(|(seq_len, _, _, _)| seq_len).unwrap_or(0)
+    }
+
+    pub fn recovered_count(&self) -> usize {
+        if self.is_complete() { self.seq_len() } else { self.simple.len() }
+    }
+
+    /// Fragment bytes held for an incomplete message, simple and mixed.
+    #[cfg(test)]
+    pub fn buffered_bytes(&self) -> usize {
+        self.simple.values().chain(self.mixed.iter().map(|(_, data)| data)).map(Vec::len).sum()
+    }
+
+    pub fn receive(&mut self, part: &Part) -> Result<(), AirgapError> {
+        let fragment_len = part.data.len();
+        match self.expected {
+            None => {
+                // The sender splits into `ceil(message_len / fragment_len)` parts
+                if part.message_len == 0 || fragment_len == 0 || part.seq_len != part.message_len.div_ceil(fragment_len) {
+                    return Err(AirgapError::InvalidUr("inconsistent part lengths".into()));
+                }
+                self.expected = Some((part.seq_len, part.message_len, part.checksum, fragment_len));
+            }
+            Some((seq_len, message_len, checksum, expected_fragment_len)) => {
+                if checksum != part.checksum {
+                    return Err(AirgapError::SessionMismatch);
+                }
+                if seq_len != part.seq_len
+                    || message_len != part.message_len
+                    || expected_fragment_len != fragment_len
+                {
+                    return Err(AirgapError::MetadataMismatch);
+                }
+            }
+        }
+
+        if self.is_complete() {
+            return Ok(());
+        }
+
+        let indexes = choose_fragments(part.seq_num, part.seq_len, part.checksum);
+        let mut queue = vec![(indexes, part.data.clone())];
+
+        while let Some((indexes, data)) = queue.pop() {
+            if indexes.len() == 1 {
+                self.process_simple(*indexes.first().unwrap(), data, &mut queue)?;
+            } else {
+                self.process_mixed(indexes, data, &mut queue);
+            }
+            if self.is_complete() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn process_simple(
+        &mut self,
+        index: usize,
+        data: Vec<u8>,
+        queue: &mut Vec<(BTreeSet<usize>, Vec<u8>)>,
+    ) -> Result<(), AirgapError> {
+        if self.simple.contains_key(&index) {
+            return Ok(());
+        }
+        self.simple.insert(index, data);
+
+        let (seq_len, message_len, checksum, _) = self.expected.unwrap();
+        if self.simple.len() == seq_len {
+            let mut message: Vec<u8> = self.simple.values().flatten().copied().collect();
+            message.truncate(message_len);
+            if crc32fast::hash(&message) != checksum {
+                // A bad fragment is in there somewhere; start over rather
+                // than treat every later part as a duplicate
+                *self = Self::default();
+                return Err(AirgapError::CrcMismatch);
+            }
+            self.message = Some(message);
+            self.mixed.clear();
+            return Ok(());
+        }
+
+        // Peel this fragment out of every mixed part that contains it
+        let data = &self.simple[&index];
+        let mut remaining = VecDeque::with_capacity(self.mixed.len());
+        for (mut mixed_indexes, mut mixed_data) in self.mixed.drain(..) {
+            if mixed_indexes.remove(&index) {
+                xor_into(&mut mixed_data, data);
+            }
+            if mixed_indexes.len() == 1 {
+                queue.push((mixed_indexes, mixed_data));
+            } else {
+                remaining.push_back((mixed_indexes, mixed_data));
+            }
+        }
+        self.mixed = remaining;
+        Ok(())
+    }
+
+    fn process_mixed(
+        &mut self,
+        mut indexes: BTreeSet<usize>,
+        mut data: Vec<u8>,
+        queue: &mut Vec<(BTreeSet<usize>, Vec<u8>)>,
+    ) {
+        if self.mixed.iter().any(|(existing, _)| *existing == indexes) {
+            return;
+        }
+
+        // Reduce by every fragment we already know
+        let known: Vec<usize> = indexes.iter().copied()
+            .filter(|index| self.simple.contains_key(index))
+            .collect();
+        for index in known {
+            indexes.remove(&index);
+            xor_into(&mut data, &self.simple[&index]);
+        }
+
+        match indexes.len() {
+            0 => {}
+            1 => queue.push((indexes, data)),
+            _ => {
+                if self.mixed.len() >= self.seq_len() {
+                    self.mixed.pop_front();
+                }
+                self.mixed.push_back((indexes, data));
+            }
+        }
+    }
+}
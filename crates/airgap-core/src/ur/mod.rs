@@ -0,0 +1,231 @@
+// src/ur - Blockchain Commons Uniform Resources (BCR-2020-005), single and multipart
+
+mod bytewords;
+mod cbor;
+pub(crate) mod fountain;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::str::FromStr;
+use crate::error::AirgapError;
+use crate::limits::DecoderLimits;
+use fountain::{FountainDecoder, FountainEncoder, Part};
+
+/// UR types understood by the encoder and decoder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UrType {
+    /// `ur:bytes` — an opaque CBOR byte string.
+    Bytes,
+    /// `ur:crypto-psbt` — a PSBT wrapped in a CBOR byte string.
+    CryptoPsbt,
+}
+
+impl UrType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UrType::Bytes => "bytes",
+            UrType::CryptoPsbt => "crypto-psbt",
+        }
+    }
+}
+
+impl FromStr for UrType {
+    type Err = AirgapError;
+
+    fn from_str(ur_type: &str) -> Result<Self, Self::Err> {
+        match ur_type {
+            "bytes" => Ok(UrType::Bytes),
+            "crypto-psbt" => Ok(UrType::CryptoPsbt),
+            other => Err(AirgapError::InvalidUr(format!("unsupported type '{}'", other))),
+        }
+    }
+}
+
+/// Header information of a single scanned UR frame.
+#[derive(Debug, Clone)]
+pub struct UrFrame {
+    pub ur_type: UrType,
+    /// 1-based sequence number; values above `seq_len` are fountain mixes.
+    pub seq_num: u32,
+    pub seq_len: usize,
+    /// CRC32 of the whole CBOR message, shared by every part of a transfer.
+    pub checksum: u32,
+    pub data: Vec<u8>,
+}
+
+// ============================================================================
+// ENCODER
+// ============================================================================
+
+/// Produces `ur:<type>/...` strings for a payload.
+#[derive(Debug, Clone)]
+pub struct UrEncoder {
+    ur_type: UrType,
+    fountain: FountainEncoder,
+}
+
+impl UrEncoder {
+    pub fn new(ur_type: UrType, data: &[u8], max_fragment_len: usize) -> Result<Self, AirgapError> {
+        if data.is_empty() {
+            return Err(AirgapError::EmptyData);
+        }
+
+        let mut message = Vec::with_capacity(data.len() + 9);
+        cbor::write_bytes(&mut message, data);
+
+        let fountain = FountainEncoder::new(&message, max_fragment_len);
+        if fountain.seq_len() > u16::MAX as usize {
+            return Err(AirgapError::TooManyChunks(fountain.seq_len()));
+        }
+
+        Ok(Self { ur_type, fountain })
+    }
+
+    pub fn ur_type(&self) -> UrType {
+        self.ur_type
+    }
+
+    pub fn seq_len(&self) -> usize {
+        self.fountain.seq_len()
+    }
+
+    pub fn checksum(&self) -> u32 {
+        self.fountain.checksum()
+    }
+
+    /// UR string for part `seq_num` (1-based). A payload that fits in one
+    /// fragment is emitted as a single-part UR without a sequence component.
+    pub fn part(&self, seq_num: u32) -> String {
+        let part = self.fountain.part(seq_num);
+        if part.seq_len == 1 {
+            let mut message = part.data;
+            message.truncate(part.message_len);
+            return format!("ur:{}/{}", self.ur_type.as_str(), bytewords::encode_minimal(&message));
+        }
+
+        format!(
+            "ur:{}/{}-{}/{}",
+            self.ur_type.as_str(),
+            part.seq_num,
+            part.seq_len,
+            bytewords::encode_minimal(&part.to_cbor()),
+        )
+    }
+}
+
+// ============================================================================
+// DECODER
+// ============================================================================
+
+/// Reassembles single or multipart URs of one expected type.
+#[derive(Debug)]
+pub struct UrDecoder {
+    ur_type: UrType,
+    fountain: FountainDecoder,
+    limits: DecoderLimits,
+}
+
+impl UrDecoder {
+    pub fn new(ur_type: UrType) -> Self {
+        Self {
+            ur_type,
+            fountain: FountainDecoder::new(),
+            limits: DecoderLimits::default(),
+        }
+    }
+
+    pub fn set_limits(&mut self, limits: DecoderLimits) {
+        self.limits = limits;
+    }
+
+    pub fn ur_type(&self) -> UrType {
+        self.ur_type
+    }
+
+    pub fn receive(&mut self, text: &str) -> Result<UrFrame, AirgapError> {
+        let text = text.trim().to_ascii_lowercase();
+        let body = text.strip_prefix("ur:")
+            .ok_or_else(|| AirgapError::InvalidUr("missing 'ur:' prefix".into()))?;
+
+        let components: Vec<&str> = body.split('/').collect();
+        let ur_type: UrType = components[0].parse()?;
+        if ur_type != self.ur_type {
+            return Err(AirgapError::InvalidUr(format!(
+                "expected type '{}', got '{}'", self.ur_type.as_str(), ur_type.as_str()
+            )));
+        }
+
+        let part = match components[1..] {
+            [payload] => {
+                let message = bytewords::decode_minimal(payload)?;
+                Part {
+                    seq_num: 1,
+                    seq_len: 1,
+                    message_len: message.len(),
+                    checksum: crc32fast::hash(&message),
+                    data: message,
+                }
+            }
+            [sequence, payload] => {
+                let part = Part::from_cbor(&bytewords::decode_minimal(payload)?)?;
+                if sequence != format!("{}-{}", part.seq_num, part.seq_len) {
+                    return Err(AirgapError::MetadataMismatch);
+                }
+                part
+            }
+            _ => return Err(AirgapError::InvalidUr("malformed UR path".into())),
+        };
+
+        if part.seq_len > u16::MAX as usize {
+            return Err(AirgapError::TooManyChunks(part.seq_len));
+        }
+        self.limits.check_total_chunks(part.seq_len)?;
+        self.limits.check_payload_bytes(part.message_len)?;
+
+        self.fountain.receive(&part)?;
+
+        Ok(UrFrame {
+            ur_type,
+            seq_num: part.seq_num,
+            seq_len: part.seq_len,
+            checksum: part.checksum,
+            data: part.data,
+        })
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.fountain.is_complete()
+    }
+
+    pub fn checksum(&self) -> Option<u32> {
+        self.fountain.checksum()
+    }
+
+    pub fn recovered_count(&self) -> usize {
+        self.fountain.recovered_count()
+    }
+
+    pub fn seq_len(&self) -> usize {
+        self.fountain.seq_len()
+    }
+
+    /// The payload carried inside the reassembled CBOR byte string.
+    pub fn get_data(&self) -> Result<Vec<u8>, AirgapError> {
+        let message = self.fountain.message().ok_or_else(|| AirgapError::EncodingError(format!(
+            "Incomplete: {}/{} fragments", self.recovered_count(), self.seq_len()
+        )))?;
+
+        let mut reader = cbor::Reader::new(message);
+        let data = reader.read_bytes()?;
+        if !reader.is_empty() {
+            return Err(AirgapError::InvalidUr("trailing bytes after payload".into()));
+        }
+        Ok(data.to_vec())
+    }
+
+    pub fn reset(&mut self) {
+        self.fountain = FountainDecoder::new();
+    }
+}
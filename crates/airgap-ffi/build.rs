@@ -3,9 +3,9 @@ use std::path::PathBuf;
 
 fn main() {
     let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
-    let header_path = PathBuf::from(&crate_dir)
-        .join("include")
-        .join("airgap.h");
+    let workspace_dir = PathBuf::from(&crate_dir).join("../..");
+    // Workspace-level include/, where the iOS and ObjC builds look for it
+    let header_path = workspace_dir.join("include").join("airgap.h");
     std::fs::create_dir_all(header_path.parent().unwrap()).unwrap();
     cbindgen::Builder::new()
         .with_crate(&crate_dir)
@@ -13,6 +13,9 @@ fn main() {
         .generate()
         .expect("Unable to generate bindings")
         .write_to_file(&header_path);
+
     println!("cargo:rerun-if-changed=src/");
     println!("cargo:rerun-if-changed=cbindgen.toml");
-}
\ No newline at end of file
+    println!("cargo:rerun-if-changed=../airgap-core/src/");
+    println!("cargo:rerun-if-changed=../airgap-render/src/");
+}
@@ -0,0 +1,78 @@
+use std::ffi::{c_void, CString};
+use std::os::raw::c_int;
+use std::ptr::null_mut;
+use airgap_core::AirgapError;
+
+/// Outcome of a fallible call. Release it with `airgap_result_free`, which
+/// frees both the error message and the payload. A call that panicked
+/// returns `AIRGAP_ERR_PANIC` with the panic message instead of unwinding
+/// into the caller.
+#[repr(C)]
+pub struct CResult {
+    pub code: c_int,
+    pub payload: *const c_void,
+    pub error_message: *const std::os::raw::c_char,
+    /// One of the `AIRGAP_PAYLOAD_*` codes, telling what `payload` points to.
+    pub payload_kind: c_int,
+}
+
+pub const AIRGAP_OK: c_int = 0;
+
+pub const AIRGAP_PAYLOAD_NONE: c_int = 0;
+/// `AirgapEncoder *`, freed with `airgap_encoder_free`.
+pub const AIRGAP_PAYLOAD_ENCODER: c_int = 1;
+/// `ByteArray *`, freed with `airgap_boxed_byte_array_free`.
+pub const AIRGAP_PAYLOAD_BYTE_ARRAY: c_int = 2;
+/// `QRResult *`, freed with `airgap_qr_result_free`.
+pub const AIRGAP_PAYLOAD_QR_RESULT: c_int = 3;
+/// `AutoQRResult *`, freed with `airgap_auto_qr_result_free`.
+pub const AIRGAP_PAYLOAD_AUTO_QR_RESULT: c_int = 4;
+/// `QRMatrix *`, freed with `airgap_qr_matrix_free`.
+pub const AIRGAP_PAYLOAD_QR_MATRIX: c_int = 5;
+/// `QRPixelBuffer *`, freed with `airgap_pixel_buffer_free`.
+pub const AIRGAP_PAYLOAD_PIXEL_BUFFER: c_int = 6;
+
+/// Free only the error message of `result`, leaving the payload to the
+/// caller. Prefer `airgap_result_free`.
+#[unsafe(no_mangle)]
+pub extern "C" fn result_error_message_free(result: CResult) {
+    ffi_guard!({
+        if !result.error_message.is_null() {
+            unsafe {
+                let _ = CString::from_raw(result.error_message as *mut _);
+            }
+        }
+    })
+}
+
+/// A type returned boxed in `CResult::payload`.
+#[cfg(not(cbindgen))]
+pub trait CPayload {
+    /// The `AIRGAP_PAYLOAD_*` code stored in `CResult::payload_kind`.
+    const KIND: c_int;
+}
+
+#[cfg(not(cbindgen))]
+impl CResult {
+    pub fn from_success<V: CPayload>(payload: Box<V>) -> Self {
+        CResult {
+            code: AIRGAP_OK,
+            payload: Box::into_raw(payload) as *mut c_void,
+            error_message: null_mut(),
+            payload_kind: V::KIND,
+        }
+    }
+
+    pub fn from_error(error: AirgapError) -> Self {
+        let code = error.to_code() as c_int;
+        let message = CString::new(error.to_string())
+            .unwrap_or_else(|_| CString::new("Unknown error").unwrap());
+
+        CResult {
+            code,
+            payload: null_mut(),
+            error_message: message.into_raw(), // Transfer ownership to caller
+            payload_kind: AIRGAP_PAYLOAD_NONE,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+// src/guard - keeps panics from unwinding across the C boundary
+
+use std::os::raw::c_int;
+use airgap_core::AirgapError;
+use airgap_core::error::AIRGAP_ERR_PANIC;
+use crate::c_result::CResult;
+use crate::ScheduledFrame;
+
+/// What an `extern "C"` function returns when its body panicked.
+pub(crate) trait PanicFallback {
+    fn from_panic(error: AirgapError) -> Self;
+}
+
+impl PanicFallback for CResult {
+    fn from_panic(error: AirgapError) -> Self {
+        CResult::from_error(error)
+    }
+}
+
+impl PanicFallback for () {
+    fn from_panic(_: AirgapError) -> Self {}
+}
+
+impl PanicFallback for bool {
+    fn from_panic(_: AirgapError) -> Self {
+        false
+    }
+}
+
+impl PanicFallback for usize {
+    fn from_panic(_: AirgapError) -> Self {
+        0
+    }
+}
+
+impl PanicFallback for u32 {
+    fn from_panic(_: AirgapError) -> Self {
+        0
+    }
+}
+
+impl PanicFallback for isize {
+    fn from_panic(_: AirgapError) -> Self {
+        -1
+    }
+}
+
+impl PanicFallback for c_int {
+    fn from_panic(_: AirgapError) -> Self {
+        AIRGAP_ERR_PANIC
+    }
+}
+
+impl<T> PanicFallback for *mut T {
+    fn from_panic(_: AirgapError) -> Self {
+        std::ptr::null_mut()
+    }
+}
+
+impl PanicFallback for ScheduledFrame {
+    fn from_panic(_: AirgapError) -> Self {
+        ScheduledFrame { frame_index: 0, changed: false, next_change_ms: 0 }
+    }
+}
+
+/// Run an `extern "C"` function body, turning a panic into the return
+/// type's `PanicFallback`: an `AIRGAP_ERR_PANIC` result or code, a null
+/// handle, or a zero value.
+macro_rules! ffi_guard {
+    ($body:block) => {
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| $body)) {
+            Ok(value) => value,
+            Err(payload) => $crate::guard::PanicFallback::from_panic(
+                airgap_core::AirgapError::from_panic(&*payload),
+            ),
+        }
+    };
+}
@@ -0,0 +1,1282 @@
+// src/lib - C FFI interface for iOS and other native hosts; build.rs writes include/airgap.h
+
+use std::ffi::{c_void, CString};
+use std::os::raw::{c_char, c_int};
+use std::{ptr, slice};
+
+// Only import when not generating bindings
+#[cfg(not(cbindgen))]
+use airgap_core::{AutoDecoder, DecoderEvent, DecoderLimits, DecoderTimeouts, SharedDecoder};
+#[cfg(not(cbindgen))]
+use airgap_render::{DisplayScheduler, Encoder};
+use airgap_core::AirgapError;
+use airgap_core::error::AIRGAP_ERR_NULL_POINTER;
+use airgap_render::QrConfig;
+#[cfg(not(cbindgen))]
+use airgap_render::{FrameIndicator, QrLogo};
+use c_result::{result_error_message_free, CResult, AIRGAP_OK};
+#[cfg(not(cbindgen))]
+use c_result::CPayload;
+use c_result::{
+    AIRGAP_PAYLOAD_AUTO_QR_RESULT, AIRGAP_PAYLOAD_BYTE_ARRAY, AIRGAP_PAYLOAD_ENCODER,
+    AIRGAP_PAYLOAD_PIXEL_BUFFER, AIRGAP_PAYLOAD_QR_MATRIX, AIRGAP_PAYLOAD_QR_RESULT,
+};
+pub use airgap_core::codes::*;
+pub use airgap_render::codes::*;
+
+#[macro_use]
+mod guard;
+mod c_result;
+
+pub enum AirgapEncoder {}
+
+/// Thread-safe decoder handle: any function taking it may be called
+/// concurrently from several threads, e.g. a camera thread feeding frames
+/// while the UI thread polls progress. Only `airgap_decoder_free` must not
+/// race with other calls on the same handle.
+pub enum AirgapDecoder {}
+
+pub enum AirgapAutoDecoder {}
+
+pub enum AirgapScheduler {}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct ByteArray {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+#[repr(C)]
+pub struct QRResult {
+    pub chunk_number: usize,
+    pub total_chunk_count: usize,
+}
+
+#[repr(C)]
+pub struct AutoQRResult {
+    pub chunk_number: usize,
+    pub total_chunk_count: usize,
+    /// One of the `AIRGAP_FORMAT_*` codes.
+    pub format: c_int,
+}
+
+pub const AIRGAP_EVENT_SESSION_STARTED: c_int = 0;
+pub const AIRGAP_EVENT_CHUNK_RECEIVED: c_int = 1;
+pub const AIRGAP_EVENT_DUPLICATE: c_int = 2;
+pub const AIRGAP_EVENT_CONFLICT: c_int = 3;
+pub const AIRGAP_EVENT_ERROR: c_int = 4;
+pub const AIRGAP_EVENT_COMPLETED: c_int = 5;
+
+/// Decoder notification passed to an `AirgapEventCallback`. Fields that do
+/// not apply to `kind` are 0 / null.
+#[repr(C)]
+pub struct AirgapEvent {
+    /// One of the `AIRGAP_EVENT_*` codes.
+    pub kind: c_int,
+    pub session_id: u32,
+    pub chunk_index: usize,
+    pub received: usize,
+    pub total: usize,
+    /// `AIRGAP_ERR_*` code for conflicts and errors.
+    pub error_code: c_int,
+    /// Error text, valid only for the duration of the callback.
+    pub error_message: *const c_char,
+}
+
+/// Called synchronously from `airgap_decoder_process_qr`, on the calling thread.
+pub type AirgapEventCallback = Option<unsafe extern "C" fn(event: *const AirgapEvent, user_data: *mut c_void)>;
+
+#[cfg(not(cbindgen))]
+struct CallbackObserver {
+    callback: unsafe extern "C" fn(*const AirgapEvent, *mut c_void),
+    user_data: *mut c_void,
+}
+
+// The caller of `airgap_decoder_set_callback` vouches for `user_data` being
+// usable from whichever thread processes frames.
+#[cfg(not(cbindgen))]
+unsafe impl Send for CallbackObserver {}
+
+#[cfg(not(cbindgen))]
+impl airgap_core::DecoderObserver for CallbackObserver {
+    fn on_event(&mut self, event: &DecoderEvent) {
+        let mut c_event = AirgapEvent {
+            kind: 0,
+            session_id: 0,
+            chunk_index: 0,
+            received: 0,
+            total: 0,
+            error_code: AIRGAP_OK,
+            error_message: ptr::null(),
+        };
+        let mut message = None;
+        match event {
+            DecoderEvent::SessionStarted { session_id, total_chunks } => {
+                c_event.kind = AIRGAP_EVENT_SESSION_STARTED;
+                c_event.session_id = *session_id;
+                c_event.total = *total_chunks;
+            }
+            DecoderEvent::ChunkReceived { chunk, received, total } => {
+                c_event.kind = AIRGAP_EVENT_CHUNK_RECEIVED;
+                c_event.session_id = chunk.session_id;
+                c_event.chunk_index = chunk.chunk_index as usize;
+                c_event.received = *received;
+                c_event.total = *total;
+            }
+            DecoderEvent::Duplicate { chunk } => {
+                c_event.kind = AIRGAP_EVENT_DUPLICATE;
+                c_event.session_id = chunk.session_id;
+                c_event.chunk_index = chunk.chunk_index as usize;
+            }
+            DecoderEvent::Conflict(error) | DecoderEvent::Error(error) => {
+                c_event.kind = match event {
+                    DecoderEvent::Conflict(_) => AIRGAP_EVENT_CONFLICT,
+                    _ => AIRGAP_EVENT_ERROR,
+                };
+                c_event.error_code = error.to_code();
+                message = CString::new(error.to_string()).ok();
+            }
+            DecoderEvent::Completed => c_event.kind = AIRGAP_EVENT_COMPLETED,
+        }
+        if let Some(message) = &message {
+            c_event.error_message = message.as_ptr();
+        }
+        unsafe { (self.callback)(&c_event, self.user_data) };
+    }
+}
+
+/// Frame to display, returned by `airgap_scheduler_advance`.
+#[repr(C)]
+pub struct ScheduledFrame {
+    /// Pass to `airgap_encoder_get_qr_string` / `airgap_encoder_generate_png`.
+    /// Indices at or past the chunk count are UR fountain frames.
+    pub frame_index: usize,
+    /// True when the frame differs from the previous call's frame.
+    pub changed: bool,
+    /// Milliseconds until the frame is due to change.
+    pub next_change_ms: u64,
+}
+
+/// QR module grid, quiet zone excluded. Free with `airgap_qr_matrix_free`.
+#[repr(C)]
+pub struct QRMatrix {
+    pub width: usize,
+    /// `width * width` bytes, row-major: 1 for dark, 0 for light.
+    pub modules: ByteArray,
+}
+
+/// Uncompressed frame pixels. Free with `airgap_pixel_buffer_free`.
+#[repr(C)]
+pub struct QRPixelBuffer {
+    pub width: u32,
+    pub height: u32,
+    /// One of the `AIRGAP_PIXEL_FORMAT_*` codes.
+    pub format: c_int,
+    /// Row-major pixel data, `width * height * bytes per pixel` long.
+    pub pixels: ByteArray,
+}
+
+/// Free the bytes of an array copied out of a result; the `ByteArray *`
+/// payload itself is freed with `airgap_boxed_byte_array_free`.
+///
+/// # Safety
+///
+/// `array` must have been returned by this library and not freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_byte_array_free(array: ByteArray) {
+    ffi_guard!({
+        if !array.is_null() {
+            let _ = unsafe { Vec::from_raw_parts(array.data, array.len, array.len) };
+        }
+    })
+}
+
+/// Free a `ByteArray *` payload together with its bytes.
+///
+/// # Safety
+///
+/// `array` must be null or a payload from this library not freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_boxed_byte_array_free(array: *mut ByteArray) {
+    ffi_guard!({
+        if !array.is_null() {
+            unsafe { airgap_byte_array_free(*Box::from_raw(array)) };
+        }
+    })
+}
+
+/// # Safety
+///
+/// `result` must be null or a payload from `airgap_decoder_process_qr` not freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_qr_result_free(result: *mut QRResult) {
+    ffi_guard!({
+        if !result.is_null() {
+            drop(unsafe { Box::from_raw(result) });
+        }
+    })
+}
+
+/// # Safety
+///
+/// `result` must be null or a payload from `airgap_auto_decoder_process_qr` not freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_auto_qr_result_free(result: *mut AutoQRResult) {
+    ffi_guard!({
+        if !result.is_null() {
+            drop(unsafe { Box::from_raw(result) });
+        }
+    })
+}
+
+/// Free a matrix payload together with its modules.
+///
+/// # Safety
+///
+/// `matrix` must be null or a payload from `airgap_encoder_get_matrix` not freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_qr_matrix_free(matrix: *mut QRMatrix) {
+    ffi_guard!({
+        if !matrix.is_null() {
+            unsafe { airgap_byte_array_free(Box::from_raw(matrix).modules) };
+        }
+    })
+}
+
+/// Free a pixel buffer payload together with its pixels.
+///
+/// # Safety
+///
+/// `buffer` must be null or a payload from `airgap_encoder_render_pixels` not freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_pixel_buffer_free(buffer: *mut QRPixelBuffer) {
+    ffi_guard!({
+        if !buffer.is_null() {
+            unsafe { airgap_byte_array_free(Box::from_raw(buffer).pixels) };
+        }
+    })
+}
+
+/// Free everything `result` owns: the error message and the payload named
+/// by `payload_kind`. To keep the payload, e.g. a new encoder handle, set
+/// `payload` to null before calling this.
+///
+/// # Safety
+///
+/// `result` must have been returned by this library, and neither its
+/// message nor its payload freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_result_free(result: CResult) {
+    ffi_guard!({
+        let payload = result.payload as *mut c_void;
+        unsafe {
+            match result.payload_kind {
+                AIRGAP_PAYLOAD_ENCODER => airgap_encoder_free(payload as *mut AirgapEncoder),
+                AIRGAP_PAYLOAD_BYTE_ARRAY => airgap_boxed_byte_array_free(payload as *mut ByteArray),
+                AIRGAP_PAYLOAD_QR_RESULT => airgap_qr_result_free(payload as *mut QRResult),
+                AIRGAP_PAYLOAD_AUTO_QR_RESULT => airgap_auto_qr_result_free(payload as *mut AutoQRResult),
+                AIRGAP_PAYLOAD_QR_MATRIX => airgap_qr_matrix_free(payload as *mut QRMatrix),
+                AIRGAP_PAYLOAD_PIXEL_BUFFER => airgap_pixel_buffer_free(payload as *mut QRPixelBuffer),
+                _ => {}
+            }
+        }
+        result_error_message_free(result);
+    })
+}
+
+#[cfg(not(cbindgen))]
+impl CPayload for Encoder {
+    const KIND: c_int = AIRGAP_PAYLOAD_ENCODER;
+}
+
+#[cfg(not(cbindgen))]
+impl CPayload for ByteArray {
+    const KIND: c_int = AIRGAP_PAYLOAD_BYTE_ARRAY;
+}
+
+#[cfg(not(cbindgen))]
+impl CPayload for QRResult {
+    const KIND: c_int = AIRGAP_PAYLOAD_QR_RESULT;
+}
+
+#[cfg(not(cbindgen))]
+impl CPayload for AutoQRResult {
+    const KIND: c_int = AIRGAP_PAYLOAD_AUTO_QR_RESULT;
+}
+
+#[cfg(not(cbindgen))]
+impl CPayload for QRMatrix {
+    const KIND: c_int = AIRGAP_PAYLOAD_QR_MATRIX;
+}
+
+#[cfg(not(cbindgen))]
+impl CPayload for QRPixelBuffer {
+    const KIND: c_int = AIRGAP_PAYLOAD_PIXEL_BUFFER;
+}
+
+#[cfg(not(cbindgen))]
+impl ByteArray {
+    pub fn from_vec(mut vec: Vec<u8>) -> Self {
+        let data = vec.as_mut_ptr();
+        let len = vec.len();
+        std::mem::forget(vec);
+        Self { data, len }
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            data: ptr::null_mut(),
+            len: 0,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.data.is_null()
+    }
+}
+
+
+/// Rendering settings for `airgap_encoder_new_with_config`. Start from
+/// `airgap_qr_config_default()` and change what you need.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct AirgapQrConfig {
+    /// One of the `AIRGAP_EC_LEVEL_*` codes.
+    pub ec_level: c_int,
+    /// Minimum edge length of the image in pixels; ignored when
+    /// `module_size` is set.
+    pub qr_size: u32,
+    /// RGBA color of dark modules.
+    pub foreground: [u8; 4],
+    /// RGBA color of light modules and the quiet zone.
+    pub background: [u8; 4],
+    /// Quiet zone width in modules.
+    pub quiet_zone: u32,
+    /// Exact pixels per module, or 0 to scale the symbol to `qr_size`.
+    pub module_size: u32,
+    /// Swap foreground and background, e.g. for dark-mode screens.
+    pub invert: bool,
+    /// Draw a "7/32" frame counter below the symbol; UR fountain frames
+    /// read "+1", "+2", ...
+    pub caption: bool,
+    /// Append a 4-digit hex tag derived from the session id to the caption.
+    pub session_tag: bool,
+    /// Draw a bar filled in proportion to the frame position.
+    pub progress_bar: bool,
+    /// Row-major RGBA pixels of a logo drawn over the centre of the symbol,
+    /// `logo_width * logo_height * 4` bytes, or null for no logo. Copied
+    /// when the encoder is created.
+    pub logo_rgba: *const u8,
+    pub logo_width: u32,
+    pub logo_height: u32,
+    /// Logo edge length as a fraction of the symbol edge.
+    pub logo_size_ratio: f32,
+}
+
+// Convert the C settings, copying the logo pixels
+#[cfg(not(cbindgen))]
+unsafe fn qr_config_from_c(config: &AirgapQrConfig) -> Result<QrConfig, AirgapError> {
+    let ec_level = airgap_render::codes::ec_level_from_code(config.ec_level)
+        .ok_or(AirgapError::UnknownCode("EC level", config.ec_level))?;
+    let logo = match config.logo_rgba.is_null() {
+        true => None,
+        false => {
+            // Dimensions whose byte count overflows can't describe a real buffer
+            let len = (config.logo_width as usize)
+                .checked_mul(config.logo_height as usize)
+                .and_then(|pixels| pixels.checked_mul(4))
+                .filter(|&len| len <= isize::MAX as usize)
+                .ok_or_else(|| AirgapError::InvalidConfig("logo dimensions too large".into()))?;
+            let rgba = unsafe { slice::from_raw_parts(config.logo_rgba, len) }.to_vec();
+            Some(QrLogo::from_rgba(config.logo_width, config.logo_height, rgba, config.logo_size_ratio)?)
+        }
+    };
+    let config = QrConfig {
+        ec_level,
+        qr_size: config.qr_size,
+        foreground: config.foreground,
+        background: config.background,
+        quiet_zone: config.quiet_zone,
+        module_size: (config.module_size > 0).then_some(config.module_size),
+        invert: config.invert,
+        logo,
+        indicator: FrameIndicator {
+            caption: config.caption,
+            session_tag: config.session_tag,
+            progress_bar: config.progress_bar,
+        },
+    };
+    config.check_sizes()?;
+    Ok(config)
+}
+
+/// The settings `airgap_encoder_new` uses, with `qr_size` 400.
+#[unsafe(no_mangle)]
+pub extern "C" fn airgap_qr_config_default() -> AirgapQrConfig {
+    let config = QrConfig::default();
+    AirgapQrConfig {
+        ec_level: AIRGAP_EC_LEVEL_M,
+        qr_size: config.qr_size,
+        foreground: config.foreground,
+        background: config.background,
+        quiet_zone: config.quiet_zone,
+        module_size: 0,
+        invert: false,
+        caption: false,
+        session_tag: false,
+        progress_bar: false,
+        logo_rgba: ptr::null(),
+        logo_width: 0,
+        logo_height: 0,
+        logo_size_ratio: 0.2,
+    }
+}
+
+/// Create an encoder emitting frames in `format` (one of the
+/// `AIRGAP_FORMAT_*` codes) rendered with `config`.
+///
+/// # Safety
+///
+/// `data` must point to `data_len` readable bytes and `config` to a valid
+/// `AirgapQrConfig`, whose `logo_rgba` is null or points to
+/// `logo_width * logo_height * 4` readable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_encoder_new_with_config(
+    data: *const u8,
+    data_len: usize,
+    chunk_size: usize,
+    format: c_int,
+    config: *const AirgapQrConfig,
+) -> CResult {
+    ffi_guard!({
+        if data.is_null() {
+            return CResult::from_error(AirgapError::NullPointer("data"))
+        }
+        if config.is_null() {
+            return CResult::from_error(AirgapError::NullPointer("config"))
+        }
+
+        let Some(format) = frame_format_from_code(format) else {
+            return CResult::from_error(AirgapError::UnknownCode("frame format", format));
+        };
+
+        let config = match unsafe { qr_config_from_c(&*config) } {
+            Ok(config) => config,
+            Err(err) => return CResult::from_error(err),
+        };
+
+        let data_slice = unsafe { slice::from_raw_parts(data, data_len) };
+
+        match Encoder::with_format(data_slice, chunk_size, config, format) {
+            Ok(encoder) => CResult::from_success(Box::new(encoder)),
+            Err(err) => CResult::from_error(err),
+        }
+    })
+}
+
+/// # Safety
+///
+/// `data` must point to `data_len` readable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_encoder_new(
+    data: *const u8,
+    data_len: usize,
+    chunk_size: usize,
+    qr_size: u32
+) -> CResult {
+    ffi_guard!({
+        if data.is_null() {
+            return CResult::from_error(AirgapError::NullPointer("data"))
+        }
+
+        let data_slice = unsafe { slice::from_raw_parts(data, data_len) };
+
+        match Encoder::with_config(data_slice, chunk_size, QrConfig::with_size(qr_size)) {
+            Ok(encoder) => CResult::from_success(Box::new(encoder)),
+            Err(err) => CResult::from_error(err),
+        }
+    })
+}
+
+/// Like `airgap_encoder_new`, but with a fixed session id so the frames are
+/// reproducible, e.g. for comparing against the golden test vectors.
+///
+/// # Safety
+///
+/// `data` must point to `data_len` readable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_encoder_new_with_session_id(
+    data: *const u8,
+    data_len: usize,
+    chunk_size: usize,
+    qr_size: u32,
+    session_id: u32,
+) -> CResult {
+    ffi_guard!({
+        if data.is_null() {
+            return CResult::from_error(AirgapError::NullPointer("data"))
+        }
+
+        let data_slice = unsafe { slice::from_raw_parts(data, data_len) };
+
+        match Encoder::with_session_id(data_slice, chunk_size, QrConfig::with_size(qr_size), session_id) {
+            Ok(encoder) => CResult::from_success(Box::new(encoder)),
+            Err(err) => CResult::from_error(err),
+        }
+    })
+}
+
+/// # Safety
+///
+/// `data` must point to `data_len` readable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_encoder_new_with_format(
+    data: *const u8,
+    data_len: usize,
+    chunk_size: usize,
+    qr_size: u32,
+    format: c_int,
+) -> CResult {
+    ffi_guard!({
+        if data.is_null() {
+            return CResult::from_error(AirgapError::NullPointer("data"))
+        }
+
+        let Some(format) = frame_format_from_code(format) else {
+            return CResult::from_error(AirgapError::UnknownCode("frame format", format));
+        };
+
+        let data_slice = unsafe { slice::from_raw_parts(data, data_len) };
+
+        match Encoder::with_format(data_slice, chunk_size, QrConfig::with_size(qr_size), format) {
+            Ok(encoder) => CResult::from_success(Box::new(encoder)),
+            Err(err) => CResult::from_error(err),
+        }
+    })
+}
+
+/// # Safety
+///
+/// `encoder` must be null or a handle from `airgap_encoder_new*` not freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_encoder_free(encoder: *mut AirgapEncoder) {
+    ffi_guard!({
+        if !encoder.is_null() {
+            drop(unsafe { Box::from_raw(encoder as *mut Encoder) });
+        }
+    })
+}
+
+/// # Safety
+///
+/// `encoder` must be null or a live handle from `airgap_encoder_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_encoder_chunk_count(encoder: *const AirgapEncoder) -> usize {
+    ffi_guard!({
+        if encoder.is_null() {
+            return 0;
+        }
+        unsafe { &*(encoder as *const Encoder) }.chunk_count()
+    })
+}
+
+/// # Safety
+///
+/// `encoder` must be null or a live handle from `airgap_encoder_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_encoder_session_id(encoder: *const AirgapEncoder) -> u32 {
+    ffi_guard!({
+        if encoder.is_null() {
+            return 0;
+        }
+        unsafe { &*(encoder as *const Encoder) }.session_id()
+    })
+}
+
+/// # Safety
+///
+/// `encoder` must be null or a live handle from `airgap_encoder_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_encoder_get_qr_string(
+    encoder: *const AirgapEncoder,
+    index: usize,
+) -> CResult {
+    ffi_guard!({
+        if encoder.is_null() {
+            return CResult::from_error(AirgapError::NullPointer("encoder"));
+        }
+
+        let qr_string = match unsafe { &*(encoder as *const Encoder) }.get_qr_string(index) {
+            Ok(s) => s,
+            Err(e) => {
+                return CResult::from_error(e);
+            }
+        };
+
+        let c_string = match std::ffi::CString::new(qr_string) {
+            Ok(s) => s,
+            Err(_) => {
+                return CResult::from_error(AirgapError::BindingError("QR string contains a NUL byte".to_string()));
+            }
+        };
+
+        CResult::from_success(Box::new(ByteArray::from_vec(c_string.into_bytes_with_nul())))
+    })
+}
+
+/// # Safety
+///
+/// `encoder` must be null or a live handle from `airgap_encoder_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_encoder_generate_png(
+    encoder: *const AirgapEncoder,
+    index: usize,
+) -> CResult {
+    ffi_guard!({
+        if encoder.is_null() {
+            return CResult::from_error(AirgapError::NullPointer("encoder"));
+        }
+
+        let png = match unsafe { &*(encoder as *const Encoder) }.generate_png_bytes_for_item(index) {
+            Ok(p) => p,
+            Err(e) => {
+                return CResult::from_error(e);
+            }
+        };
+        CResult::from_success(Box::new(ByteArray::from_vec(png)))
+    })
+}
+
+/// # Safety
+///
+/// `encoder` must be null or a live handle from `airgap_encoder_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_encoder_get_matrix(
+    encoder: *const AirgapEncoder,
+    index: usize,
+) -> CResult {
+    ffi_guard!({
+        if encoder.is_null() {
+            return CResult::from_error(AirgapError::NullPointer("encoder"));
+        }
+
+        match unsafe { &*(encoder as *const Encoder) }.qr_matrix(index) {
+            Ok(matrix) => CResult::from_success(Box::new(QRMatrix {
+                width: matrix.width,
+                modules: ByteArray::from_vec(matrix.modules),
+            })),
+            Err(e) => CResult::from_error(e),
+        }
+    })
+}
+
+/// # Safety
+///
+/// `encoder` must be null or a live handle from `airgap_encoder_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_encoder_render_pixels(
+    encoder: *const AirgapEncoder,
+    index: usize,
+    pixel_format: c_int,
+) -> CResult {
+    ffi_guard!({
+        if encoder.is_null() {
+            return CResult::from_error(AirgapError::NullPointer("encoder"));
+        }
+
+        let Some(format) = pixel_format_from_code(pixel_format) else {
+            return CResult::from_error(AirgapError::UnknownCode("pixel format", pixel_format));
+        };
+
+        match unsafe { &*(encoder as *const Encoder) }.render_pixels(index, format) {
+            Ok(buffer) => CResult::from_success(Box::new(QRPixelBuffer {
+                width: buffer.width,
+                height: buffer.height,
+                format: pixel_format,
+                pixels: ByteArray::from_vec(buffer.data),
+            })),
+            Err(e) => CResult::from_error(e),
+        }
+    })
+}
+
+/// # Safety
+///
+/// Always safe to call; the handle must be released with `airgap_decoder_free`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_decoder_new() -> *mut AirgapDecoder {
+    ffi_guard!({
+        Box::into_raw(Box::new(SharedDecoder::new())) as *mut AirgapDecoder
+    })
+}
+
+/// # Safety
+///
+/// Always safe to call; the handle must be released with `airgap_decoder_free`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_decoder_new_with_format(format: c_int) -> *mut AirgapDecoder {
+    ffi_guard!({
+        match frame_format_from_code(format) {
+            Some(format) => Box::into_raw(Box::new(SharedDecoder::with_format(format))) as *mut AirgapDecoder,
+            None => ptr::null_mut(),
+        }
+    })
+}
+
+/// Register `callback` to be called with `user_data` for every decoder
+/// event; a null `callback` removes it.
+///
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_decoder_new*`.
+/// `callback` must be safe to call with `user_data` until it is replaced or
+/// the decoder is freed, from any thread that processes frames.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_decoder_set_callback(
+    decoder: *const AirgapDecoder,
+    callback: AirgapEventCallback,
+    user_data: *mut c_void,
+) {
+    ffi_guard!({
+        if decoder.is_null() {
+            return;
+        }
+        let decoder = unsafe { &*(decoder as *const SharedDecoder) };
+        match callback {
+            Some(callback) => decoder.set_observer(Box::new(CallbackObserver { callback, user_data })),
+            None => decoder.clear_observer(),
+        }
+    })
+}
+
+/// # Safety
+///
+/// `decoder` must be null or a handle from `airgap_decoder_new*` not freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_decoder_free(decoder: *mut AirgapDecoder) {
+    ffi_guard!({
+        if !decoder.is_null() {
+            drop(unsafe { Box::from_raw(decoder as *mut SharedDecoder) });
+        }
+    })
+}
+
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_decoder_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_decoder_is_complete(decoder: *const AirgapDecoder) -> bool {
+    ffi_guard!({
+        if decoder.is_null() {
+            return false;
+        }
+        unsafe { &*(decoder as *const SharedDecoder) }.is_complete()
+    })
+}
+
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_decoder_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_decoder_get_total(decoder: *const AirgapDecoder) -> usize {
+    ffi_guard!({
+        if decoder.is_null() {
+            return 0;
+        }
+        unsafe { &*(decoder as *const SharedDecoder) }.total_count()
+    })
+}
+
+
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_decoder_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_decoder_get_received(decoder: *const AirgapDecoder) -> usize {
+    ffi_guard!({
+        if decoder.is_null() {
+            return 0;
+        }
+        unsafe { &*(decoder as *const SharedDecoder) }.received_count()
+    })
+}
+
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_decoder_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_decoder_get_session_id(decoder: *const AirgapDecoder) -> isize {
+    ffi_guard!({
+        if decoder.is_null() {
+            return 0;
+        }
+        match unsafe { &*(decoder as *const SharedDecoder) }.session_id() {
+            Some(session_id) => session_id as isize,
+            None => -1
+        }
+    })
+}
+
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_decoder_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_decoder_reset(decoder: *const AirgapDecoder) -> c_int{
+    ffi_guard!({
+        if decoder.is_null() {
+            return AIRGAP_ERR_NULL_POINTER;
+        }
+        unsafe { &*(decoder as *const SharedDecoder) }.reset();
+        AIRGAP_OK
+    })
+}
+
+/// Report the session as stalled after `idle_ms` without a new chunk and as
+/// expired `total_ms` after its first frame; 0 disables either limit. With
+/// `auto_expire`, stale sessions are dropped as new frames arrive, otherwise
+/// frames for an expired session fail with `AIRGAP_ERR_SESSION_EXPIRED`.
+///
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_decoder_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_decoder_set_timeouts(
+    decoder: *const AirgapDecoder,
+    idle_ms: u64,
+    total_ms: u64,
+    auto_expire: bool,
+) -> c_int {
+    ffi_guard!({
+        if decoder.is_null() {
+            return AIRGAP_ERR_NULL_POINTER;
+        }
+        let timeouts = DecoderTimeouts { idle_ms, total_ms, auto_expire };
+        unsafe { &*(decoder as *const SharedDecoder) }.set_timeouts(timeouts);
+        AIRGAP_OK
+    })
+}
+
+/// Cap what the decoder accepts from untrusted frames; 0 disables a limit.
+/// New decoders allow 4096 chunks, 16 MiB of payload and 4296-character
+/// frames, with no session limit. Frames over a limit fail with `AIRGAP_ERR_FRAME_TOO_LONG`,
+/// `AIRGAP_ERR_CHUNK_LIMIT`, `AIRGAP_ERR_PAYLOAD_TOO_LARGE` or
+/// `AIRGAP_ERR_SESSION_LIMIT`.
+///
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_decoder_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_decoder_set_limits(
+    decoder: *const AirgapDecoder,
+    max_total_chunks: usize,
+    max_payload_bytes: usize,
+    max_sessions: usize,
+    max_frame_len: usize,
+) -> c_int {
+    ffi_guard!({
+        if decoder.is_null() {
+            return AIRGAP_ERR_NULL_POINTER;
+        }
+        let limits = DecoderLimits { max_total_chunks, max_payload_bytes, max_sessions, max_frame_len };
+        unsafe { &*(decoder as *const SharedDecoder) }.set_limits(limits);
+        AIRGAP_OK
+    })
+}
+
+/// One of the `AIRGAP_STATUS_*` codes, or `AIRGAP_ERR_NULL_POINTER` for a null handle.
+///
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_decoder_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_decoder_get_status(decoder: *const AirgapDecoder) -> c_int {
+    ffi_guard!({
+        if decoder.is_null() {
+            return AIRGAP_ERR_NULL_POINTER;
+        }
+        decoder_status_to_code(unsafe { &*(decoder as *const SharedDecoder) }.status())
+    })
+}
+
+/// Reset the session if it is stalled or expired; returns whether it did.
+///
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_decoder_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_decoder_expire_stale(decoder: *const AirgapDecoder) -> bool {
+    ffi_guard!({
+        if decoder.is_null() {
+            return false;
+        }
+        unsafe { &*(decoder as *const SharedDecoder) }.expire_stale()
+    })
+}
+
+
+/// # Safety
+///
+/// `decoder` must be null or a live decoder handle; `qr_string` must be null or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_decoder_process_qr(
+    decoder: *const AirgapDecoder,
+    qr_string: *const std::os::raw::c_char,
+) -> CResult {
+    ffi_guard!({
+        if decoder.is_null() {
+            return CResult::from_error(AirgapError::NullPointer("decoder"));
+        }
+
+        if qr_string.is_null() {
+            return CResult::from_error(AirgapError::NullPointer("qr_string"));
+        }
+
+        let c_str = unsafe { std::ffi::CStr::from_ptr(qr_string) };
+        let qr_data = match c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => return CResult::from_error(AirgapError::InvalidUtf8),
+        };
+
+        match unsafe { &*(decoder as *const SharedDecoder) }.process_qr_string(qr_data) {
+            Ok(chunk) => CResult::from_success(Box::new(QRResult{ chunk_number: chunk.chunk_index as usize, total_chunk_count: chunk.total_chunks as usize })),
+            Err(err) => CResult::from_error(err),
+        }
+    })
+}
+
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_decoder_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_decoder_get_data(
+    decoder: *const AirgapDecoder,
+) -> CResult {
+    ffi_guard!({
+        if decoder.is_null() {
+            return CResult::from_error(AirgapError::NullPointer("decoder"));
+        }
+
+        match unsafe { &*(decoder as *const SharedDecoder) }.get_data() {
+            Ok(vec) => {
+                CResult::from_success(Box::new(ByteArray::from_vec(vec)))
+            }
+            Err(err) => CResult::from_error(err),
+        }
+    })
+}
+
+/// # Safety
+///
+/// Always safe to call; the handle must be released with `airgap_auto_decoder_free`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_auto_decoder_new() -> *mut AirgapAutoDecoder {
+    ffi_guard!({
+        Box::into_raw(Box::new(AutoDecoder::new())) as *mut AirgapAutoDecoder
+    })
+}
+
+/// # Safety
+///
+/// `decoder` must be null or a handle from `airgap_auto_decoder_new` not freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_auto_decoder_free(decoder: *mut AirgapAutoDecoder) {
+    ffi_guard!({
+        if !decoder.is_null() {
+            drop(unsafe { Box::from_raw(decoder as *mut AutoDecoder) });
+        }
+    })
+}
+
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_auto_decoder_new`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_auto_decoder_get_format(decoder: *const AirgapAutoDecoder) -> c_int {
+    ffi_guard!({
+        if decoder.is_null() {
+            return AIRGAP_FORMAT_NONE;
+        }
+        detected_format_to_code(unsafe { &*(decoder as *const AutoDecoder) }.format())
+    })
+}
+
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_auto_decoder_new`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_auto_decoder_is_complete(decoder: *const AirgapAutoDecoder) -> bool {
+    ffi_guard!({
+        if decoder.is_null() {
+            return false;
+        }
+        unsafe { &*(decoder as *const AutoDecoder) }.is_complete()
+    })
+}
+
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_auto_decoder_new`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_auto_decoder_get_total(decoder: *const AirgapAutoDecoder) -> usize {
+    ffi_guard!({
+        if decoder.is_null() {
+            return 0;
+        }
+        unsafe { &*(decoder as *const AutoDecoder) }.total_count()
+    })
+}
+
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_auto_decoder_new`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_auto_decoder_get_received(decoder: *const AirgapAutoDecoder) -> usize {
+    ffi_guard!({
+        if decoder.is_null() {
+            return 0;
+        }
+        unsafe { &*(decoder as *const AutoDecoder) }.received_count()
+    })
+}
+
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_auto_decoder_new`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_auto_decoder_reset(decoder: *mut AirgapAutoDecoder) -> c_int {
+    ffi_guard!({
+        if decoder.is_null() {
+            return AIRGAP_ERR_NULL_POINTER;
+        }
+        unsafe { &mut *(decoder as *mut AutoDecoder) }.reset();
+        AIRGAP_OK
+    })
+}
+
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_auto_decoder_new`;
+/// `qr_string` must be null or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_auto_decoder_process_qr(
+    decoder: *mut AirgapAutoDecoder,
+    qr_string: *const std::os::raw::c_char,
+) -> CResult {
+    ffi_guard!({
+        if decoder.is_null() {
+            return CResult::from_error(AirgapError::NullPointer("decoder"));
+        }
+
+        if qr_string.is_null() {
+            return CResult::from_error(AirgapError::NullPointer("qr_string"));
+        }
+
+        let c_str = unsafe { std::ffi::CStr::from_ptr(qr_string) };
+        let qr_data = match c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => return CResult::from_error(AirgapError::InvalidUtf8),
+        };
+
+        match unsafe { &mut *(decoder as *mut AutoDecoder) }.process_qr_string(qr_data) {
+            Ok(frame) => CResult::from_success(Box::new(AutoQRResult {
+                chunk_number: frame.chunk.chunk_index as usize,
+                total_chunk_count: frame.chunk.total_chunks as usize,
+                format: detected_format_to_code(Some(frame.format)),
+            })),
+            Err(err) => CResult::from_error(err),
+        }
+    })
+}
+
+/// # Safety
+///
+/// `decoder` must be null or a live handle from `airgap_auto_decoder_new`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_auto_decoder_get_data(
+    decoder: *const AirgapAutoDecoder,
+) -> CResult {
+    ffi_guard!({
+        if decoder.is_null() {
+            return CResult::from_error(AirgapError::NullPointer("decoder"));
+        }
+
+        match unsafe { &*(decoder as *const AutoDecoder) }.get_data() {
+            Ok(vec) => CResult::from_success(Box::new(ByteArray::from_vec(vec))),
+            Err(err) => CResult::from_error(err),
+        }
+    })
+}
+
+/// # Safety
+///
+/// `encoder` must be null or a live handle from `airgap_encoder_new*`. The
+/// scheduler does not borrow the encoder and must be released with
+/// `airgap_scheduler_free`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_scheduler_new(
+    encoder: *const AirgapEncoder,
+    frames_per_second: f32,
+) -> *mut AirgapScheduler {
+    ffi_guard!({
+        if encoder.is_null() {
+            return ptr::null_mut();
+        }
+        let encoder = unsafe { &*(encoder as *const Encoder) };
+        Box::into_raw(Box::new(DisplayScheduler::new(encoder, frames_per_second))) as *mut AirgapScheduler
+    })
+}
+
+/// # Safety
+///
+/// `scheduler` must be null or a handle from `airgap_scheduler_new` not freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_scheduler_free(scheduler: *mut AirgapScheduler) {
+    ffi_guard!({
+        if !scheduler.is_null() {
+            drop(unsafe { Box::from_raw(scheduler as *mut DisplayScheduler) });
+        }
+    })
+}
+
+/// # Safety
+///
+/// `scheduler` must be null or a live handle from `airgap_scheduler_new`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_scheduler_set_frame_rate(
+    scheduler: *mut AirgapScheduler,
+    frames_per_second: f32,
+) {
+    ffi_guard!({
+        if scheduler.is_null() {
+            return;
+        }
+        unsafe { &mut *(scheduler as *mut DisplayScheduler) }.set_frame_rate(frames_per_second);
+    })
+}
+
+/// # Safety
+///
+/// `scheduler` must be null or a live handle from `airgap_scheduler_new`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_scheduler_set_fountain_interval(
+    scheduler: *mut AirgapScheduler,
+    every: usize,
+) {
+    ffi_guard!({
+        if scheduler.is_null() {
+            return;
+        }
+        unsafe { &mut *(scheduler as *mut DisplayScheduler) }.set_fountain_interval(every);
+    })
+}
+
+/// # Safety
+///
+/// `scheduler` must be null or a live handle from `airgap_scheduler_new`, and
+/// `indices` must point to `len` readable values (or be null when `len` is 0).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_scheduler_report_missing(
+    scheduler: *mut AirgapScheduler,
+    indices: *const usize,
+    len: usize,
+) {
+    ffi_guard!({
+        if scheduler.is_null() || indices.is_null() {
+            return;
+        }
+        let indices = unsafe { slice::from_raw_parts(indices, len) };
+        unsafe { &mut *(scheduler as *mut DisplayScheduler) }.report_missing(indices);
+    })
+}
+
+/// Call with the milliseconds elapsed since the previous call and display
+/// the returned frame.
+///
+/// # Safety
+///
+/// `scheduler` must be null or a live handle from `airgap_scheduler_new`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_scheduler_advance(
+    scheduler: *mut AirgapScheduler,
+    elapsed_ms: u64,
+) -> ScheduledFrame {
+    ffi_guard!({
+        if scheduler.is_null() {
+            return ScheduledFrame { frame_index: 0, changed: false, next_change_ms: 0 };
+        }
+        let frame = unsafe { &mut *(scheduler as *mut DisplayScheduler) }.advance(elapsed_ms);
+        ScheduledFrame {
+            frame_index: frame.index,
+            changed: frame.changed,
+            next_change_ms: frame.next_change_ms,
+        }
+    })
+}
+
+/// # Safety
+///
+/// `scheduler` must be null or a live handle from `airgap_scheduler_new`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn airgap_scheduler_reset(scheduler: *mut AirgapScheduler) {
+    ffi_guard!({
+        if scheduler.is_null() {
+            return;
+        }
+        unsafe { &mut *(scheduler as *mut DisplayScheduler) }.reset();
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+    use std::os::raw::c_int;
+    use airgap_core::error::{AIRGAP_ERR_INVALID_CONFIG, AIRGAP_ERR_PANIC};
+    use airgap_core::AirgapError;
+    use super::{airgap_encoder_new_with_config, airgap_qr_config_default, airgap_result_free, AirgapDecoder, CResult, AIRGAP_FORMAT_AIRGAP};
+
+    extern "C" fn panicking_result(index: usize) -> CResult {
+        ffi_guard!({
+            let chunks: Vec<u8> = Vec::new();
+            CResult::from_error(AirgapError::MissingChunk(chunks[index] as u16))
+        })
+    }
+
+    extern "C" fn panicking_handle() -> *mut AirgapDecoder {
+        ffi_guard!({ panic!("no decoder") })
+    }
+
+    extern "C" fn panicking_code() -> c_int {
+        ffi_guard!({ panic!("{} is not a code", 7) })
+    }
+
+    extern "C" fn panicking_flag() -> bool {
+        ffi_guard!({ panic!("no flag") })
+    }
+
+    #[test]
+    fn test_ffi_guard() {
+        let result = panicking_result(3);
+        assert_eq!(result.code, AIRGAP_ERR_PANIC);
+        assert!(result.payload.is_null());
+        let message = unsafe { CStr::from_ptr(result.error_message) }.to_str().unwrap();
+        assert!(message.contains("index out of bounds"), "{}", message);
+        unsafe { airgap_result_free(result) };
+
+        assert!(panicking_handle().is_null());
+        assert_eq!(panicking_code(), AIRGAP_ERR_PANIC);
+        assert!(!panicking_flag());
+    }
+
+    #[test]
+    fn test_oversized_config() {
+        // Rejected up front rather than panicking or aborting in the renderer
+        let data = [0x42u8; 100];
+        let mut config = airgap_qr_config_default();
+        config.module_size = u32::MAX / 2;
+        let result = unsafe { airgap_encoder_new_with_config(data.as_ptr(), data.len(), 50, AIRGAP_FORMAT_AIRGAP, &config) };
+        assert_eq!(result.code, AIRGAP_ERR_INVALID_CONFIG);
+        assert!(result.payload.is_null());
+        unsafe { airgap_result_free(result) };
+    }
+}
@@ -0,0 +1,315 @@
+//
+//  harness.c
+//  Airgap
+//
+//  Ownership tests for the C API: every result and payload is released the
+//  documented way, so a leak checker sees no allocation left behind.
+//  Built and run by test-c.sh under AddressSanitizer and Valgrind.
+//
+
+#include <stdio.h>
+#include <string.h>
+#include "airgap.h"
+
+#define CHECK(condition) \
+    if (!(condition)) { \
+        fprintf(stderr, "❌ FAILED: %s (%s:%d)\n", #condition, __FILE__, __LINE__); \
+        return 0; \
+    }
+
+#define DATA_LEN 3000
+
+static void fill_data(unsigned char *data) {
+    for (size_t i = 0; i < DATA_LEN; i++) {
+        data[i] = (unsigned char)(i * 31 % 251);
+    }
+}
+
+// Create an encoder, taking the handle out of its result
+static struct AirgapEncoder *new_encoder(const unsigned char *data, int format) {
+    struct CResult created = airgap_encoder_new_with_format(data, DATA_LEN, 400, 300, format);
+    if (created.code != AIRGAP_OK || created.payload_kind != AIRGAP_PAYLOAD_ENCODER) {
+        airgap_result_free(created);
+        return NULL;
+    }
+    struct AirgapEncoder *encoder = (struct AirgapEncoder *)created.payload;
+    created.payload = NULL;
+    airgap_result_free(created);
+    return encoder;
+}
+
+static int test_round_trip(void) {
+    unsigned char data[DATA_LEN];
+    fill_data(data);
+
+    struct AirgapEncoder *encoder = new_encoder(data, AIRGAP_FORMAT_AIRGAP);
+    CHECK(encoder != NULL);
+    struct AirgapDecoder *decoder = airgap_decoder_new();
+
+    size_t count = airgap_encoder_chunk_count(encoder);
+    for (size_t i = 0; i < count; i++) {
+        struct CResult qr = airgap_encoder_get_qr_string(encoder, i);
+        CHECK(qr.code == AIRGAP_OK && qr.payload_kind == AIRGAP_PAYLOAD_BYTE_ARRAY);
+        const struct ByteArray *text = qr.payload;
+
+        struct CResult scanned = airgap_decoder_process_qr(decoder, (const char *)text->data);
+        CHECK(scanned.code == AIRGAP_OK && scanned.payload_kind == AIRGAP_PAYLOAD_QR_RESULT);
+        CHECK(((const struct QRResult *)scanned.payload)->chunk_number == i);
+        airgap_result_free(scanned);
+        airgap_result_free(qr);
+    }
+
+    struct CResult decoded = airgap_decoder_get_data(decoder);
+    CHECK(decoded.code == AIRGAP_OK && decoded.payload_kind == AIRGAP_PAYLOAD_BYTE_ARRAY);
+    const struct ByteArray *bytes = decoded.payload;
+    CHECK(bytes->len == DATA_LEN && memcmp(bytes->data, data, DATA_LEN) == 0);
+    airgap_result_free(decoded);
+
+    airgap_decoder_free(decoder);
+    airgap_encoder_free(encoder);
+    return 1;
+}
+
+static int test_payload_take_out(void) {
+    unsigned char data[DATA_LEN];
+    fill_data(data);
+    struct AirgapEncoder *encoder = new_encoder(data, AIRGAP_FORMAT_AIRGAP);
+    CHECK(encoder != NULL);
+
+    // Each payload kind can outlive its result and be freed on its own
+    struct CResult png = airgap_encoder_generate_png(encoder, 0);
+    CHECK(png.code == AIRGAP_OK && png.payload_kind == AIRGAP_PAYLOAD_BYTE_ARRAY);
+    struct ByteArray *png_bytes = (struct ByteArray *)png.payload;
+    png.payload = NULL;
+    airgap_result_free(png);
+    CHECK(png_bytes->len > 8 && memcmp(png_bytes->data, "\x89PNG", 4) == 0);
+    airgap_boxed_byte_array_free(png_bytes);
+
+    struct CResult matrix = airgap_encoder_get_matrix(encoder, 1);
+    CHECK(matrix.code == AIRGAP_OK && matrix.payload_kind == AIRGAP_PAYLOAD_QR_MATRIX);
+    struct QRMatrix *modules = (struct QRMatrix *)matrix.payload;
+    matrix.payload = NULL;
+    airgap_result_free(matrix);
+    CHECK(modules->modules.len == modules->width * modules->width);
+    airgap_qr_matrix_free(modules);
+
+    struct CResult pixels = airgap_encoder_render_pixels(encoder, 2, AIRGAP_PIXEL_FORMAT_LUMA8);
+    CHECK(pixels.code == AIRGAP_OK && pixels.payload_kind == AIRGAP_PAYLOAD_PIXEL_BUFFER);
+    struct QRPixelBuffer *buffer = (struct QRPixelBuffer *)pixels.payload;
+    pixels.payload = NULL;
+    airgap_result_free(pixels);
+    CHECK(buffer->pixels.len == (size_t)buffer->width * buffer->height);
+    airgap_pixel_buffer_free(buffer);
+
+    // ...or go with the result
+    airgap_result_free(airgap_encoder_get_matrix(encoder, 0));
+    airgap_result_free(airgap_encoder_render_pixels(encoder, 0, AIRGAP_PIXEL_FORMAT_RGBA8));
+    airgap_result_free(airgap_encoder_generate_png(encoder, 1));
+
+    // An encoder result freed whole takes the encoder with it
+    airgap_result_free(airgap_encoder_new(data, DATA_LEN, 400, 300));
+
+    // An array copied out of its box is freed by value, the emptied box with the result
+    struct CResult qr = airgap_encoder_get_qr_string(encoder, 0);
+    CHECK(qr.code == AIRGAP_OK);
+    struct ByteArray *boxed = (struct ByteArray *)qr.payload;
+    struct ByteArray copy = *boxed;
+    boxed->data = NULL;
+    boxed->len = 0;
+    airgap_result_free(qr);
+    airgap_byte_array_free(copy);
+
+    airgap_encoder_free(encoder);
+    return 1;
+}
+
+static int test_error_results(void) {
+    struct AirgapDecoder *decoder = airgap_decoder_new();
+
+    struct CResult garbage = airgap_decoder_process_qr(decoder, "NOT A FRAME");
+    CHECK(garbage.code != AIRGAP_OK);
+    CHECK(garbage.payload == NULL && garbage.payload_kind == AIRGAP_PAYLOAD_NONE);
+    CHECK(garbage.error_message != NULL);
+    airgap_result_free(garbage);
+
+    struct CResult incomplete = airgap_decoder_get_data(decoder);
+    CHECK(incomplete.code == AIRGAP_ERR_ENCODING && incomplete.error_message != NULL);
+    airgap_result_free(incomplete);
+
+    struct CResult null_string = airgap_decoder_process_qr(decoder, NULL);
+    CHECK(null_string.code == AIRGAP_ERR_NULL_POINTER && null_string.error_message != NULL);
+    airgap_result_free(null_string);
+
+    struct CResult bad_utf8 = airgap_decoder_process_qr(decoder, "\xff\xfe");
+    CHECK(bad_utf8.code == AIRGAP_ERR_INVALID_UTF8);
+    airgap_result_free(bad_utf8);
+
+    CHECK(airgap_decoder_reset(NULL) == AIRGAP_ERR_NULL_POINTER);
+    CHECK(airgap_decoder_get_status(NULL) == AIRGAP_ERR_NULL_POINTER);
+
+    unsigned char data[DATA_LEN];
+    fill_data(data);
+    struct CResult bad_format = airgap_encoder_new_with_format(data, DATA_LEN, 400, 300, 99);
+    CHECK(bad_format.code == AIRGAP_ERR_UNKNOWN_CODE && bad_format.payload == NULL);
+    airgap_result_free(bad_format);
+
+    struct CResult empty = airgap_encoder_new(data, 0, 400, 300);
+    CHECK(empty.code == AIRGAP_ERR_EMPTY_DATA);
+    airgap_result_free(empty);
+
+    struct AirgapEncoder *encoder = new_encoder(data, AIRGAP_FORMAT_AIRGAP);
+    CHECK(encoder != NULL);
+    struct CResult past_end = airgap_encoder_get_qr_string(encoder, 1000);
+    CHECK(past_end.code == AIRGAP_ERR_CHUNK_OUT_OF_BOUNDS && past_end.payload == NULL);
+    airgap_result_free(past_end);
+    airgap_encoder_free(encoder);
+
+    // Freeing a result that owns nothing is a no-op
+    struct CResult nothing = { AIRGAP_OK, NULL, NULL, AIRGAP_PAYLOAD_NONE };
+    airgap_result_free(nothing);
+
+    airgap_decoder_free(decoder);
+    return 1;
+}
+
+static int test_auto_decoder(void) {
+    unsigned char data[DATA_LEN];
+    fill_data(data);
+    struct AirgapEncoder *encoder = new_encoder(data, AIRGAP_FORMAT_BBQR_BINARY);
+    CHECK(encoder != NULL);
+    struct AirgapAutoDecoder *decoder = airgap_auto_decoder_new();
+
+    size_t count = airgap_encoder_chunk_count(encoder);
+    for (size_t i = 0; i < count; i++) {
+        struct CResult qr = airgap_encoder_get_qr_string(encoder, i);
+        CHECK(qr.code == AIRGAP_OK);
+        struct CResult scanned = airgap_auto_decoder_process_qr(decoder, (const char *)((const struct ByteArray *)qr.payload)->data);
+        CHECK(scanned.code == AIRGAP_OK && scanned.payload_kind == AIRGAP_PAYLOAD_AUTO_QR_RESULT);
+        CHECK(((const struct AutoQRResult *)scanned.payload)->format == AIRGAP_FORMAT_BBQR_BINARY);
+        airgap_result_free(scanned);
+        airgap_result_free(qr);
+    }
+
+    struct CResult decoded = airgap_auto_decoder_get_data(decoder);
+    CHECK(decoded.code == AIRGAP_OK);
+    CHECK(((const struct ByteArray *)decoded.payload)->len == DATA_LEN);
+    airgap_result_free(decoded);
+
+    airgap_auto_decoder_free(decoder);
+    airgap_encoder_free(encoder);
+    return 1;
+}
+
+static int test_qr_config(void) {
+    unsigned char data[DATA_LEN];
+    fill_data(data);
+    unsigned char logo[4 * 4 * 4];
+    memset(logo, 0x80, sizeof logo);
+
+    struct AirgapQrConfig config = airgap_qr_config_default();
+    config.ec_level = AIRGAP_EC_LEVEL_H;
+    config.background[0] = 255;
+    config.background[1] = 0;
+    config.background[2] = 0;
+    config.quiet_zone = 1;
+    config.module_size = 2;
+    config.logo_rgba = logo;
+    config.logo_width = 4;
+    config.logo_height = 4;
+
+    struct CResult created = airgap_encoder_new_with_config(data, DATA_LEN, 300, AIRGAP_FORMAT_AIRGAP, &config);
+    CHECK(created.code == AIRGAP_OK && created.payload_kind == AIRGAP_PAYLOAD_ENCODER);
+    struct AirgapEncoder *encoder = (struct AirgapEncoder *)created.payload;
+
+    struct CResult matrix = airgap_encoder_get_matrix(encoder, 0);
+    CHECK(matrix.code == AIRGAP_OK);
+    size_t width = ((const struct QRMatrix *)matrix.payload)->width;
+    airgap_result_free(matrix);
+
+    // The quiet zone is painted with the configured background
+    struct CResult pixels = airgap_encoder_render_pixels(encoder, 0, AIRGAP_PIXEL_FORMAT_RGBA8);
+    CHECK(pixels.code == AIRGAP_OK);
+    const struct QRPixelBuffer *buffer = pixels.payload;
+    CHECK(buffer->width == (width + 2) * 2);
+    CHECK(memcmp(buffer->pixels.data, "\xff\x00\x00\xff", 4) == 0);
+    airgap_result_free(pixels);
+    airgap_result_free(created);
+
+    config.ec_level = 7;
+    struct CResult bad_level = airgap_encoder_new_with_config(data, DATA_LEN, 300, AIRGAP_FORMAT_AIRGAP, &config);
+    CHECK(bad_level.code == AIRGAP_ERR_UNKNOWN_CODE && bad_level.payload == NULL);
+    airgap_result_free(bad_level);
+
+    // Pixels that don't match the logo dimensions are rejected
+    config.ec_level = AIRGAP_EC_LEVEL_M;
+    config.logo_width = 0;
+    struct CResult bad_logo = airgap_encoder_new_with_config(data, DATA_LEN, 300, AIRGAP_FORMAT_AIRGAP, &config);
+    CHECK(bad_logo.code == AIRGAP_ERR_INVALID_CONFIG);
+    airgap_result_free(bad_logo);
+
+    // So are dimensions whose byte count overflows, before the pixels are read
+    config.logo_width = UINT32_MAX;
+    config.logo_height = UINT32_MAX;
+    struct CResult huge_logo = airgap_encoder_new_with_config(data, DATA_LEN, 300, AIRGAP_FORMAT_AIRGAP, &config);
+    CHECK(huge_logo.code == AIRGAP_ERR_INVALID_CONFIG);
+    airgap_result_free(huge_logo);
+
+    // Sizes past the largest image edge fail before anything is rendered
+    config.logo_rgba = NULL;
+    config.module_size = UINT32_MAX / 2;
+    struct CResult huge_modules = airgap_encoder_new_with_config(data, DATA_LEN, 300, AIRGAP_FORMAT_AIRGAP, &config);
+    CHECK(huge_modules.code == AIRGAP_ERR_INVALID_CONFIG && huge_modules.payload == NULL);
+    airgap_result_free(huge_modules);
+    return 1;
+}
+
+static void count_event(const struct AirgapEvent *event, void *user_data) {
+    size_t *counts = user_data;
+    counts[event->kind]++;
+}
+
+static int test_callback_and_scheduler(void) {
+    unsigned char data[DATA_LEN];
+    fill_data(data);
+    struct AirgapEncoder *encoder = new_encoder(data, AIRGAP_FORMAT_AIRGAP);
+    CHECK(encoder != NULL);
+
+    size_t counts[AIRGAP_EVENT_COMPLETED + 1] = { 0 };
+    struct AirgapDecoder *decoder = airgap_decoder_new();
+    airgap_decoder_set_callback(decoder, count_event, counts);
+
+    struct AirgapScheduler *scheduler = airgap_scheduler_new(encoder, 10.0f);
+    CHECK(scheduler != NULL);
+    size_t count = airgap_encoder_chunk_count(encoder);
+    for (size_t i = 0; i < count + 2; i++) {
+        struct ScheduledFrame frame = airgap_scheduler_advance(scheduler, 100);
+        struct CResult qr = airgap_encoder_get_qr_string(encoder, frame.frame_index);
+        CHECK(qr.code == AIRGAP_OK);
+        airgap_result_free(airgap_decoder_process_qr(decoder, (const char *)((const struct ByteArray *)qr.payload)->data));
+        airgap_result_free(qr);
+    }
+    airgap_result_free(airgap_decoder_process_qr(decoder, "NOT A FRAME"));
+
+    CHECK(airgap_decoder_is_complete(decoder));
+    CHECK(counts[AIRGAP_EVENT_SESSION_STARTED] == 1);
+    CHECK(counts[AIRGAP_EVENT_CHUNK_RECEIVED] == count);
+    CHECK(counts[AIRGAP_EVENT_COMPLETED] == 1);
+    CHECK(counts[AIRGAP_EVENT_ERROR] == 1);
+
+    airgap_scheduler_free(scheduler);
+    airgap_decoder_free(decoder);
+    airgap_encoder_free(encoder);
+    return 1;
+}
+
+int main(void) {
+    int allPassed = 1;
+    allPassed &= test_round_trip();
+    allPassed &= test_payload_take_out();
+    allPassed &= test_error_results();
+    allPassed &= test_auto_decoder();
+    allPassed &= test_qr_config();
+    allPassed &= test_callback_and_scheduler();
+    printf(allPassed ? "✅ C harness passed\n" : "❌ C harness failed\n");
+    return allPassed ? 0 : 1;
+}
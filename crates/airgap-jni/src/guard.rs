@@ -0,0 +1,67 @@
+// src/guard - keeps panics from unwinding into the JVM
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use jni::objects::JObject;
+use jni::sys::{jboolean, jbyteArray, jint, jlong};
+use airgap_core::AirgapError;
+
+/// Run `body`, reporting a panic as `AirgapError::Panic`.
+pub(crate) fn catch_panic<T>(body: impl FnOnce() -> T) -> Result<T, AirgapError> {
+    catch_unwind(AssertUnwindSafe(body)).map_err(|payload| AirgapError::from_panic(&*payload))
+}
+
+/// What a JNI function returns after throwing for a panic in its body; the
+/// JVM ignores it because an `AirgapException` is pending.
+pub(crate) trait PanicFallback {
+    fn panic_fallback() -> Self;
+}
+
+impl PanicFallback for () {
+    fn panic_fallback() -> Self {}
+}
+
+impl PanicFallback for jint {
+    fn panic_fallback() -> Self {
+        0
+    }
+}
+
+impl PanicFallback for jlong {
+    fn panic_fallback() -> Self {
+        0
+    }
+}
+
+impl PanicFallback for jboolean {
+    fn panic_fallback() -> Self {
+        0
+    }
+}
+
+impl PanicFallback for jbyteArray {
+    fn panic_fallback() -> Self {
+        std::ptr::null_mut()
+    }
+}
+
+impl PanicFallback for JObject<'_> {
+    fn panic_fallback() -> Self {
+        JObject::null()
+    }
+}
+
+/// Run a JNI function body, turning a panic into an `AirgapException`
+/// carrying `AIRGAP_ERR_PANIC`. An exception already pending, e.g. from a
+/// listener callback, is replaced.
+macro_rules! jni_guard {
+    ($env:ident, $body:block) => {
+        match $crate::guard::catch_panic(|| $body) {
+            Ok(value) => value,
+            Err(error) => {
+                let _ = $env.exception_clear();
+                $crate::throw_exception(&mut $env, &error);
+                $crate::guard::PanicFallback::panic_fallback()
+            }
+        }
+    };
+}
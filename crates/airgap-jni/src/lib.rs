@@ -0,0 +1,1163 @@
+// src/lib - JNI interface for Android and desktop JVMs
+
+use jni::{JNIEnv, JavaVM};
+use jni::objects::{GlobalRef, JClass, JByteArray, JByteBuffer, JIntArray, JObject, JThrowable, JValue};
+use jni::sys::{jlong, jint, jfloat, jboolean, jbyteArray};
+use airgap_core::{AirgapError, AutoDecoder, DecoderEvent, DecoderLimits, DecoderObserver, DecoderTimeouts, FrameFormat, SharedDecoder};
+use airgap_core::codes::{decoder_status_to_code, detected_format_to_code, frame_format_from_code};
+use airgap_render::{DisplayScheduler, Encoder, FrameIndicator, PixelBuffer, PixelFormat, QrConfig, QrLogo, QrMatrix};
+use airgap_render::codes::{ec_level_from_code, pixel_format_from_code};
+
+#[macro_use]
+mod guard;
+
+// Throw an AirgapException carrying the error's code, kind and fields
+fn throw_exception(env: &mut JNIEnv, error: &AirgapError) {
+    let thrown = new_exception(env, error).and_then(|exception| env.throw(JThrowable::from(exception)));
+    if thrown.is_err() {
+        let _ = env.exception_clear();
+        let _ = env.throw_new("app/rkz/airgap/AirgapException", error.to_string());
+    }
+}
+
+fn new_exception<'local>(env: &mut JNIEnv<'local>, error: &AirgapError) -> jni::errors::Result<JObject<'local>> {
+    let message = env.new_string(error.to_string())?;
+    let kind = env.new_string(error.kind())?;
+    // -1 marks a field the error doesn't carry
+    env.new_object(
+        "app/rkz/airgap/AirgapException",
+        "(Ljava/lang/String;ILjava/lang/String;IJJ)V",
+        &[
+            JValue::Object(&message),
+            JValue::Int(error.to_code()),
+            JValue::Object(&kind),
+            JValue::Int(error.chunk_index().map_or(-1, jint::from)),
+            JValue::Long(error.value().map_or(-1, |value| value as jlong)),
+            JValue::Long(error.limit().map_or(-1, |limit| limit as jlong)),
+        ],
+    )
+}
+
+// ============================================================================
+// ENCODER JNI FUNCTIONS
+// ============================================================================
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapEncoder_nativeNew<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    data: JByteArray<'local>,
+    chunk_size: jint,
+    format: jint,
+    has_session_id: jboolean,
+    session_id: jint,
+    config: JObject<'local>,
+) -> jlong {
+    jni_guard!(env, {
+        let Some(format) = frame_format_from_code(format) else {
+            throw_exception(&mut env, &AirgapError::UnknownCode("frame format", format));
+            return 0;
+        };
+        if has_session_id != 0 && format != FrameFormat::Airgap {
+            throw_exception(&mut env, &AirgapError::InvalidConfig(
+                "a fixed session ID is only supported for native frames".to_string()
+            ));
+            return 0;
+        }
+        let config = match qr_config_from_java(&mut env, &config) {
+            Ok(config) => config,
+            Err(err) => {
+                throw_exception(&mut env, &err);
+                return 0;
+            }
+        };
+
+        new_encoder(&mut env, &data, |data| match has_session_id != 0 {
+            true => Encoder::with_session_id(data, chunk_size as usize, config, session_id as u32),
+            false => Encoder::with_format(data, chunk_size as usize, config, format),
+        })
+    })
+}
+
+// Read a Kotlin `QrConfig`, and its `QrLogo` if set, field by field
+fn qr_config_from_java(env: &mut JNIEnv, config: &JObject) -> Result<QrConfig, AirgapError> {
+    let binding = |e: jni::errors::Error| AirgapError::BindingError(format!("Failed to read QrConfig: {}", e));
+    if config.is_null() {
+        return Err(AirgapError::NullPointer("config"));
+    }
+    let int = |env: &mut JNIEnv, object: &JObject, name: &str| env.get_field(object, name, "I").and_then(|v| v.i());
+    let flag = |env: &mut JNIEnv, name: &str| env.get_field(config, name, "Z").and_then(|v| v.z());
+
+    let ec_level = env.get_field(config, "ecLevel", "Lapp/rkz/airgap/EcLevel;").and_then(|v| v.l()).map_err(binding)?;
+    let ec_code = int(env, &ec_level, "code").map_err(binding)?;
+    let ec_level = ec_level_from_code(ec_code).ok_or(AirgapError::UnknownCode("EC level", ec_code))?;
+
+    let logo = env.get_field(config, "logo", "Lapp/rkz/airgap/QrLogo;").and_then(|v| v.l()).map_err(binding)?;
+    let logo = match logo.is_null() {
+        true => None,
+        false => {
+            let width = int(env, &logo, "width").map_err(binding)?;
+            let height = int(env, &logo, "height").map_err(binding)?;
+            let size_ratio = env.get_field(&logo, "sizeRatio", "F").and_then(|v| v.f()).map_err(binding)?;
+            let rgba = JByteArray::from(env.get_field(&logo, "rgba", "[B").and_then(|v| v.l()).map_err(binding)?);
+            let rgba = env.convert_byte_array(&rgba).map_err(binding)?;
+            Some(QrLogo::from_rgba(width.max(0) as u32, height.max(0) as u32, rgba, size_ratio)?)
+        }
+    };
+
+    let module_size = int(env, config, "moduleSize").map_err(binding)?;
+    let config = QrConfig {
+        ec_level,
+        qr_size: int(env, config, "qrSize").map_err(binding)?.max(0) as u32,
+        foreground: argb_to_rgba(int(env, config, "foreground").map_err(binding)?),
+        background: argb_to_rgba(int(env, config, "background").map_err(binding)?),
+        quiet_zone: int(env, config, "quietZone").map_err(binding)?.max(0) as u32,
+        module_size: (module_size > 0).then_some(module_size as u32),
+        invert: flag(env, "invert").map_err(binding)?,
+        logo,
+        indicator: FrameIndicator {
+            caption: flag(env, "caption").map_err(binding)?,
+            session_tag: flag(env, "sessionTag").map_err(binding)?,
+            progress_bar: flag(env, "progressBar").map_err(binding)?,
+        },
+    };
+    config.check_sizes()?;
+    Ok(config)
+}
+
+// Android color ints are 0xAARRGGBB
+fn argb_to_rgba(color: jint) -> [u8; 4] {
+    let [a, r, g, b] = (color as u32).to_be_bytes();
+    [r, g, b, a]
+}
+
+fn new_encoder(
+    env: &mut JNIEnv,
+    data: &JByteArray,
+    build: impl FnOnce(&[u8]) -> Result<Encoder, AirgapError>,
+) -> jlong {
+    let data_bytes: Vec<u8> = match env.convert_byte_array(data) {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            throw_exception(env, &AirgapError::BindingError("Failed to convert byte array".to_string()));
+            return 0;
+        }
+    };
+
+    match build(&data_bytes) {
+        Ok(encoder) => Box::into_raw(Box::new(encoder)) as jlong,
+        Err(err) => {
+            throw_exception(env, &err);
+            0
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapEncoder_nativeFree(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    jni_guard!(env, {
+        if handle != 0 {
+            unsafe {
+                drop(Box::from_raw(handle as *mut Encoder));
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapEncoder_nativeChunkCount(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jint {
+    jni_guard!(env, {
+        if handle == 0 {
+            return 0;
+        }
+        let encoder = unsafe { &*(handle as *const Encoder) };
+        encoder.chunk_count() as jint
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapEncoder_nativeSessionId(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jint {
+    jni_guard!(env, {
+        if handle == 0 {
+            return 0;
+        }
+        let encoder = unsafe { &*(handle as *const Encoder) };
+        encoder.session_id() as jint
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapEncoder_nativeGetQRString<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    index: jint,
+) -> JObject<'local> {
+    jni_guard!(env, {
+        if handle == 0 {
+            throw_exception(&mut env, &AirgapError::NullPointer("encoder"));
+            return JObject::null();
+        }
+
+        let encoder = unsafe { &*(handle as *const Encoder) };
+
+        match encoder.get_qr_string(index as usize) {
+            Ok(qr_string) => match env.new_string(&qr_string) {
+                Ok(s) => s.into(),
+                Err(_) => {
+                    throw_exception(&mut env, &AirgapError::BindingError("Failed to create Java string".to_string()));
+                    JObject::null()
+                }
+            },
+            Err(err) => {
+                throw_exception(&mut env, &err);
+                JObject::null()
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapEncoder_nativeGeneratePng<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    index: jint,
+) -> jbyteArray {
+    jni_guard!(env, {
+        if handle == 0 {
+            throw_exception(&mut env, &AirgapError::NullPointer("encoder"));
+            return JObject::null().into_raw();
+        }
+
+        let encoder = unsafe { &*(handle as *const Encoder) };
+
+        match encoder.generate_png_bytes_for_item(index as usize) {
+            Ok(png_bytes) => match env.byte_array_from_slice(&png_bytes) {
+                Ok(arr) => arr.into_raw(),
+                Err(_) => {
+                    throw_exception(&mut env, &AirgapError::BindingError("Failed to create Java byte array".to_string()));
+                    JObject::null().into_raw()
+                }
+            },
+            Err(err) => {
+                throw_exception(&mut env, &err);
+                JObject::null().into_raw()
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapEncoder_nativeGetMatrix<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    index: jint,
+) -> JObject<'local> {
+    jni_guard!(env, {
+        if handle == 0 {
+            throw_exception(&mut env, &AirgapError::NullPointer("encoder"));
+            return JObject::null();
+        }
+
+        let encoder = unsafe { &*(handle as *const Encoder) };
+
+        match encoder.qr_matrix(index as usize) {
+            Ok(matrix) => match new_matrix_object(&mut env, &matrix) {
+                Ok(obj) => obj,
+                Err(_) => {
+                    throw_exception(&mut env, &AirgapError::BindingError("Failed to create QrMatrix object".to_string()));
+                    JObject::null()
+                }
+            },
+            Err(err) => {
+                throw_exception(&mut env, &err);
+                JObject::null()
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapEncoder_nativeRenderPixels<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    index: jint,
+    format: jint,
+) -> JObject<'local> {
+    jni_guard!(env, {
+        if handle == 0 {
+            throw_exception(&mut env, &AirgapError::NullPointer("encoder"));
+            return JObject::null();
+        }
+
+        let Some(pixel_format) = pixel_format_from_code(format) else {
+            throw_exception(&mut env, &AirgapError::UnknownCode("pixel format", format));
+            return JObject::null();
+        };
+
+        let encoder = unsafe { &*(handle as *const Encoder) };
+
+        match encoder.render_pixels(index as usize, pixel_format) {
+            Ok(buffer) => match new_pixels_object(&mut env, &buffer, format) {
+                Ok(obj) => obj,
+                Err(_) => {
+                    throw_exception(&mut env, &AirgapError::BindingError("Failed to create QrPixels object".to_string()));
+                    JObject::null()
+                }
+            },
+            Err(err) => {
+                throw_exception(&mut env, &err);
+                JObject::null()
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapEncoder_nativeRenderArgb<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    index: jint,
+) -> JObject<'local> {
+    jni_guard!(env, {
+        if handle == 0 {
+            throw_exception(&mut env, &AirgapError::NullPointer("encoder"));
+            return JObject::null();
+        }
+
+        let encoder = unsafe { &*(handle as *const Encoder) };
+
+        match encoder.render_pixels(index as usize, PixelFormat::Rgba8) {
+            Ok(buffer) => match new_bitmap_object(&mut env, &buffer) {
+                Ok(obj) => obj,
+                Err(_) => {
+                    throw_exception(&mut env, &AirgapError::BindingError("Failed to create QrBitmap object".to_string()));
+                    JObject::null()
+                }
+            },
+            Err(err) => {
+                throw_exception(&mut env, &err);
+                JObject::null()
+            }
+        }
+    })
+}
+
+fn new_matrix_object<'local>(env: &mut JNIEnv<'local>, matrix: &QrMatrix) -> jni::errors::Result<JObject<'local>> {
+    let modules = env.byte_array_from_slice(&matrix.modules)?;
+    env.new_object(
+        "app/rkz/airgap/QrMatrix",
+        "(I[B)V",
+        &[JValue::Int(matrix.width as jint), JValue::Object(&modules)],
+    )
+}
+
+// Copies into a Java-owned direct buffer so the pixels outlive this call
+fn new_pixels_object<'local>(env: &mut JNIEnv<'local>, buffer: &PixelBuffer, format: jint) -> jni::errors::Result<JObject<'local>> {
+    let direct = env
+        .call_static_method(
+            "java/nio/ByteBuffer",
+            "allocateDirect",
+            "(I)Ljava/nio/ByteBuffer;",
+            &[JValue::Int(buffer.data.len() as jint)],
+        )?
+        .l()?;
+    let direct = JByteBuffer::from(direct);
+    let address = env.get_direct_buffer_address(&direct)?;
+    unsafe { std::ptr::copy_nonoverlapping(buffer.data.as_ptr(), address, buffer.data.len()) };
+
+    env.new_object(
+        "app/rkz/airgap/QrPixels",
+        "(IIILjava/nio/ByteBuffer;)V",
+        &[
+            JValue::Int(buffer.width as jint),
+            JValue::Int(buffer.height as jint),
+            JValue::Int(format),
+            JValue::Object(&direct),
+        ],
+    )
+}
+
+fn new_bitmap_object<'local>(env: &mut JNIEnv<'local>, buffer: &PixelBuffer) -> jni::errors::Result<JObject<'local>> {
+    // Android's ARGB_8888 color ints are 0xAARRGGBB
+    let argb: Vec<jint> = buffer.data
+        .chunks_exact(4)
+        .map(|p| i32::from_be_bytes([p[3], p[0], p[1], p[2]]))
+        .collect();
+    let pixels = env.new_int_array(argb.len() as jint)?;
+    env.set_int_array_region(&pixels, 0, &argb)?;
+
+    env.new_object(
+        "app/rkz/airgap/QrBitmap",
+        "(II[I)V",
+        &[
+            JValue::Int(buffer.width as jint),
+            JValue::Int(buffer.height as jint),
+            JValue::Object(&pixels),
+        ],
+    )
+}
+
+// ============================================================================
+// DECODER JNI FUNCTIONS
+// ============================================================================
+
+// Decoder handles point at a `SharedDecoder`, so every call below may run on
+// any thread. Kotlin guarantees `nativeFree` never overlaps another call.
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDecoder_nativeNew(
+    mut env: JNIEnv,
+    _class: JClass,
+) -> jlong {
+    jni_guard!(env, {
+        Box::into_raw(Box::new(SharedDecoder::new())) as jlong
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDecoder_nativeNewWithFormat(
+    mut env: JNIEnv,
+    _class: JClass,
+    format: jint,
+) -> jlong {
+    jni_guard!(env, {
+        match frame_format_from_code(format) {
+            Some(format) => Box::into_raw(Box::new(SharedDecoder::with_format(format))) as jlong,
+            None => {
+                throw_exception(&mut env, &AirgapError::UnknownCode("frame format", format));
+                0
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDecoder_nativeFree(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    jni_guard!(env, {
+        if handle != 0 {
+            unsafe {
+                drop(Box::from_raw(handle as *mut SharedDecoder));
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDecoder_nativeIsComplete(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jboolean {
+    jni_guard!(env, {
+        if handle == 0 {
+            return 0;
+        }
+        let decoder = unsafe { &*(handle as *const SharedDecoder) };
+        if decoder.is_complete() { 1 } else { 0 }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDecoder_nativeGetTotal(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jint {
+    jni_guard!(env, {
+        if handle == 0 {
+            return 0;
+        }
+        let decoder = unsafe { &*(handle as *const SharedDecoder) };
+        decoder.total_count() as jint
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDecoder_nativeGetReceived(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jint {
+    jni_guard!(env, {
+        if handle == 0 {
+            return 0;
+        }
+        let decoder = unsafe { &*(handle as *const SharedDecoder) };
+        decoder.received_count() as jint
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDecoder_nativeGetSessionId(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jint {
+    jni_guard!(env, {
+        if handle == 0 {
+            return -1;
+        }
+        let decoder = unsafe { &*(handle as *const SharedDecoder) };
+        decoder.session_id().map(|id| id as jint).unwrap_or(-1)
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDecoder_nativeReset(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    jni_guard!(env, {
+        if handle == 0 {
+            return;
+        }
+        let decoder = unsafe { &*(handle as *const SharedDecoder) };
+        decoder.reset();
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDecoder_nativeSetTimeouts(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    idle_ms: jlong,
+    total_ms: jlong,
+    auto_expire: jboolean,
+) {
+    jni_guard!(env, {
+        if handle == 0 {
+            return;
+        }
+        let decoder = unsafe { &*(handle as *const SharedDecoder) };
+        decoder.set_timeouts(DecoderTimeouts {
+            idle_ms: idle_ms.max(0) as u64,
+            total_ms: total_ms.max(0) as u64,
+            auto_expire: auto_expire != 0,
+        });
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDecoder_nativeSetLimits(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    max_total_chunks: jint,
+    max_payload_bytes: jlong,
+    max_sessions: jint,
+    max_frame_len: jint,
+) {
+    jni_guard!(env, {
+        if handle == 0 {
+            return;
+        }
+        let decoder = unsafe { &*(handle as *const SharedDecoder) };
+        decoder.set_limits(DecoderLimits {
+            max_total_chunks: max_total_chunks.max(0) as usize,
+            max_payload_bytes: max_payload_bytes.max(0) as usize,
+            max_sessions: max_sessions.max(0) as usize,
+            max_frame_len: max_frame_len.max(0) as usize,
+        });
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDecoder_nativeGetStatus(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jint {
+    jni_guard!(env, {
+        if handle == 0 {
+            return -1;
+        }
+        let decoder = unsafe { &*(handle as *const SharedDecoder) };
+        decoder_status_to_code(decoder.status())
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDecoder_nativeExpireStale(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jboolean {
+    jni_guard!(env, {
+        if handle == 0 {
+            return 0;
+        }
+        let decoder = unsafe { &*(handle as *const SharedDecoder) };
+        if decoder.expire_stale() { 1 } else { 0 }
+    })
+}
+
+// Forwards decoder events to a Kotlin `DecoderListener`
+struct JniObserver {
+    vm: JavaVM,
+    listener: GlobalRef,
+}
+
+impl DecoderObserver for JniObserver {
+    fn on_event(&mut self, event: &DecoderEvent) {
+        let Ok(mut env) = self.vm.attach_current_thread() else {
+            return;
+        };
+        // Leave an exception thrown by an earlier callback for the caller to see
+        if env.exception_check().unwrap_or(true) {
+            return;
+        }
+
+        let listener = self.listener.as_obj();
+        let _ = match event {
+            DecoderEvent::SessionStarted { session_id, total_chunks } => env.call_method(
+                listener,
+                "onSessionStart",
+                "(II)V",
+                &[JValue::Int(*session_id as jint), JValue::Int(*total_chunks as jint)],
+            ),
+            DecoderEvent::ChunkReceived { chunk, received, total } => env.call_method(
+                listener,
+                "onChunk",
+                "(III)V",
+                &[
+                    JValue::Int(chunk.chunk_index as jint),
+                    JValue::Int(*received as jint),
+                    JValue::Int(*total as jint),
+                ],
+            ),
+            DecoderEvent::Duplicate { chunk } => env.call_method(
+                listener,
+                "onDuplicate",
+                "(I)V",
+                &[JValue::Int(chunk.chunk_index as jint)],
+            ),
+            DecoderEvent::Conflict(error) | DecoderEvent::Error(error) => {
+                let method = match event {
+                    DecoderEvent::Conflict(_) => "onConflict",
+                    _ => "onError",
+                };
+                match env.new_string(error.to_string()) {
+                    Ok(message) => env.call_method(
+                        listener,
+                        method,
+                        "(ILjava/lang/String;)V",
+                        &[JValue::Int(error.to_code()), JValue::Object(&message)],
+                    ),
+                    Err(err) => Err(err),
+                }
+            }
+            DecoderEvent::Completed => env.call_method(listener, "onComplete", "()V", &[]),
+        };
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDecoder_nativeSetListener<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    listener: JObject<'local>,
+) {
+    jni_guard!(env, {
+        if handle == 0 {
+            throw_exception(&mut env, &AirgapError::NullPointer("decoder"));
+            return;
+        }
+
+        let decoder = unsafe { &*(handle as *const SharedDecoder) };
+
+        if listener.is_null() {
+            decoder.clear_observer();
+            return;
+        }
+
+        let observer = env.get_java_vm().and_then(|vm| {
+            Ok(JniObserver { vm, listener: env.new_global_ref(&listener)? })
+        });
+        match observer {
+            Ok(observer) => decoder.set_observer(Box::new(observer)),
+            Err(_) => {
+                throw_exception(&mut env, &AirgapError::BindingError("Failed to register listener".to_string()));
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDecoder_nativeProcessQr<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    qr_string: JObject<'local>,
+) -> JObject<'local> {
+    jni_guard!(env, {
+        if handle == 0 {
+            throw_exception(&mut env, &AirgapError::NullPointer("decoder"));
+            return JObject::null();
+        }
+
+        let jstring: jni::objects::JString = qr_string.into();
+        let qr_str = match env.get_string(&jstring) {
+            Ok(s) => s,
+            Err(_) => {
+                throw_exception(&mut env, &AirgapError::BindingError("Failed to get string".to_string()));
+                return JObject::null();
+            }
+        };
+
+        let qr_data: String = qr_str.into();
+        let decoder = unsafe { &*(handle as *const SharedDecoder) };
+
+        match decoder.process_qr_string(&qr_data) {
+            Ok(chunk) => {
+                // Create QRResult Java object
+                let qr_result_class = match env.find_class("app/rkz/airgap/QRResult") {
+                    Ok(cls) => cls,
+                    Err(_) => {
+                        throw_exception(&mut env, &AirgapError::BindingError("Failed to find QRResult class".to_string()));
+                        return JObject::null();
+                    }
+                };
+
+                match env.new_object(
+                    qr_result_class,
+                    "(II)V",
+                    &[
+                        jni::objects::JValue::Int(chunk.chunk_index as jint),
+                        jni::objects::JValue::Int(chunk.total_chunks as jint),
+                    ],
+                ) {
+                    Ok(obj) => obj,
+                    Err(_) => {
+                        throw_exception(&mut env, &AirgapError::BindingError("Failed to create QRResult object".to_string()));
+                        JObject::null()
+                    }
+                }
+            }
+            Err(err) => {
+                throw_exception(&mut env, &err);
+                JObject::null()
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDecoder_nativeGetData<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) -> jbyteArray {
+    jni_guard!(env, {
+        if handle == 0 {
+            throw_exception(&mut env, &AirgapError::NullPointer("decoder"));
+            return JObject::null().into_raw();
+        }
+
+        let decoder = unsafe { &*(handle as *const SharedDecoder) };
+
+        match decoder.get_data() {
+            Ok(data) => match env.byte_array_from_slice(&data) {
+                Ok(arr) => arr.into_raw(),
+                Err(_) => {
+                    throw_exception(&mut env, &AirgapError::BindingError("Failed to create Java byte array".to_string()));
+                    JObject::null().into_raw()
+                }
+            },
+            Err(err) => {
+                throw_exception(&mut env, &err);
+                JObject::null().into_raw()
+            }
+        }
+    })
+}
+
+// ============================================================================
+// AUTO DECODER JNI FUNCTIONS
+// ============================================================================
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapAutoDecoder_nativeNew(
+    mut env: JNIEnv,
+    _class: JClass,
+) -> jlong {
+    jni_guard!(env, {
+        Box::into_raw(Box::new(AutoDecoder::new())) as jlong
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapAutoDecoder_nativeFree(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    jni_guard!(env, {
+        if handle != 0 {
+            unsafe {
+                drop(Box::from_raw(handle as *mut AutoDecoder));
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapAutoDecoder_nativeGetFormat(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jint {
+    jni_guard!(env, {
+        if handle == 0 {
+            return detected_format_to_code(None);
+        }
+        let decoder = unsafe { &*(handle as *const AutoDecoder) };
+        detected_format_to_code(decoder.format())
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapAutoDecoder_nativeIsComplete(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jboolean {
+    jni_guard!(env, {
+        if handle == 0 {
+            return 0;
+        }
+        let decoder = unsafe { &*(handle as *const AutoDecoder) };
+        if decoder.is_complete() { 1 } else { 0 }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapAutoDecoder_nativeGetTotal(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jint {
+    jni_guard!(env, {
+        if handle == 0 {
+            return 0;
+        }
+        let decoder = unsafe { &*(handle as *const AutoDecoder) };
+        decoder.total_count() as jint
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapAutoDecoder_nativeGetReceived(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jint {
+    jni_guard!(env, {
+        if handle == 0 {
+            return 0;
+        }
+        let decoder = unsafe { &*(handle as *const AutoDecoder) };
+        decoder.received_count() as jint
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapAutoDecoder_nativeReset(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    jni_guard!(env, {
+        if handle == 0 {
+            return;
+        }
+        let decoder = unsafe { &mut *(handle as *mut AutoDecoder) };
+        decoder.reset();
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapAutoDecoder_nativeProcessQr<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    qr_string: JObject<'local>,
+) -> JObject<'local> {
+    jni_guard!(env, {
+        if handle == 0 {
+            throw_exception(&mut env, &AirgapError::NullPointer("decoder"));
+            return JObject::null();
+        }
+
+        let jstring: jni::objects::JString = qr_string.into();
+        let qr_data: String = match env.get_string(&jstring) {
+            Ok(s) => s.into(),
+            Err(_) => {
+                throw_exception(&mut env, &AirgapError::BindingError("Failed to get string".to_string()));
+                return JObject::null();
+            }
+        };
+
+        let decoder = unsafe { &mut *(handle as *mut AutoDecoder) };
+
+        match decoder.process_qr_string(&qr_data) {
+            Ok(frame) => {
+                let result_class = match env.find_class("app/rkz/airgap/AutoQRResult") {
+                    Ok(cls) => cls,
+                    Err(_) => {
+                        throw_exception(&mut env, &AirgapError::BindingError("Failed to find AutoQRResult class".to_string()));
+                        return JObject::null();
+                    }
+                };
+
+                match env.new_object(
+                    result_class,
+                    "(III)V",
+                    &[
+                        jni::objects::JValue::Int(frame.chunk.chunk_index as jint),
+                        jni::objects::JValue::Int(frame.chunk.total_chunks as jint),
+                        jni::objects::JValue::Int(detected_format_to_code(Some(frame.format))),
+                    ],
+                ) {
+                    Ok(obj) => obj,
+                    Err(_) => {
+                        throw_exception(&mut env, &AirgapError::BindingError("Failed to create AutoQRResult object".to_string()));
+                        JObject::null()
+                    }
+                }
+            }
+            Err(err) => {
+                throw_exception(&mut env, &err);
+                JObject::null()
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapAutoDecoder_nativeGetData<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) -> jbyteArray {
+    jni_guard!(env, {
+        if handle == 0 {
+            throw_exception(&mut env, &AirgapError::NullPointer("decoder"));
+            return JObject::null().into_raw();
+        }
+
+        let decoder = unsafe { &*(handle as *const AutoDecoder) };
+
+        match decoder.get_data() {
+            Ok(data) => match env.byte_array_from_slice(&data) {
+                Ok(arr) => arr.into_raw(),
+                Err(_) => {
+                    throw_exception(&mut env, &AirgapError::BindingError("Failed to create Java byte array".to_string()));
+                    JObject::null().into_raw()
+                }
+            },
+            Err(err) => {
+                throw_exception(&mut env, &err);
+                JObject::null().into_raw()
+            }
+        }
+    })
+}
+
+// ============================================================================
+// DISPLAY SCHEDULER JNI FUNCTIONS
+// ============================================================================
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDisplayScheduler_nativeNew(
+    mut env: JNIEnv,
+    _class: JClass,
+    encoder_handle: jlong,
+    frames_per_second: jfloat,
+) -> jlong {
+    jni_guard!(env, {
+        if encoder_handle == 0 {
+            throw_exception(&mut env, &AirgapError::NullPointer("encoder"));
+            return 0;
+        }
+        let encoder = unsafe { &*(encoder_handle as *const Encoder) };
+        Box::into_raw(Box::new(DisplayScheduler::new(encoder, frames_per_second))) as jlong
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDisplayScheduler_nativeFree(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    jni_guard!(env, {
+        if handle != 0 {
+            unsafe {
+                drop(Box::from_raw(handle as *mut DisplayScheduler));
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDisplayScheduler_nativeSetFrameRate(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    frames_per_second: jfloat,
+) {
+    jni_guard!(env, {
+        if handle == 0 {
+            return;
+        }
+        let scheduler = unsafe { &mut *(handle as *mut DisplayScheduler) };
+        scheduler.set_frame_rate(frames_per_second);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDisplayScheduler_nativeSetFountainInterval(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    every: jint,
+) {
+    jni_guard!(env, {
+        if handle == 0 {
+            return;
+        }
+        let scheduler = unsafe { &mut *(handle as *mut DisplayScheduler) };
+        scheduler.set_fountain_interval(every.max(0) as usize);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDisplayScheduler_nativeReportMissing<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    indices: JIntArray<'local>,
+) {
+    jni_guard!(env, {
+        if handle == 0 {
+            return;
+        }
+
+        let len = match env.get_array_length(&indices) {
+            Ok(len) => len as usize,
+            Err(_) => {
+                throw_exception(&mut env, &AirgapError::BindingError("Failed to read indices".to_string()));
+                return;
+            }
+        };
+        let mut buf = vec![0 as jint; len];
+        if env.get_int_array_region(&indices, 0, &mut buf).is_err() {
+            throw_exception(&mut env, &AirgapError::BindingError("Failed to read indices".to_string()));
+            return;
+        }
+
+        let indices: Vec<usize> = buf.into_iter()
+            .filter_map(|index| usize::try_from(index).ok())
+            .collect();
+        let scheduler = unsafe { &mut *(handle as *mut DisplayScheduler) };
+        scheduler.report_missing(&indices);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDisplayScheduler_nativeAdvance<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    elapsed_ms: jlong,
+) -> JObject<'local> {
+    jni_guard!(env, {
+        if handle == 0 {
+            throw_exception(&mut env, &AirgapError::NullPointer("scheduler"));
+            return JObject::null();
+        }
+
+        let scheduler = unsafe { &mut *(handle as *mut DisplayScheduler) };
+        let frame = scheduler.advance(elapsed_ms.max(0) as u64);
+
+        match env.new_object(
+            "app/rkz/airgap/ScheduledFrame",
+            "(IZJ)V",
+            &[
+                JValue::Int(frame.index as jint),
+                JValue::Bool(frame.changed as jboolean),
+                JValue::Long(frame.next_change_ms as jlong),
+            ],
+        ) {
+            Ok(obj) => obj,
+            Err(_) => {
+                throw_exception(&mut env, &AirgapError::BindingError("Failed to create ScheduledFrame object".to_string()));
+                JObject::null()
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_app_rkz_airgap_AirgapDisplayScheduler_nativeReset(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    jni_guard!(env, {
+        if handle == 0 {
+            return;
+        }
+        let scheduler = unsafe { &mut *(handle as *mut DisplayScheduler) };
+        scheduler.reset();
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use jni::objects::JObject;
+    use jni::sys::{jbyteArray, jint};
+    use airgap_core::error::AIRGAP_ERR_PANIC;
+    use crate::guard::{catch_panic, PanicFallback};
+
+    #[test]
+    fn test_catch_panic() {
+        let error = catch_panic(|| -> jint {
+            let chunks: Vec<jint> = Vec::new();
+            chunks[3]
+        }).unwrap_err();
+        assert_eq!(error.to_code(), AIRGAP_ERR_PANIC);
+        assert!(error.to_string().contains("index out of bounds"), "{}", error);
+
+        let error = catch_panic(|| -> JObject<'static> { panic!("no {} here", "matrix") }).unwrap_err();
+        assert_eq!(error.to_string(), "Internal panic: no matrix here");
+
+        assert_eq!(catch_panic(|| 7).unwrap(), 7);
+        assert!(JObject::panic_fallback().is_null());
+        assert!(<jbyteArray as PanicFallback>::panic_fallback().is_null());
+    }
+}
@@ -0,0 +1,96 @@
+// src/cache - bounded LRU of rendered frames, keyed by (frame index, config)
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use crate::render::ConfigKey;
+
+/// Frames kept by a new `Encoder` before the least recently used is dropped.
+pub const DEFAULT_CACHE_CAPACITY: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    /// Frames currently cached.
+    pub len: usize,
+    pub capacity: usize,
+}
+
+// (frame index, `QrConfig::cache_key`); the config is shared by every
+// frame rendered with it
+pub(crate) type Key = (usize, Arc<ConfigKey>);
+
+#[derive(Debug)]
+pub(crate) struct FrameCache {
+    entries: HashMap<Key, Vec<u8>>,
+    // Least recently used first
+    order: VecDeque<Key>,
+    stats: CacheStats,
+}
+
+impl FrameCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            stats: CacheStats { capacity, ..CacheStats::default() },
+        }
+    }
+
+    pub fn get(&mut self, key: &Key) -> Option<Vec<u8>> {
+        match self.entries.get(key) {
+            Some(png) => {
+                self.stats.hits += 1;
+                if let Some(pos) = self.order.iter().position(|k| k == key) {
+                    self.order.remove(pos);
+                }
+                self.order.push_back(key.clone());
+                Some(png.clone())
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn contains(&self, key: &Key) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn insert(&mut self, key: Key, png: Vec<u8>) {
+        if self.stats.capacity == 0 {
+            return;
+        }
+        if self.entries.insert(key.clone(), png).is_some() {
+            // Rendered concurrently by another caller; keep the existing position
+            return;
+        }
+        self.order.push_back(key);
+        self.evict_to(self.stats.capacity);
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.stats.capacity = capacity;
+        self.evict_to(capacity);
+    }
+
+    fn evict_to(&mut self, capacity: usize) {
+        while self.order.len() > capacity {
+            if let Some(old) = self.order.pop_front() {
+                self.entries.remove(&old);
+                self.stats.evictions += 1;
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats { len: self.entries.len(), ..self.stats }
+    }
+}
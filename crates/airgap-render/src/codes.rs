@@ -0,0 +1,39 @@
+// src/codes - pixel format and error correction codes shared by the C, JNI and wasm bindings
+
+use core::ffi::c_int;
+#[cfg(not(cbindgen))]
+use crate::{EcLevel, PixelFormat};
+
+/// 4 bytes per pixel, R G B A.
+pub const AIRGAP_PIXEL_FORMAT_RGBA8: c_int = 0;
+/// 1 byte per pixel, grayscale.
+pub const AIRGAP_PIXEL_FORMAT_LUMA8: c_int = 1;
+
+#[cfg(not(cbindgen))]
+pub fn pixel_format_from_code(code: c_int) -> Option<PixelFormat> {
+    match code {
+        AIRGAP_PIXEL_FORMAT_RGBA8 => Some(PixelFormat::Rgba8),
+        AIRGAP_PIXEL_FORMAT_LUMA8 => Some(PixelFormat::Luma8),
+        _ => None,
+    }
+}
+
+/// Recovers about 7% of the symbol.
+pub const AIRGAP_EC_LEVEL_L: c_int = 0;
+/// Recovers about 15% of the symbol; the default.
+pub const AIRGAP_EC_LEVEL_M: c_int = 1;
+/// Recovers about 25% of the symbol.
+pub const AIRGAP_EC_LEVEL_Q: c_int = 2;
+/// Recovers about 30% of the symbol.
+pub const AIRGAP_EC_LEVEL_H: c_int = 3;
+
+#[cfg(not(cbindgen))]
+pub fn ec_level_from_code(code: c_int) -> Option<EcLevel> {
+    match code {
+        AIRGAP_EC_LEVEL_L => Some(EcLevel::L),
+        AIRGAP_EC_LEVEL_M => Some(EcLevel::M),
+        AIRGAP_EC_LEVEL_Q => Some(EcLevel::Q),
+        AIRGAP_EC_LEVEL_H => Some(EcLevel::H),
+        _ => None,
+    }
+}
@@ -0,0 +1,274 @@
+// encoder.rs
+use airgap_core::AirgapError;
+use airgap_core::FrameEncoder;
+use airgap_core::protocol::*;
+#[cfg(feature = "png")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "png")]
+use airgap_core::batch::{map_batch, BatchOptions};
+#[cfg(feature = "png")]
+use crate::cache::{CacheStats, FrameCache, DEFAULT_CACHE_CAPACITY};
+use crate::render::{QrConfig, QrMatrix};
+#[cfg(feature = "png")]
+use crate::render::{render_frame, ConfigKey, FrameInfo, PixelBuffer, PixelFormat};
+use qrcode::QrCode;
+#[cfg(feature = "png")]
+use image::DynamicImage;
+
+pub struct Encoder {
+    frames: FrameEncoder,
+    config: QrConfig,
+    #[cfg(feature = "png")]
+    config_key: Arc<ConfigKey>,
+    #[cfg(feature = "png")]
+    cache: Mutex<FrameCache>,
+}
+
+impl Encoder {
+    pub fn new(
+        data: &[u8],
+        chunk_size: usize,
+    ) -> Result<Self, AirgapError> {
+        Self::with_config(data, chunk_size, QrConfig::default())
+    }
+
+    /// Create an encoder emitting frames in `format`; see `FrameEncoder::with_format`.
+    pub fn with_format(
+        data: &[u8],
+        chunk_size: usize,
+        config: QrConfig,
+        format: FrameFormat,
+    ) -> Result<Self, AirgapError> {
+        Ok(Self::from_frames(FrameEncoder::with_format(data, chunk_size, format)?, config))
+    }
+
+    pub fn with_config(
+        data: &[u8],
+        chunk_size: usize,
+        config: QrConfig,
+    ) -> Result<Self, AirgapError> {
+        Ok(Self::from_frames(FrameEncoder::new(data, chunk_size)?, config))
+    }
+
+    /// Create a native-format encoder with a fixed session id instead of a
+    /// random one, so the frames are fully determined by the inputs. Meant
+    /// for test vectors; live transfers should keep random ids so that a
+    /// receiver can tell consecutive sessions apart.
+    pub fn with_session_id(
+        data: &[u8],
+        chunk_size: usize,
+        config: QrConfig,
+        session_id: u32,
+    ) -> Result<Self, AirgapError> {
+        Ok(Self::from_frames(FrameEncoder::with_session_id(data, chunk_size, session_id)?, config))
+    }
+
+    /// Render frames produced by `frames`.
+    pub fn from_frames(frames: FrameEncoder, config: QrConfig) -> Self {
+        Self {
+            frames,
+            #[cfg(feature = "png")]
+            config_key: Arc::new(config.cache_key()),
+            config,
+            #[cfg(feature = "png")]
+            cache: Mutex::new(FrameCache::new(DEFAULT_CACHE_CAPACITY)),
+        }
+    }
+
+    /// The frames being rendered, without the rendering state.
+    pub fn frames(&self) -> &FrameEncoder {
+        &self.frames
+    }
+
+    /// QR text of frame `index`; see `FrameEncoder::get_qr_string`.
+    pub fn get_qr_string(&self, index: usize) -> Result<String, AirgapError> {
+        self.frames.get_qr_string(index)
+    }
+
+    /// Stream of the QR strings of every frame, in order; see `FrameStream`.
+    #[cfg(feature = "async")]
+    pub fn frame_stream(&self) -> airgap_core::FrameStream<'_> {
+        self.frames.frame_stream()
+    }
+
+    pub fn session_id(&self) -> u32 {
+        self.frames.session_id()
+    }
+    pub fn chunk_count(&self) -> usize {
+        self.frames.chunk_count()
+    }
+    pub fn format(&self) -> FrameFormat {
+        self.frames.format()
+    }
+
+    pub fn config(&self) -> &QrConfig {
+        &self.config
+    }
+
+    /// Render with `config` from now on. Frames cached for the previous
+    /// config stay until evicted, so switching back is still a lookup.
+    pub fn set_config(&mut self, config: QrConfig) {
+        #[cfg(feature = "png")]
+        {
+            self.config_key = Arc::new(config.cache_key());
+        }
+        self.config = config;
+    }
+
+    /// Module grid of chunk `index`, for UIs that draw the symbol themselves.
+    pub fn qr_matrix(&self, index: usize) -> Result<QrMatrix, AirgapError> {
+        generate_matrix_from_text(&self.qr_text(index)?, &self.config)
+    }
+
+    /// SVG document of chunk `index`; see `render_svg`.
+    #[cfg(feature = "svg")]
+    pub fn generate_svg(&self, index: usize) -> Result<String, AirgapError> {
+        generate_svg_from_text(&self.qr_text(index)?, &self.config)
+    }
+
+    // Text placed in the QR symbol. UR is uppercased so the QR can use the
+    // denser alphanumeric mode; decoders treat UR case-insensitively.
+    fn qr_text(&self, index: usize) -> Result<String, AirgapError> {
+        let text = self.get_qr_string(index)?;
+        match self.format() {
+            FrameFormat::Airgap | FrameFormat::Bbqr(_) => Ok(text),
+            FrameFormat::Ur(_) => Ok(text.to_ascii_uppercase()),
+        }
+    }
+}
+
+#[cfg(feature = "png")]
+impl Encoder {
+    pub fn generate_png_bytes(&self) -> Result<Vec<Vec<u8>>, AirgapError> {
+        self.generate_png_bytes_with(&BatchOptions::default())
+    }
+    /// Render and compress every frame, across `options.threads` workers
+    /// when the `parallel` feature is enabled.
+    pub fn generate_png_bytes_with(&self, options: &BatchOptions) -> Result<Vec<Vec<u8>>, AirgapError> {
+        map_batch(self.chunk_count(), options, |index| self.generate_png_bytes_for_item(index))
+    }
+    pub fn generate_png_bytes_for_item(&self, index: usize) ->  Result<Vec<u8>, AirgapError> {
+        let key = (index, Arc::clone(&self.config_key));
+        if let Some(png) = self.lock_cache().get(&key) {
+            return Ok(png);
+        }
+
+        // Render without holding the lock so other frames can be served meanwhile
+        let png = generate_png_bytes(&self.frame_image(index)?)?;
+        self.lock_cache().insert(key, png.clone());
+        Ok(png)
+    }
+
+    pub fn cache_stats(&self) -> CacheStats {
+        self.lock_cache().stats()
+    }
+
+    /// Maximum number of cached PNG frames; 0 disables caching.
+    pub fn set_cache_capacity(&self, capacity: usize) {
+        self.lock_cache().set_capacity(capacity);
+    }
+
+    /// Render the first `cache_capacity` frames that are not cached yet.
+    pub fn prewarm_cache(&self, options: &BatchOptions) -> Result<(), AirgapError> {
+        let capacity = self.cache_stats().capacity;
+        let missing: Vec<usize> = {
+            let cache = self.lock_cache();
+            (0..self.chunk_count().min(capacity))
+                .filter(|&index| !cache.contains(&(index, Arc::clone(&self.config_key))))
+                .collect()
+        };
+        let pngs = map_batch(missing.len(), options, |i| {
+            generate_png_bytes(&self.frame_image(missing[i])?)
+        })?;
+
+        let mut cache = self.lock_cache();
+        for (index, png) in missing.into_iter().zip(pngs) {
+            cache.insert((index, Arc::clone(&self.config_key)), png);
+        }
+        Ok(())
+    }
+
+    pub fn clear_cache(&self) {
+        self.lock_cache().clear();
+    }
+
+    fn lock_cache(&self) -> std::sync::MutexGuard<'_, FrameCache> {
+        // The cache holds plain data, so a poisoned lock is still usable
+        self.cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Uncompressed pixels of chunk `index`, rendered exactly like its PNG.
+    pub fn render_pixels(&self, index: usize, format: PixelFormat) -> Result<PixelBuffer, AirgapError> {
+        Ok(PixelBuffer::from_image(&self.frame_image(index)?, format))
+    }
+
+    fn frame_image(&self, index: usize) -> Result<DynamicImage, AirgapError> {
+        let frame = FrameInfo {
+            index,
+            total: self.chunk_count(),
+            session_id: self.session_id(),
+        };
+        generate_frame_image(&self.qr_text(index)?, Some(frame), &self.config)
+    }
+}
+
+#[cfg(feature = "png")]
+pub fn generate_image_from_chunk(chunk: &Chunk, config: &QrConfig) -> Result<DynamicImage, AirgapError> {
+    let chunk_bytes = chunk.to_bytes();
+    let encoded = base45::encode(chunk_bytes);
+    let frame = FrameInfo {
+        index: chunk.chunk_index as usize,
+        total: chunk.total_chunks as usize,
+        session_id: chunk.session_id,
+    };
+    generate_frame_image(&encoded, Some(frame), config)
+}
+
+#[cfg(feature = "png")]
+pub fn generate_image_from_text(text: &str, config: &QrConfig) -> Result<DynamicImage, AirgapError> {
+    generate_frame_image(text, None, config)
+}
+
+#[cfg(feature = "png")]
+/// Render `text`, drawing `config.indicator` below the symbol when `frame` is known.
+pub fn generate_frame_image(text: &str, frame: Option<FrameInfo>, config: &QrConfig) -> Result<DynamicImage, AirgapError> {
+    let code = QrCode::with_error_correction_level(text, config.ec_level)
+        .map_err(|e| AirgapError::EncodingError(e.to_string()))?;
+
+    render_frame(&code, config, frame)
+}
+
+pub fn generate_matrix_from_text(text: &str, config: &QrConfig) -> Result<QrMatrix, AirgapError> {
+    let code = QrCode::with_error_correction_level(text, config.ec_level)
+        .map_err(|e| AirgapError::EncodingError(e.to_string()))?;
+    Ok(QrMatrix::from_code(&code))
+}
+
+#[cfg(feature = "svg")]
+pub fn generate_svg_from_text(text: &str, config: &QrConfig) -> Result<String, AirgapError> {
+    let code = QrCode::with_error_correction_level(text, config.ec_level)
+        .map_err(|e| AirgapError::EncodingError(e.to_string()))?;
+    crate::render::render_svg(&code, config)
+}
+
+#[cfg(feature = "png")]
+pub fn generate_images_from_chunks(chunks: &[Chunk], qr_config: &QrConfig) -> Result<Vec<DynamicImage>, AirgapError> {
+    map_batch(chunks.len(), &BatchOptions::default(), |index| {
+        generate_image_from_chunk(&chunks[index], qr_config)
+    })
+}
+
+#[cfg(feature = "png")]
+pub fn generate_png_bytes(image: &DynamicImage) -> Result<Vec<u8>, AirgapError> {
+    let mut bytes = Vec::new();
+    image.write_to(
+        &mut std::io::Cursor::new(&mut bytes),
+        image::ImageFormat::Png
+    ).map_err(|e| AirgapError::EncodingError(e.to_string()))?;
+    Ok(bytes)
+}
+
+#[cfg(feature = "png")]
+pub fn generate_pngs_bytes(images: Vec<DynamicImage>) -> Result<Vec<Vec<u8>>, AirgapError> {
+    map_batch(images.len(), &BatchOptions::default(), |index| generate_png_bytes(&images[index]))
+}
\ No newline at end of file
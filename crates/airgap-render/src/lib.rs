@@ -0,0 +1,325 @@
+pub mod encoder;
+pub mod render;
+#[cfg(feature = "png")]
+mod raster;
+#[cfg(feature = "png")]
+pub mod cache;
+pub mod scheduler;
+#[cfg(feature = "png")]
+pub mod codes;
+
+pub use encoder::Encoder;
+#[cfg(feature = "png")]
+pub use cache::CacheStats;
+pub use scheduler::{DisplayScheduler, ScheduledFrame};
+pub use render::{FrameIndicator, FrameInfo, QrConfig, QrMatrix, MAX_IMAGE_EDGE};
+#[cfg(feature = "png")]
+pub use render::{PixelBuffer, PixelFormat, QrLogo};
+pub use qrcode::EcLevel;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use airgap_core::{Decoder, FrameFormat, UrType};
+    #[cfg(feature = "png")]
+    use airgap_core::{AirgapError, BatchOptions};
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_ml_kem_key() {
+
+        let pubkey = vec![0xAB; 1568];
+
+        let encoder = Encoder::new(&pubkey, 780).unwrap();
+        
+        assert_eq!(encoder.chunk_count(), 3);
+        
+        let pngs = encoder.generate_png_bytes().unwrap();
+        assert_eq!(pngs.len(), 3);
+        
+        for png in pngs {
+            assert!(png.len() > 1000); // PNG has overhead
+        }
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_qr_rendering_config() {
+        let text = "WABCDEF1234567890";
+        let width = qrcode::QrCode::with_error_correction_level(text, EcLevel::M).unwrap().width() as u32;
+
+        // Exact module size and custom quiet zone fix the image dimensions
+        let config = QrConfig {
+            module_size: Some(3),
+            quiet_zone: 2,
+            foreground: [0, 0, 128, 255],
+            background: [255, 255, 0, 255],
+            ..QrConfig::default()
+        };
+        let image = encoder::generate_image_from_text(text, &config).unwrap().to_rgba8();
+        assert_eq!(image.width(), (width + 4) * 3);
+        assert_eq!(image.get_pixel(0, 0).0, [255, 255, 0, 255]);
+        // Top-left finder pattern starts right after the quiet zone
+        assert_eq!(image.get_pixel(6, 6).0, [0, 0, 128, 255]);
+
+        let inverted = QrConfig { invert: true, ..config.clone() };
+        let image = encoder::generate_image_from_text(text, &inverted).unwrap().to_rgba8();
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 128, 255]);
+        assert_eq!(image.get_pixel(6, 6).0, [255, 255, 0, 255]);
+
+        // Logos must stay within the EC level's recovery capacity
+        let logo = image::RgbaImage::from_pixel(16, 16, image::Rgba([255, 0, 0, 255]));
+        let long_text = "W".repeat(300);
+        let with_logo = |ec_level, size_ratio| QrConfig {
+            ec_level,
+            logo: Some(QrLogo::new(logo.clone(), size_ratio)),
+            ..QrConfig::default()
+        };
+        assert!(encoder::generate_image_from_text(&long_text, &with_logo(EcLevel::H, 0.3)).is_ok());
+        assert!(matches!(
+            encoder::generate_image_from_text(&long_text, &with_logo(EcLevel::L, 0.3)),
+            Err(AirgapError::InvalidConfig(_))
+        ));
+
+        // Oversized images fail instead of overflowing or allocating gigabytes
+        let oversized = [
+            QrConfig { module_size: Some(u32::MAX / 2), ..QrConfig::default() },
+            QrConfig { module_size: Some(1000), ..QrConfig::default() },
+            QrConfig::with_size(u32::MAX),
+            QrConfig { quiet_zone: u32::MAX, ..QrConfig::default() },
+        ];
+        for config in oversized {
+            assert!(matches!(encoder::generate_image_from_text(text, &config), Err(AirgapError::InvalidConfig(_))));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_qr_config() {
+        let config = QrConfig {
+            ec_level: EcLevel::H,
+            module_size: Some(4),
+            invert: true,
+            indicator: FrameIndicator { caption: true, ..FrameIndicator::default() },
+            ..QrConfig::with_size(320)
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains(r#""ec_level":"H""#));
+        let parsed: QrConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.ec_level, EcLevel::H);
+        assert_eq!((parsed.qr_size, parsed.module_size, parsed.invert), (320, Some(4), true));
+        assert_eq!(parsed.indicator, config.indicator);
+
+        // Omitted fields fall back to the defaults
+        let partial: QrConfig = serde_json::from_str(r#"{"ec_level":"Q","qr_size":256}"#).unwrap();
+        assert_eq!(partial.ec_level, EcLevel::Q);
+        assert_eq!(partial.quiet_zone, render::DEFAULT_QUIET_ZONE);
+        assert_eq!(partial.colors(), (render::BLACK, render::WHITE));
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_frame_indicator() {
+        let data = vec![0x42; 1000];
+        let config = QrConfig {
+            module_size: Some(4),
+            indicator: FrameIndicator { caption: true, session_tag: true, progress_bar: true },
+            ..QrConfig::default()
+        };
+        let encoder = Encoder::with_config(&data, 100, config.clone()).unwrap();
+
+        let frame = FrameInfo { index: 6, total: 32, session_id: 0x1234_0000 };
+        assert_eq!(frame.caption(&config.indicator), "7/32 #1234");
+
+        // The indicator adds a band below the symbol; plain text renders have no position
+        let image = image::load_from_memory(&encoder.generate_png_bytes_for_item(6).unwrap()).unwrap();
+        assert!(image.height() > image.width());
+        let plain = encoder::generate_image_from_text(&encoder.get_qr_string(6).unwrap(), &config).unwrap();
+        assert_eq!(plain.width(), image.width());
+        assert_eq!(plain.height(), plain.width());
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_fountain_frame_indicator() {
+        let indicator = FrameIndicator { caption: true, progress_bar: true, ..FrameIndicator::default() };
+        let config = QrConfig { module_size: Some(2), indicator, ..QrConfig::default() };
+        let data: Vec<u8> = (0..600u32).map(|i| (i * 7) as u8).collect();
+        let ur = Encoder::with_format(&data, 100, config.clone(), FrameFormat::Ur(UrType::Bytes)).unwrap();
+
+        let mut scheduler = DisplayScheduler::new(&ur, 10.0);
+        scheduler.set_fountain_interval(2);
+        let index = (0..3).map(|_| scheduler.advance(100).index).last().unwrap();
+        assert_eq!(index, ur.chunk_count());
+
+        // Fountain frames get their own label instead of running past the total
+        let frame = FrameInfo { index, total: ur.chunk_count(), session_id: ur.session_id() };
+        assert_eq!(frame.caption(&indicator), "+1");
+        assert_eq!(frame.progress(), 1.0);
+        let png = ur.generate_png_bytes_for_item(index).unwrap();
+        let text = ur.get_qr_string(index).unwrap().to_ascii_uppercase();
+        let expected = encoder::generate_frame_image(&text, Some(frame), &config).unwrap();
+        assert_eq!(png, encoder::generate_png_bytes(&expected).unwrap());
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_matrix_and_pixel_buffers() {
+        let config = QrConfig { module_size: Some(2), ..QrConfig::default() };
+        let encoder = Encoder::with_config(&[0x42; 300], 100, config).unwrap();
+
+        let matrix = encoder.qr_matrix(0).unwrap();
+        assert_eq!(matrix.modules.len(), matrix.width * matrix.width);
+        // Finder pattern corner is dark, its separator light
+        assert!(matrix.is_dark(0, 0));
+        assert!(!matrix.is_dark(7, 0));
+        assert_eq!(matrix.to_bits().len(), matrix.width * matrix.width.div_ceil(8));
+        assert_eq!(matrix.to_bits()[0] & 0xFE, 0xFE);
+
+        let rgba = encoder.render_pixels(0, PixelFormat::Rgba8).unwrap();
+        let png = image::load_from_memory(&encoder.generate_png_bytes_for_item(0).unwrap()).unwrap();
+        assert_eq!((rgba.width, rgba.height), (png.width(), png.height()));
+        assert_eq!(rgba.data, png.to_rgba8().into_raw());
+
+        let luma = encoder.render_pixels(0, PixelFormat::Luma8).unwrap();
+        assert_eq!(luma.data.len(), rgba.data.len() / 4);
+        // Quiet zone is white, first module of the finder pattern black
+        assert_eq!(luma.data[0], 255);
+        assert_eq!(luma.data[(8 * luma.width + 8) as usize], 0);
+    }
+
+    #[cfg(feature = "svg")]
+    #[test]
+    fn test_svg_rendering() {
+        let config = QrConfig {
+            module_size: Some(3),
+            quiet_zone: 2,
+            foreground: [0x12, 0x34, 0x56, 255],
+            background: [255, 255, 255, 0],
+            ..QrConfig::default()
+        };
+        let encoder = Encoder::with_config(&[0x42; 300], 100, config).unwrap();
+        let matrix = encoder.qr_matrix(0).unwrap();
+        let svg = encoder.generate_svg(0).unwrap();
+
+        // One user unit per module, scaled by the module size
+        let full_width = matrix.width + 4;
+        assert!(svg.starts_with("<svg ") && svg.ends_with("</svg>"));
+        assert!(svg.contains(&format!("width=\"{}\" height=\"{}\"", full_width * 3, full_width * 3)));
+        assert!(svg.contains(&format!("viewBox=\"0 0 {full_width} {full_width}\"")));
+        assert!(svg.contains(" fill=\"#ffffff\" fill-opacity=\"0.000\""));
+        assert!(svg.contains(" fill=\"#123456\" d="));
+
+        // One square per dark module, offset by the quiet zone
+        let dark = matrix.modules.iter().filter(|&&module| module != 0).count();
+        assert_eq!(svg.matches("h1v1h-1z").count(), dark);
+        assert!(svg.contains("M2 2h1v1h-1z"));
+
+        let invalid = QrConfig { module_size: Some(0), ..QrConfig::default() };
+        assert!(encoder::generate_svg_from_text("airgap", &invalid).is_err());
+        let oversized = QrConfig { module_size: Some(u32::MAX / 2), ..QrConfig::default() };
+        assert!(matches!(encoder::generate_svg_from_text("airgap", &oversized), Err(airgap_core::AirgapError::InvalidConfig(_))));
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_batch_png_generation() {
+        let data = vec![0x5A; 2000];
+        let encoder = Encoder::with_config(&data, 200, QrConfig::with_size(100)).unwrap();
+
+        let pngs = encoder.generate_png_bytes_with(&BatchOptions::with_threads(2)).unwrap();
+        assert_eq!(pngs.len(), encoder.chunk_count());
+        // Results keep frame order
+        for (i, png) in pngs.iter().enumerate() {
+            assert_eq!(png, &encoder.generate_png_bytes_for_item(i).unwrap());
+        }
+
+        let options = BatchOptions::default();
+        options.cancel.cancel();
+        assert!(matches!(
+            encoder.generate_png_bytes_with(&options),
+            Err(AirgapError::Cancelled)
+        ));
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_frame_cache() {
+        let data = vec![0x33; 1000];
+        let mut encoder = Encoder::with_config(&data, 200, QrConfig::with_size(100)).unwrap();
+
+        let first = encoder.generate_png_bytes_for_item(0).unwrap();
+        assert_eq!(encoder.generate_png_bytes_for_item(0).unwrap(), first);
+        let stats = encoder.cache_stats();
+        assert_eq!((stats.hits, stats.misses, stats.len), (1, 1, 1));
+
+        encoder.prewarm_cache(&BatchOptions::default()).unwrap();
+        assert_eq!(encoder.cache_stats().len, encoder.chunk_count());
+
+        // A different config is a different cache entry
+        encoder.set_config(QrConfig { invert: true, ..QrConfig::with_size(100) });
+        assert_ne!(encoder.generate_png_bytes_for_item(0).unwrap(), first);
+        assert_eq!(encoder.cache_stats().len, encoder.chunk_count() + 1);
+
+        // Configs differing only in logo pixels don't share entries
+        let with_logo = |color| QrConfig {
+            ec_level: EcLevel::H,
+            logo: Some(QrLogo::new(image::RgbaImage::from_pixel(8, 8, image::Rgba(color)), 0.2)),
+            ..QrConfig::with_size(100)
+        };
+        encoder.set_config(with_logo([255, 0, 0, 255]));
+        let red = encoder.generate_png_bytes_for_item(0).unwrap();
+        encoder.set_config(with_logo([0, 0, 255, 255]));
+        assert_ne!(encoder.generate_png_bytes_for_item(0).unwrap(), red);
+        assert_eq!(encoder.cache_stats().len, encoder.chunk_count() + 3);
+
+        // Least recently used frames are evicted past capacity
+        encoder.set_cache_capacity(2);
+        let stats = encoder.cache_stats();
+        assert_eq!(stats.len, 2);
+        assert_eq!(stats.evictions, encoder.chunk_count() as u64 + 1);
+
+        encoder.clear_cache();
+        assert_eq!(encoder.cache_stats().len, 0);
+    }
+
+    #[test]
+    fn test_display_scheduler() {
+        let encoder = Encoder::new(&[0x11; 1000], 250).unwrap();
+        let mut scheduler = DisplayScheduler::new(&encoder, 10.0);
+        assert_eq!(scheduler.frame_interval_ms(), 100);
+
+        let first = scheduler.advance(0);
+        assert_eq!((first.index, first.changed, first.next_change_ms), (0, true, 100));
+        assert!(!scheduler.advance(60).changed);
+        assert_eq!(scheduler.advance(40).index, 1);
+
+        // A long stall advances one frame, not several
+        assert_eq!(scheduler.advance(1000).index, 2);
+
+        // Missing chunks jump the queue, then the loop resumes
+        scheduler.report_missing(&[0, 0, 99]);
+        assert_eq!(scheduler.advance(100).index, 0);
+        assert_eq!(scheduler.advance(100).index, 3);
+        assert_eq!(scheduler.advance(100).index, 0);
+
+        // UR transfers can interleave fountain frames
+        let data: Vec<u8> = (0..600u32).map(|i| (i * 7) as u8).collect();
+        let ur = Encoder::with_format(&data, 100, QrConfig::default(), FrameFormat::Ur(UrType::Bytes)).unwrap();
+        let mut scheduler = DisplayScheduler::new(&ur, 10.0);
+        scheduler.set_fountain_interval(2);
+        let frames: Vec<usize> = (0..6).map(|_| scheduler.advance(100).index).collect();
+        assert_eq!(frames, [0, 1, ur.chunk_count(), 2, 3, ur.chunk_count() + 1]);
+
+        // Fountain frames are regular UR parts to the receiver
+        let mut decoder = Decoder::with_format(FrameFormat::Ur(UrType::Bytes));
+        for index in frames {
+            decoder.process_qr_string(&ur.get_qr_string(index).unwrap()).unwrap();
+        }
+        while !decoder.is_complete() {
+            let index = scheduler.advance(100).index;
+            decoder.process_qr_string(&ur.get_qr_string(index).unwrap()).unwrap();
+        }
+        assert_eq!(decoder.get_data().unwrap(), data);
+    }
+}
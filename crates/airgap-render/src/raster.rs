@@ -0,0 +1,273 @@
+// src/raster - bitmap rendering of QR symbols, logos and frame indicators
+
+use image::{imageops, DynamicImage, Rgba, RgbaImage};
+use qrcode::{Color, EcLevel, QrCode};
+use airgap_core::AirgapError;
+use crate::render::{recovery_capacity, symbol_layout, FrameIndicator, FrameInfo, QrConfig};
+
+// Modules kept clear of the logo on each side so the finder patterns,
+// their separators and the timing pattern stay intact.
+const FINDER_MARGIN: usize = 8;
+
+/// A logo placed in the middle of the QR symbol.
+///
+/// The modules under the logo (plus a one-module border) are lost, so the
+/// covered area must stay within what the EC level can recover.
+#[derive(Debug, Clone)]
+pub struct QrLogo {
+    pub image: RgbaImage,
+    /// Logo edge length as a fraction of the symbol edge (quiet zone excluded).
+    pub size_ratio: f32,
+}
+
+impl QrLogo {
+    pub fn new(image: RgbaImage, size_ratio: f32) -> Self {
+        Self { image, size_ratio }
+    }
+
+    /// Build a logo from raw row-major RGBA pixels, as passed by the bindings.
+    pub fn from_rgba(width: u32, height: u32, rgba: Vec<u8>, size_ratio: f32) -> Result<Self, AirgapError> {
+        let expected = width as usize * height as usize * 4;
+        if expected == 0 || rgba.len() != expected {
+            return Err(AirgapError::InvalidConfig(format!(
+                "logo of {}x{} pixels needs {} RGBA bytes, got {}", width, height, expected, rgba.len()
+            )));
+        }
+        RgbaImage::from_raw(width, height, rgba)
+            .map(|image| Self::new(image, size_ratio))
+            .ok_or_else(|| AirgapError::InvalidConfig("logo dimensions overflow".to_string()))
+    }
+}
+
+/// Layout of an uncompressed `PixelBuffer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// 4 bytes per pixel, straight alpha; matches `ImageData` and Android `ARGB_8888` buffers.
+    Rgba8,
+    /// 1 byte per pixel.
+    Luma8,
+}
+
+impl PixelFormat {
+    pub fn bytes_per_pixel(&self) -> usize {
+        match self {
+            PixelFormat::Rgba8 => 4,
+            PixelFormat::Luma8 => 1,
+        }
+    }
+}
+
+/// Uncompressed, row-major rendering of a frame for direct upload to a UI bitmap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PixelBuffer {
+    pub width: u32,
+    pub height: u32,
+    pub format: PixelFormat,
+    pub data: Vec<u8>,
+}
+
+impl PixelBuffer {
+    pub fn from_image(image: &DynamicImage, format: PixelFormat) -> Self {
+        let data = match format {
+            PixelFormat::Rgba8 => image.to_rgba8().into_raw(),
+            PixelFormat::Luma8 => image.to_luma8().into_raw(),
+        };
+        Self { width: image.width(), height: image.height(), format, data }
+    }
+}
+
+/// Render `code` according to `config`.
+///
+/// Plain grayscale output without a logo is returned as `Luma8`, everything
+/// else as `Rgba8`.
+pub fn render_qr(code: &QrCode, config: &QrConfig) -> Result<DynamicImage, AirgapError> {
+    render_frame(code, config, None)
+}
+
+/// Render `code` like `render_qr`, adding `config.indicator` for `frame`.
+pub fn render_frame(code: &QrCode, config: &QrConfig, frame: Option<FrameInfo>) -> Result<DynamicImage, AirgapError> {
+    let width = code.width();
+    let quiet_zone = config.quiet_zone as usize;
+    let (_, module_size, edge) = symbol_layout(config, width)?;
+
+    let cleared = config.logo.as_ref()
+        .map(|logo| logo_modules(logo, width, config.ec_level))
+        .transpose()?;
+
+    let (dark, light) = config.colors();
+    let colors = code.to_colors();
+    let indicator = frame.filter(|_| config.indicator.is_enabled())
+        .map(|frame| IndicatorLayout::new(&config.indicator, frame, edge));
+    let band = indicator.as_ref().map_or(0, IndicatorLayout::height);
+    let mut image = RgbaImage::from_pixel(edge, edge + band, Rgba(light));
+
+    let clear_start = cleared.map(|c| (width - c) / 2);
+    let in_logo = |x: usize, y: usize| match (cleared, clear_start) {
+        (Some(c), Some(start)) => (start..start + c).contains(&x) && (start..start + c).contains(&y),
+        _ => false,
+    };
+
+    for y in 0..width {
+        for x in 0..width {
+            if colors[y * width + x] != Color::Dark || in_logo(x, y) {
+                continue;
+            }
+            let px = ((quiet_zone + x) * module_size) as u32;
+            let py = ((quiet_zone + y) * module_size) as u32;
+            for dy in 0..module_size as u32 {
+                for dx in 0..module_size as u32 {
+                    image.put_pixel(px + dx, py + dy, Rgba(dark));
+                }
+            }
+        }
+    }
+
+    if let Some(layout) = &indicator {
+        layout.draw(&mut image, edge, dark);
+    }
+
+    if let (Some(logo), Some(cleared), Some(start)) = (&config.logo, cleared, clear_start) {
+        // One module of padding inside the cleared square
+        let inner = ((cleared - 2) * module_size) as u32;
+        let scaled = imageops::resize(&logo.image, inner, inner, imageops::FilterType::Lanczos3);
+        let offset = ((quiet_zone + start + 1) * module_size) as i64;
+        imageops::overlay(&mut image, &scaled, offset, offset);
+        return Ok(DynamicImage::ImageRgba8(image));
+    }
+
+    let is_gray = |c: [u8; 4]| c[0] == c[1] && c[1] == c[2] && c[3] == u8::MAX;
+    if is_gray(dark) && is_gray(light) {
+        return Ok(DynamicImage::ImageLuma8(DynamicImage::ImageRgba8(image).to_luma8()));
+    }
+    Ok(DynamicImage::ImageRgba8(image))
+}
+
+// Edge length, in modules, of the square cleared for the logo. Always odd so
+// it centres on the (odd-width) symbol.
+fn logo_modules(logo: &QrLogo, width: usize, ec_level: EcLevel) -> Result<usize, AirgapError> {
+    if !(logo.size_ratio > 0.0 && logo.size_ratio < 1.0) {
+        return Err(AirgapError::InvalidConfig(format!(
+            "logo size ratio {} must be between 0 and 1", logo.size_ratio
+        )));
+    }
+
+    let inner = (width as f32 * logo.size_ratio).ceil() as usize;
+    let cleared = (inner + 2) | 1;
+
+    let covered = (cleared * cleared) as f32 / (width * width) as f32;
+    let capacity = recovery_capacity(ec_level);
+    if covered > capacity || cleared + 2 * FINDER_MARGIN > width {
+        return Err(AirgapError::InvalidConfig(format!(
+            "logo covers {:.0}% of the symbol, EC level {:?} recovers at most {:.0}%",
+            covered * 100.0, ec_level, capacity * 100.0
+        )));
+    }
+
+    Ok(cleared)
+}
+
+// ============================================================================
+// FRAME INDICATOR
+// ============================================================================
+
+// Glyphs are 5x7 pixels drawn on a 6-pixel advance
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+const GLYPH_ADVANCE: u32 = 6;
+
+struct IndicatorLayout {
+    caption: String,
+    // Filled fraction of the progress bar, if drawn
+    progress: Option<f32>,
+    // Pixels per glyph pixel; also the unit for padding and bar height
+    scale: u32,
+}
+
+impl IndicatorLayout {
+    fn new(indicator: &FrameIndicator, frame: FrameInfo, edge: u32) -> Self {
+        let caption = frame.caption(indicator);
+        let text_width = (caption.len() as u32 * GLYPH_ADVANCE).max(1);
+        let scale = (edge / 100).min(edge / text_width).max(1);
+        let progress = indicator.progress_bar
+            .then(|| frame.progress());
+        Self { caption, progress, scale }
+    }
+
+    fn caption_height(&self) -> u32 {
+        if self.caption.is_empty() { 0 } else { (GLYPH_HEIGHT + 2) * self.scale }
+    }
+
+    fn bar_height(&self) -> u32 {
+        if self.progress.is_some() { 4 * self.scale } else { 0 }
+    }
+
+    fn height(&self) -> u32 {
+        self.caption_height() + self.bar_height()
+    }
+
+    // Draws into the band starting at row `top`, below the quiet zone
+    fn draw(&self, image: &mut RgbaImage, top: u32, color: [u8; 4]) {
+        let (width, height) = image.dimensions();
+        let scale = self.scale;
+        let mut fill = |x0: u32, y0: u32, w: u32, h: u32| {
+            for y in y0..(y0 + h).min(height) {
+                for x in x0..(x0 + w).min(width) {
+                    image.put_pixel(x, y, Rgba(color));
+                }
+            }
+        };
+
+        if !self.caption.is_empty() {
+            let text_width = self.caption.len() as u32 * GLYPH_ADVANCE * scale - scale;
+            let left = width.saturating_sub(text_width) / 2;
+            for (i, ch) in self.caption.chars().enumerate() {
+                let glyph_left = left + i as u32 * GLYPH_ADVANCE * scale;
+                for (row, bits) in glyph(ch).iter().enumerate() {
+                    for col in 0..GLYPH_WIDTH {
+                        if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                            fill(glyph_left + col * scale, top + (row as u32 + 1) * scale, scale, scale);
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(progress) = self.progress {
+            // Outlined bar, 2 units tall, filled in proportion to progress
+            let y = top + self.caption_height() + scale;
+            let (left, span, line) = (2 * scale, width.saturating_sub(4 * scale), scale.div_ceil(2));
+            fill(left, y, span, line);
+            fill(left, y + 2 * scale - line, span, line);
+            fill(left, y, line, 2 * scale);
+            fill(left + span.saturating_sub(line), y, line, 2 * scale);
+            fill(left, y, (span as f32 * progress).round() as u32, 2 * scale);
+        }
+    }
+}
+
+// Rows of a 5x7 glyph, most significant of the low 5 bits leftmost. Covers
+// the characters `FrameInfo::caption` produces.
+fn glyph(ch: char) -> [u8; 7] {
+    match ch {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        '/' => [0x01, 0x01, 0x02, 0x04, 0x08, 0x10, 0x10],
+        '#' => [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+        '+' => [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],
+        _ => [0; 7],
+    }
+}
@@ -0,0 +1,326 @@
+// src/render - QR symbol settings, module matrices and SVG output
+
+#[cfg(feature = "svg")]
+use std::fmt::Write;
+use qrcode::{Color, EcLevel, QrCode};
+#[cfg(any(feature = "png", feature = "svg"))]
+use airgap_core::AirgapError;
+#[cfg(feature = "png")]
+pub use crate::raster::{render_frame, render_qr, PixelBuffer, PixelFormat, QrLogo};
+
+pub const BLACK: [u8; 4] = [0, 0, 0, 255];
+pub const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+/// Quiet zone recommended by ISO/IEC 18004, in modules.
+pub const DEFAULT_QUIET_ZONE: u32 = 4;
+
+/// Largest image edge, in pixels, the renderers produce. Settings that
+/// would exceed it fail with `InvalidConfig` instead of allocating.
+pub const MAX_IMAGE_EDGE: u32 = 8192;
+
+// Modules across a version 1 symbol, the smallest there is
+#[cfg(any(feature = "png", feature = "svg"))]
+const MIN_SYMBOL_WIDTH: usize = 21;
+
+// Serde mirror of `qrcode::EcLevel`, which has no impls of its own
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(remote = "EcLevel")]
+enum EcLevelDef {
+    L,
+    M,
+    Q,
+    H,
+}
+
+/// Settings for rendering a QR symbol. With the `serde` feature, missing
+/// fields take their default values and the logo is not serialized.
+///
+/// Sizes that would make the image wider than `MAX_IMAGE_EDGE` pixels are
+/// rejected with `InvalidConfig` when rendering.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct QrConfig {
+    #[cfg_attr(feature = "serde", serde(with = "EcLevelDef"))]
+    pub ec_level: EcLevel,
+    /// Minimum edge length of the image in pixels; ignored when
+    /// `module_size` is set.
+    pub qr_size: u32,
+    /// RGBA color of dark modules.
+    pub foreground: [u8; 4],
+    /// RGBA color of light modules and the quiet zone.
+    pub background: [u8; 4],
+    /// Quiet zone width in modules.
+    pub quiet_zone: u32,
+    /// Exact pixels per module. Avoids the blur of scaling a rendered
+    /// symbol, at the cost of not hitting `qr_size` exactly.
+    pub module_size: Option<u32>,
+    /// Swap foreground and background, e.g. for dark-mode screens.
+    pub invert: bool,
+    /// Image drawn over the centre of the symbol.
+    #[cfg(feature = "png")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub logo: Option<QrLogo>,
+    /// Caption and/or progress bar drawn below the quiet zone.
+    pub indicator: FrameIndicator,
+}
+
+/// `QrConfig` reduced to what the rendered image depends on, so frames can
+/// be cached per config without relying on a hash being collision-free.
+#[cfg(feature = "png")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ConfigKey {
+    ec_level: u8,
+    qr_size: u32,
+    foreground: [u8; 4],
+    background: [u8; 4],
+    quiet_zone: u32,
+    module_size: Option<u32>,
+    invert: bool,
+    logo: Option<LogoKey>,
+    indicator: FrameIndicator,
+}
+
+#[cfg(feature = "png")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct LogoKey {
+    dimensions: (u32, u32),
+    pixels: Vec<u8>,
+    // `f32` bits, since floats are not `Eq`
+    size_ratio: u32,
+}
+
+impl QrConfig {
+    pub fn with_size(size: u32) -> Self {
+        Self {
+            qr_size: size,
+            ..Self::default()
+        }
+    }
+
+    /// Every setting that affects the rendered image, in a comparable form.
+    #[cfg(feature = "png")]
+    pub(crate) fn cache_key(&self) -> ConfigKey {
+        ConfigKey {
+            ec_level: self.ec_level as u8,
+            qr_size: self.qr_size,
+            foreground: self.foreground,
+            background: self.background,
+            quiet_zone: self.quiet_zone,
+            module_size: self.module_size,
+            invert: self.invert,
+            logo: self.logo.as_ref().map(|logo| LogoKey {
+                dimensions: logo.image.dimensions(),
+                pixels: logo.image.as_raw().clone(),
+                size_ratio: logo.size_ratio.to_bits(),
+            }),
+            indicator: self.indicator,
+        }
+    }
+
+    /// Reject sizes no symbol fits within `MAX_IMAGE_EDGE`, for bindings
+    /// that take untrusted settings; rendering re-checks the exact edge.
+    #[cfg(any(feature = "png", feature = "svg"))]
+    pub fn check_sizes(&self) -> Result<(), AirgapError> {
+        symbol_layout(self, MIN_SYMBOL_WIDTH).map(|_| ())
+    }
+
+    /// Colors actually used for (dark, light) modules after `invert`.
+    pub fn colors(&self) -> ([u8; 4], [u8; 4]) {
+        if self.invert {
+            (self.background, self.foreground)
+        } else {
+            (self.foreground, self.background)
+        }
+    }
+}
+
+impl Default for QrConfig {
+    fn default() -> Self {
+        Self {
+            ec_level: EcLevel::M,
+            qr_size: 400,
+            foreground: BLACK,
+            background: WHITE,
+            quiet_zone: DEFAULT_QUIET_ZONE,
+            module_size: None,
+            invert: false,
+            #[cfg(feature = "png")]
+            logo: None,
+            indicator: FrameIndicator::default(),
+        }
+    }
+}
+
+/// Human-readable progress drawn under each frame, so an operator can see
+/// which frame is on screen without the host UI drawing it.
+///
+/// Only frames rendered with a known position (`FrameInfo`) get an
+/// indicator; enabling any part makes the image taller than it is wide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameIndicator {
+    /// "7/32" style frame counter; fountain frames past the last chunk
+    /// read "+1", "+2", ... instead.
+    pub caption: bool,
+    /// Appends a 4-digit hex tag derived from the session id, e.g. "#1A2B".
+    pub session_tag: bool,
+    /// Bar filled in proportion to the frame position, full for fountain frames.
+    pub progress_bar: bool,
+}
+
+impl FrameIndicator {
+    pub fn is_enabled(&self) -> bool {
+        self.caption || self.session_tag || self.progress_bar
+    }
+}
+
+/// Position of a rendered frame within its transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameInfo {
+    /// 0-based frame index; UR fountain frames come at `total` and past it.
+    pub index: usize,
+    pub total: usize,
+    pub session_id: u32,
+}
+
+impl FrameInfo {
+    /// Caption text for `indicator`, e.g. "7/32 #1A2B", or "+3 #1A2B" for
+    /// the third fountain frame.
+    pub fn caption(&self, indicator: &FrameIndicator) -> String {
+        let mut parts = Vec::new();
+        if indicator.caption {
+            match self.fountain_number() {
+                Some(number) => parts.push(format!("+{}", number)),
+                None => parts.push(format!("{}/{}", self.index + 1, self.total)),
+            }
+        }
+        if indicator.session_tag {
+            let tag = (self.session_id >> 16) as u16 ^ self.session_id as u16;
+            parts.push(format!("#{:04X}", tag));
+        }
+        parts.join(" ")
+    }
+
+    /// Share of the transfer shown so far, capped at 1 for fountain frames.
+    pub fn progress(&self) -> f32 {
+        ((self.index + 1) as f32 / self.total.max(1) as f32).min(1.0)
+    }
+
+    // 1-based position among the fountain frames, if this is one
+    fn fountain_number(&self) -> Option<usize> {
+        (self.index >= self.total).then(|| self.index - self.total + 1)
+    }
+}
+
+/// Module grid of a QR symbol, quiet zone excluded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrMatrix {
+    pub width: usize,
+    /// Row-major, one byte per module: 1 for dark, 0 for light.
+    pub modules: Vec<u8>,
+}
+
+impl QrMatrix {
+    pub fn from_code(code: &QrCode) -> Self {
+        let modules = code.to_colors()
+            .into_iter()
+            .map(|color| (color == Color::Dark) as u8)
+            .collect();
+        Self { width: code.width(), modules }
+    }
+
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.width + x] != 0
+    }
+
+    /// Rows packed eight modules per byte, most significant bit first; each
+    /// row is padded to a whole byte.
+    pub fn to_bits(&self) -> Vec<u8> {
+        self.modules
+            .chunks(self.width)
+            .flat_map(|row| row.chunks(8).map(|bits| {
+                bits.iter().enumerate().fold(0u8, |byte, (i, &bit)| byte | (bit << (7 - i)))
+            }))
+            .collect()
+    }
+}
+
+/// Approximate share of codewords the EC level can restore.
+pub fn recovery_capacity(ec_level: EcLevel) -> f32 {
+    match ec_level {
+        EcLevel::L => 0.07,
+        EcLevel::M => 0.15,
+        EcLevel::Q => 0.25,
+        EcLevel::H => 0.30,
+    }
+}
+
+// Modules across a symbol `width` modules wide with its quiet zone, pixels
+// per module and the image edge in pixels, checked against `MAX_IMAGE_EDGE`.
+#[cfg(any(feature = "png", feature = "svg"))]
+pub(crate) fn symbol_layout(config: &QrConfig, width: usize) -> Result<(usize, usize, u32), AirgapError> {
+    let too_large = || AirgapError::InvalidConfig(format!(
+        "image would be larger than {} pixels across", MAX_IMAGE_EDGE
+    ));
+    let full_width = (config.quiet_zone as usize)
+        .checked_mul(2)
+        .and_then(|quiet| quiet.checked_add(width))
+        .ok_or_else(too_large)?;
+    let module_size = match config.module_size {
+        Some(0) => return Err(AirgapError::InvalidConfig("module size must be at least 1 pixel".into())),
+        Some(size) => size as usize,
+        None if config.qr_size > MAX_IMAGE_EDGE => return Err(too_large()),
+        // Rounding up to whole modules may not push a valid size past the cap
+        None => match (config.qr_size as usize).div_ceil(full_width).max(1) {
+            size if size > 1 && full_width * size > MAX_IMAGE_EDGE as usize => size - 1,
+            size => size,
+        },
+    };
+    let edge = full_width
+        .checked_mul(module_size)
+        .and_then(|edge| u32::try_from(edge).ok())
+        .filter(|&edge| edge <= MAX_IMAGE_EDGE)
+        .ok_or_else(too_large)?;
+    Ok((full_width, module_size, edge))
+}
+
+/// Render `code` as a standalone SVG document.
+///
+/// Sized, padded and colored like `render_qr`, with one user unit per
+/// module so the symbol scales without blurring. Logos and frame indicators
+/// are only drawn into raster images.
+#[cfg(feature = "svg")]
+pub fn render_svg(code: &QrCode, config: &QrConfig) -> Result<String, AirgapError> {
+    let width = code.width();
+    let quiet_zone = config.quiet_zone as usize;
+    let (full_width, _, edge) = symbol_layout(config, width)?;
+    let (dark, light) = config.colors();
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" version=\"1.1\" width=\"{edge}\" height=\"{edge}\" \
+         viewBox=\"0 0 {full_width} {full_width}\" shape-rendering=\"crispEdges\">\
+         <rect width=\"{full_width}\" height=\"{full_width}\"{}/><path{} d=\"",
+        svg_fill(light),
+        svg_fill(dark),
+    );
+    for (i, color) in code.to_colors().into_iter().enumerate() {
+        if color == Color::Dark {
+            let _ = write!(svg, "M{} {}h1v1h-1z", quiet_zone + i % width, quiet_zone + i / width);
+        }
+    }
+    svg.push_str("\"/></svg>");
+    Ok(svg)
+}
+
+// ` fill="#rrggbb"`, plus `fill-opacity` unless the color is opaque
+#[cfg(feature = "svg")]
+fn svg_fill([r, g, b, a]: [u8; 4]) -> String {
+    let mut fill = format!(" fill=\"#{r:02x}{g:02x}{b:02x}\"");
+    if a != u8::MAX {
+        let _ = write!(fill, " fill-opacity=\"{:.3}\"", a as f32 / 255.0);
+    }
+    fill
+}
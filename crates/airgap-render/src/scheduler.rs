@@ -0,0 +1,154 @@
+// src/scheduler - sender-side frame timing for looping QR playback
+
+use std::collections::VecDeque;
+use crate::encoder::Encoder;
+use airgap_core::FrameFormat;
+
+pub const DEFAULT_FRAMES_PER_SECOND: f32 = 5.0;
+
+/// What to show after a call to `DisplayScheduler::advance`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduledFrame {
+    /// Frame to display, for `Encoder::get_qr_string` and friends. Indices
+    /// at or past `chunk_count` are fountain frames (UR only).
+    pub index: usize,
+    /// True when `index` differs from the previous call's frame.
+    pub changed: bool,
+    /// Milliseconds until the frame is due to change.
+    pub next_change_ms: u64,
+}
+
+/// Decides which frame to show and for how long, driven by the host's clock.
+///
+/// The host calls `advance` with the time elapsed since its previous call
+/// (e.g. once per vsync) and displays the returned frame; no callbacks are
+/// involved, so the same loop works over C, JNI and wasm. Frames the
+/// receiver reported missing jump the queue, and for fountain-coded formats
+/// extra mixed frames can be interleaved with the regular loop.
+#[derive(Debug, Clone)]
+pub struct DisplayScheduler {
+    chunk_count: usize,
+    fountain: bool,
+    frame_interval_ms: u64,
+    fountain_interval: usize,
+    priority: VecDeque<usize>,
+    current: usize,
+    next_sequential: usize,
+    next_fountain: usize,
+    since_fountain: usize,
+    elapsed_ms: u64,
+    started: bool,
+}
+
+impl DisplayScheduler {
+    pub fn new(encoder: &Encoder, frames_per_second: f32) -> Self {
+        let mut scheduler = Self {
+            chunk_count: encoder.chunk_count(),
+            fountain: matches!(encoder.format(), FrameFormat::Ur(_)) && encoder.chunk_count() > 1,
+            frame_interval_ms: 0,
+            fountain_interval: 0,
+            priority: VecDeque::new(),
+            current: 0,
+            next_sequential: 0,
+            next_fountain: encoder.chunk_count(),
+            since_fountain: 0,
+            elapsed_ms: 0,
+            started: false,
+        };
+        scheduler.set_frame_rate(frames_per_second);
+        scheduler
+    }
+
+    /// Frames per second; non-positive or non-finite rates fall back to the default.
+    pub fn set_frame_rate(&mut self, frames_per_second: f32) {
+        let fps = if frames_per_second.is_finite() && frames_per_second > 0.0 {
+            frames_per_second
+        } else {
+            DEFAULT_FRAMES_PER_SECOND
+        };
+        self.frame_interval_ms = ((1000.0 / fps).round() as u64).max(1);
+    }
+
+    pub fn frame_interval_ms(&self) -> u64 {
+        self.frame_interval_ms
+    }
+
+    /// Show one fountain frame after every `every` regular frames; 0 disables
+    /// interleaving. Ignored for formats without fountain codes.
+    pub fn set_fountain_interval(&mut self, every: usize) {
+        self.fountain_interval = every;
+    }
+
+    /// Queue chunks the receiver is still missing ahead of the regular loop.
+    /// Unknown or already queued indices are ignored.
+    pub fn report_missing(&mut self, indices: &[usize]) {
+        for &index in indices {
+            if index < self.chunk_count && !self.priority.contains(&index) {
+                self.priority.push_back(index);
+            }
+        }
+    }
+
+    /// Frame currently on display.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Account for `elapsed_ms` since the previous call and return the frame
+    /// to display. At most one frame is skipped forward per call, so a stalled
+    /// host resumes where it left off instead of racing through frames.
+    pub fn advance(&mut self, elapsed_ms: u64) -> ScheduledFrame {
+        if !self.started {
+            self.started = true;
+            self.current = self.pick_next();
+            return self.scheduled(true);
+        }
+
+        self.elapsed_ms = self.elapsed_ms.saturating_add(elapsed_ms);
+        if self.elapsed_ms < self.frame_interval_ms {
+            return self.scheduled(false);
+        }
+
+        self.elapsed_ms = (self.elapsed_ms - self.frame_interval_ms).min(self.frame_interval_ms - 1);
+        let previous = self.current;
+        self.current = self.pick_next();
+        self.scheduled(self.current != previous)
+    }
+
+    /// Start over from the first chunk, dropping queued missing chunks.
+    pub fn reset(&mut self) {
+        self.priority.clear();
+        self.current = 0;
+        self.next_sequential = 0;
+        self.next_fountain = self.chunk_count;
+        self.since_fountain = 0;
+        self.elapsed_ms = 0;
+        self.started = false;
+    }
+
+    fn pick_next(&mut self) -> usize {
+        if let Some(index) = self.priority.pop_front() {
+            return index;
+        }
+
+        if self.fountain && self.fountain_interval > 0 && self.since_fountain >= self.fountain_interval {
+            self.since_fountain = 0;
+            let index = self.next_fountain;
+            self.next_fountain = self.next_fountain.saturating_add(1);
+            return index;
+        }
+
+        let index = self.next_sequential;
+        self.next_sequential = (self.next_sequential + 1) % self.chunk_count.max(1);
+        self.since_fountain += 1;
+        index
+    }
+
+    fn scheduled(&self, changed: bool) -> ScheduledFrame {
+        ScheduledFrame {
+            index: self.current,
+            changed,
+            next_change_ms: self.frame_interval_ms - self.elapsed_ms,
+        }
+    }
+}
@@ -0,0 +1,656 @@
+// src/lib - wasm-bindgen interface for JS/browser targets
+#![cfg(target_arch = "wasm32")]
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::Clamped;
+use airgap_core::{AirgapError, AutoDecoder, BbqrFileType, Clock, Decoder, DecoderEvent, DecoderLimits, DecoderObserver, DecoderStatus, DecoderTimeouts, FrameFormat, UrType};
+use airgap_core::codes::detected_format_to_code;
+use airgap_render::{DisplayScheduler, Encoder, PixelFormat, QrConfig, QrLogo};
+use serde::Deserialize;
+
+// Options object accepted by `WasmEncoder::with_options`
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct WasmQrOptions {
+    #[serde(flatten)]
+    config: QrConfig,
+    logo: Option<WasmQrLogo>,
+}
+
+#[derive(Deserialize)]
+struct WasmQrLogo {
+    rgba: serde_bytes::ByteBuf,
+    width: u32,
+    height: u32,
+    #[serde(default = "default_logo_ratio")]
+    size_ratio: f32,
+}
+
+fn default_logo_ratio() -> f32 {
+    0.2
+}
+
+// A JS `Error` with `code` and `kind`, plus `chunkIndex`, `value` and
+// `limit` when the error carries them
+fn js_error(error: AirgapError) -> JsValue {
+    let js_error = js_sys::Error::new(&error.to_string());
+    js_error.set_name("AirgapError");
+    let set = |key: &str, value: JsValue| {
+        let _ = js_sys::Reflect::set(&js_error, &JsValue::from_str(key), &value);
+    };
+    set("code", error.to_code().into());
+    set("kind", error.kind().into());
+    if let Some(chunk_index) = error.chunk_index() {
+        set("chunkIndex", chunk_index.into());
+    }
+    if let Some(value) = error.value() {
+        set("value", (value as f64).into());
+    }
+    if let Some(limit) = error.limit() {
+        set("limit", (limit as f64).into());
+    }
+    js_error.into()
+}
+
+fn qr_config_from_js(options: JsValue) -> Result<QrConfig, JsValue> {
+    if options.is_undefined() || options.is_null() {
+        return Ok(QrConfig::default());
+    }
+    let options: WasmQrOptions = serde_wasm_bindgen::from_value(options)
+        .map_err(|e| js_error(AirgapError::InvalidConfig(format!("invalid QR options: {}", e))))?;
+    let mut config = options.config;
+    if let Some(logo) = options.logo {
+        config.logo = Some(QrLogo::from_rgba(logo.width, logo.height, logo.rgba.into_vec(), logo.size_ratio)
+            .map_err(js_error)?);
+    }
+    config.check_sizes().map_err(js_error)?;
+    Ok(config)
+}
+
+#[wasm_bindgen]
+pub struct WasmQRResult {
+    pub chunk_index: u16,
+    pub total_chunks: u16,
+}
+
+#[wasm_bindgen]
+pub struct WasmAutoQRResult {
+    pub chunk_index: u16,
+    pub total_chunks: u16,
+    /// `WasmFrameFormat` value of the detected format, or 10 for raw text.
+    pub format: i32,
+}
+
+/// Kind of a `WasmDecoderEvent`.
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub enum WasmDecoderEventKind {
+    SessionStarted = 0,
+    ChunkReceived = 1,
+    Duplicate = 2,
+    Conflict = 3,
+    Error = 4,
+    Completed = 5,
+}
+
+/// Decoder notification passed to the `set_listener` callback. Fields that
+/// do not apply to `kind` are 0 / undefined.
+#[wasm_bindgen]
+pub struct WasmDecoderEvent {
+    pub kind: WasmDecoderEventKind,
+    pub session_id: u32,
+    pub chunk_index: u16,
+    pub received: usize,
+    pub total: usize,
+    /// `AIRGAP_ERR_*` code for conflicts and errors, 0 otherwise.
+    pub error_code: i32,
+    message: Option<String>,
+}
+
+#[wasm_bindgen]
+impl WasmDecoderEvent {
+    /// Error text for conflicts and errors.
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> Option<String> {
+        self.message.clone()
+    }
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// JS function receiving a `WasmDecoderEvent`.
+    #[wasm_bindgen(typescript_type = "(event: WasmDecoderEvent) => void")]
+    pub type DecoderEventCallback;
+
+    #[wasm_bindgen(method, js_name = call)]
+    fn call(this: &DecoderEventCallback, this_arg: &JsValue, event: WasmDecoderEvent);
+}
+
+struct JsObserver(DecoderEventCallback);
+
+// wasm32 without threads: the callback never leaves the JS thread
+unsafe impl Send for JsObserver {}
+
+impl DecoderObserver for JsObserver {
+    fn on_event(&mut self, event: &DecoderEvent) {
+        let mut js_event = WasmDecoderEvent {
+            kind: WasmDecoderEventKind::Completed,
+            session_id: 0,
+            chunk_index: 0,
+            received: 0,
+            total: 0,
+            error_code: 0,
+            message: None,
+        };
+        match event {
+            DecoderEvent::SessionStarted { session_id, total_chunks } => {
+                js_event.kind = WasmDecoderEventKind::SessionStarted;
+                js_event.session_id = *session_id;
+                js_event.total = *total_chunks;
+            }
+            DecoderEvent::ChunkReceived { chunk, received, total } => {
+                js_event.kind = WasmDecoderEventKind::ChunkReceived;
+                js_event.session_id = chunk.session_id;
+                js_event.chunk_index = chunk.chunk_index;
+                js_event.received = *received;
+                js_event.total = *total;
+            }
+            DecoderEvent::Duplicate { chunk } => {
+                js_event.kind = WasmDecoderEventKind::Duplicate;
+                js_event.session_id = chunk.session_id;
+                js_event.chunk_index = chunk.chunk_index;
+            }
+            DecoderEvent::Conflict(error) | DecoderEvent::Error(error) => {
+                js_event.kind = match event {
+                    DecoderEvent::Conflict(_) => WasmDecoderEventKind::Conflict,
+                    _ => WasmDecoderEventKind::Error,
+                };
+                js_event.error_code = error.to_code();
+                js_event.message = Some(error.to_string());
+            }
+            DecoderEvent::Completed => {}
+        }
+        self.0.call(&JsValue::NULL, js_event);
+    }
+}
+
+/// Frame to display, returned by `WasmDisplayScheduler.advance`.
+#[wasm_bindgen]
+pub struct WasmScheduledFrame {
+    /// Frame index for `get_qr_string` / `generate_png`; indices at or past
+    /// `chunk_count()` are UR fountain frames.
+    pub index: usize,
+    pub changed: bool,
+    pub next_change_ms: u32,
+}
+
+/// QR module grid, quiet zone excluded.
+#[wasm_bindgen]
+pub struct WasmQrMatrix {
+    pub width: usize,
+    modules: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl WasmQrMatrix {
+    /// `width * width` bytes, row-major: 1 for dark, 0 for light.
+    #[wasm_bindgen(getter)]
+    pub fn modules(&self) -> Vec<u8> {
+        self.modules.clone()
+    }
+}
+
+/// RGBA pixels of a rendered frame.
+#[wasm_bindgen]
+pub struct WasmPixels {
+    pub width: u32,
+    pub height: u32,
+    data: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl WasmPixels {
+    /// Pixel data for `new ImageData(pixels.data, pixels.width, pixels.height)`.
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> Clamped<Vec<u8>> {
+        Clamped(self.data.clone())
+    }
+}
+
+/// Wire format of the QR frames.
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub enum WasmFrameFormat {
+    Airgap = 0,
+    UrBytes = 1,
+    UrCryptoPsbt = 2,
+    BbqrPsbt = 3,
+    BbqrTransaction = 4,
+    BbqrJson = 5,
+    BbqrCbor = 6,
+    BbqrUnicodeText = 7,
+    BbqrBinary = 8,
+    BbqrExecutable = 9,
+}
+
+impl From<WasmFrameFormat> for FrameFormat {
+    fn from(format: WasmFrameFormat) -> Self {
+        match format {
+            WasmFrameFormat::Airgap => FrameFormat::Airgap,
+            WasmFrameFormat::UrBytes => FrameFormat::Ur(UrType::Bytes),
+            WasmFrameFormat::UrCryptoPsbt => FrameFormat::Ur(UrType::CryptoPsbt),
+            WasmFrameFormat::BbqrPsbt => FrameFormat::Bbqr(BbqrFileType::Psbt),
+            WasmFrameFormat::BbqrTransaction => FrameFormat::Bbqr(BbqrFileType::Transaction),
+            WasmFrameFormat::BbqrJson => FrameFormat::Bbqr(BbqrFileType::Json),
+            WasmFrameFormat::BbqrCbor => FrameFormat::Bbqr(BbqrFileType::Cbor),
+            WasmFrameFormat::BbqrUnicodeText => FrameFormat::Bbqr(BbqrFileType::UnicodeText),
+            WasmFrameFormat::BbqrBinary => FrameFormat::Bbqr(BbqrFileType::Binary),
+            WasmFrameFormat::BbqrExecutable => FrameFormat::Bbqr(BbqrFileType::Executable),
+        }
+    }
+}
+
+/// Status of a decoding session, see `WasmDecoder::status`.
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub enum WasmDecoderStatus {
+    Idle = 0,
+    Receiving = 1,
+    Stalled = 2,
+    Complete = 3,
+    Expired = 4,
+}
+
+impl From<DecoderStatus> for WasmDecoderStatus {
+    fn from(status: DecoderStatus) -> Self {
+        match status {
+            DecoderStatus::Idle => WasmDecoderStatus::Idle,
+            DecoderStatus::Receiving => WasmDecoderStatus::Receiving,
+            DecoderStatus::Stalled => WasmDecoderStatus::Stalled,
+            DecoderStatus::Complete => WasmDecoderStatus::Complete,
+            DecoderStatus::Expired => WasmDecoderStatus::Expired,
+        }
+    }
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = Date, js_name = now)]
+    fn date_now() -> f64;
+}
+
+// `Instant` is unavailable in the browser, so timeouts run on `Date.now()`
+struct JsClock;
+
+impl Clock for JsClock {
+    fn now_ms(&self) -> u64 {
+        date_now() as u64
+    }
+}
+
+// ============================================================================
+// ENCODER
+// ============================================================================
+
+#[wasm_bindgen]
+pub struct WasmEncoder {
+    inner: Encoder,
+}
+
+#[wasm_bindgen]
+impl WasmEncoder {
+    /// Create an encoder from raw bytes.
+    ///
+    /// - `data`       – bytes to transmit
+    /// - `chunk_size` – max payload bytes per QR chunk (16–1920, recommended ≤1100)
+    /// - `qr_size`    – output PNG pixel dimensions (e.g. 400)
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: &[u8], chunk_size: usize, qr_size: u32) -> Result<WasmEncoder, JsValue> {
+        let inner = Encoder::with_config(data, chunk_size, QrConfig::with_size(qr_size))
+            .map_err(js_error)?;
+        Ok(Self { inner })
+    }
+
+    /// Create an encoder emitting frames in `format` (e.g. `WasmFrameFormat.UrBytes`).
+    /// For UR, `chunk_size` is the maximum fountain fragment length; for BBQr
+    /// it bounds the (compressed) bytes carried per frame.
+    #[wasm_bindgen]
+    pub fn with_format(
+        data: &[u8],
+        chunk_size: usize,
+        qr_size: u32,
+        format: WasmFrameFormat,
+    ) -> Result<WasmEncoder, JsValue> {
+        let inner = Encoder::with_format(data, chunk_size, QrConfig::with_size(qr_size), format.into())
+            .map_err(js_error)?;
+        Ok(Self { inner })
+    }
+
+    /// Create an encoder with full control over rendering. `options` is an
+    /// object with any of the `QrConfig` fields, e.g.
+    /// `{ ec_level: "H", foreground: [0, 0, 128, 255], quiet_zone: 2,
+    /// indicator: { caption: true }, logo: { rgba, width, height } }`;
+    /// missing fields keep their defaults.
+    #[wasm_bindgen]
+    pub fn with_options(
+        data: &[u8],
+        chunk_size: usize,
+        format: WasmFrameFormat,
+        options: JsValue,
+    ) -> Result<WasmEncoder, JsValue> {
+        let inner = Encoder::with_format(data, chunk_size, qr_config_from_js(options)?, format.into())
+            .map_err(js_error)?;
+        Ok(Self { inner })
+    }
+
+    /// Create a native-format encoder with a fixed `session_id`, so the
+    /// frames can be compared against the golden test vectors.
+    #[wasm_bindgen]
+    pub fn with_session_id(
+        data: &[u8],
+        chunk_size: usize,
+        qr_size: u32,
+        session_id: u32,
+    ) -> Result<WasmEncoder, JsValue> {
+        let inner = Encoder::with_session_id(data, chunk_size, QrConfig::with_size(qr_size), session_id)
+            .map_err(js_error)?;
+        Ok(Self { inner })
+    }
+
+    /// Number of QR code chunks needed to transmit the data.
+    #[wasm_bindgen]
+    pub fn chunk_count(&self) -> usize {
+        self.inner.chunk_count()
+    }
+
+    /// Random session ID shared across all chunks of this transfer.
+    #[wasm_bindgen]
+    pub fn session_id(&self) -> u32 {
+        self.inner.session_id()
+    }
+
+    /// Base45-encoded string for chunk at `index` — pass this to a JS QR renderer.
+    #[wasm_bindgen]
+    pub fn get_qr_string(&self, index: usize) -> Result<String, JsValue> {
+        self.inner
+            .get_qr_string(index)
+            .map_err(js_error)
+    }
+
+    /// PNG bytes for chunk at `index` — write into a canvas or an <img> src data URL.
+    #[wasm_bindgen]
+    pub fn generate_png(&self, index: usize) -> Result<Vec<u8>, JsValue> {
+        self.inner
+            .generate_png_bytes_for_item(index)
+            .map_err(js_error)
+    }
+
+    /// Module grid for chunk at `index`, for drawing the symbol yourself.
+    #[wasm_bindgen]
+    pub fn qr_matrix(&self, index: usize) -> Result<WasmQrMatrix, JsValue> {
+        let matrix = self.inner
+            .qr_matrix(index)
+            .map_err(js_error)?;
+        Ok(WasmQrMatrix { width: matrix.width, modules: matrix.modules })
+    }
+
+    /// Uncompressed RGBA pixels for chunk at `index` — skips PNG encoding and image decoding.
+    #[wasm_bindgen]
+    pub fn render_rgba(&self, index: usize) -> Result<WasmPixels, JsValue> {
+        let buffer = self.inner
+            .render_pixels(index, PixelFormat::Rgba8)
+            .map_err(js_error)?;
+        Ok(WasmPixels { width: buffer.width, height: buffer.height, data: buffer.data })
+    }
+}
+
+// ============================================================================
+// DECODER
+// ============================================================================
+
+/// JS runs the decoder on a single thread, so it wraps a plain `Decoder`;
+/// hand frames to a worker by posting the strings, not the decoder.
+#[wasm_bindgen]
+pub struct WasmDecoder {
+    inner: Decoder,
+}
+
+#[wasm_bindgen]
+impl WasmDecoder {
+    /// Create a fresh decoder. Feed QR strings from a JS scanner via `process_qr`.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmDecoder {
+        Self { inner: Decoder::new() }
+    }
+
+    /// Create a decoder accepting frames in `format`, e.g. `ur:crypto-psbt` or BBQr PSBT sequences.
+    #[wasm_bindgen]
+    pub fn with_format(format: WasmFrameFormat) -> WasmDecoder {
+        Self { inner: Decoder::with_format(format.into()) }
+    }
+
+    /// Feed a Base45 QR string (as decoded by jsQR / ZXing-js) into the decoder.
+    /// Returns a `WasmQRResult` with `chunk_index` and `total_chunks` on success.
+    #[wasm_bindgen]
+    pub fn process_qr(&mut self, qr_string: &str) -> Result<WasmQRResult, JsValue> {
+        let chunk = self.inner
+            .process_qr_string(qr_string)
+            .map_err(js_error)?;
+        Ok(WasmQRResult {
+            chunk_index: chunk.chunk_index,
+            total_chunks: chunk.total_chunks,
+        })
+    }
+
+    /// True once all chunks for the session have been received.
+    #[wasm_bindgen]
+    pub fn is_complete(&self) -> bool {
+        self.inner.is_complete()
+    }
+
+    /// Number of chunks expected in the current session (0 until first chunk seen).
+    #[wasm_bindgen]
+    pub fn total_count(&self) -> usize {
+        self.inner.total_count()
+    }
+
+    /// Number of distinct chunks received so far.
+    #[wasm_bindgen]
+    pub fn received_count(&self) -> usize {
+        self.inner.received_count()
+    }
+
+    /// Session ID of the current transfer, or `undefined` if no chunk seen yet.
+    #[wasm_bindgen]
+    pub fn session_id(&self) -> Option<u32> {
+        self.inner.session_id()
+    }
+
+    /// Reassembled data. Call only after `is_complete()` returns true.
+    #[wasm_bindgen]
+    pub fn get_data(&self) -> Result<Vec<u8>, JsValue> {
+        self.inner
+            .get_data()
+            .map_err(js_error)
+    }
+
+    /// Call `callback(event)` on session start, new chunk, duplicate,
+    /// conflict, error and completion; pass `undefined` to stop.
+    #[wasm_bindgen]
+    pub fn set_listener(&mut self, callback: Option<DecoderEventCallback>) {
+        match callback {
+            Some(callback) => self.inner.set_observer(Box::new(JsObserver(callback))),
+            None => self.inner.clear_observer(),
+        }
+    }
+
+    /// Report the session as stalled after `idle_ms` without a new chunk and
+    /// as expired `total_ms` after its first frame; 0 disables either limit.
+    /// With `auto_expire`, stale sessions are dropped as new frames arrive;
+    /// otherwise frames for an expired session throw.
+    #[wasm_bindgen]
+    pub fn set_timeouts(&mut self, idle_ms: u32, total_ms: u32, auto_expire: bool) {
+        self.inner.set_clock(Box::new(JsClock));
+        self.inner.set_timeouts(DecoderTimeouts {
+            idle_ms: idle_ms.into(),
+            total_ms: total_ms.into(),
+            auto_expire,
+        });
+    }
+
+    /// Cap what the decoder accepts from untrusted frames; 0 disables a
+    /// limit. New decoders allow 4096 chunks, 16 MiB of payload and
+    /// 4296-character frames, with no session limit. Frames over a limit
+    /// throw.
+    #[wasm_bindgen]
+    pub fn set_limits(&mut self, max_total_chunks: u32, max_payload_bytes: u32, max_sessions: u32, max_frame_len: u32) {
+        self.inner.set_limits(DecoderLimits {
+            max_total_chunks: max_total_chunks as usize,
+            max_payload_bytes: max_payload_bytes as usize,
+            max_sessions: max_sessions as usize,
+            max_frame_len: max_frame_len as usize,
+        });
+    }
+
+    /// Where the current session stands, taking the timeouts into account.
+    #[wasm_bindgen]
+    pub fn status(&self) -> WasmDecoderStatus {
+        self.inner.status().into()
+    }
+
+    /// Reset the session if it is stalled or expired; returns whether it did.
+    #[wasm_bindgen]
+    pub fn expire_stale(&mut self) -> bool {
+        self.inner.expire_stale()
+    }
+
+    /// Reset decoder state to start a new session.
+    #[wasm_bindgen]
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+// ============================================================================
+// AUTO DECODER
+// ============================================================================
+
+#[wasm_bindgen]
+pub struct WasmAutoDecoder {
+    inner: AutoDecoder,
+}
+
+#[wasm_bindgen]
+impl WasmAutoDecoder {
+    /// Create a decoder that detects native, UR, BBQr or raw QR codes from the first frame.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmAutoDecoder {
+        Self { inner: AutoDecoder::new() }
+    }
+
+    /// Format code of the current session (see `WasmAutoQRResult.format`), or -1 before the first frame.
+    #[wasm_bindgen]
+    pub fn format(&self) -> i32 {
+        detected_format_to_code(self.inner.format())
+    }
+
+    /// Feed any scanned QR string. Frames in a different format than the
+    /// session's first frame are rejected until `reset()`.
+    #[wasm_bindgen]
+    pub fn process_qr(&mut self, qr_string: &str) -> Result<WasmAutoQRResult, JsValue> {
+        let frame = self.inner
+            .process_qr_string(qr_string)
+            .map_err(js_error)?;
+        Ok(WasmAutoQRResult {
+            chunk_index: frame.chunk.chunk_index,
+            total_chunks: frame.chunk.total_chunks,
+            format: detected_format_to_code(Some(frame.format)),
+        })
+    }
+
+    /// True once the payload is complete.
+    #[wasm_bindgen]
+    pub fn is_complete(&self) -> bool {
+        self.inner.is_complete()
+    }
+
+    /// Number of chunks expected in the current session (0 until first chunk seen).
+    #[wasm_bindgen]
+    pub fn total_count(&self) -> usize {
+        self.inner.total_count()
+    }
+
+    /// Number of distinct chunks received so far.
+    #[wasm_bindgen]
+    pub fn received_count(&self) -> usize {
+        self.inner.received_count()
+    }
+
+    /// Reassembled data. Call only after `is_complete()` returns true.
+    #[wasm_bindgen]
+    pub fn get_data(&self) -> Result<Vec<u8>, JsValue> {
+        self.inner
+            .get_data()
+            .map_err(js_error)
+    }
+
+    /// Reset decoder state to start a new session.
+    #[wasm_bindgen]
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+// ============================================================================
+// DISPLAY SCHEDULER
+// ============================================================================
+
+#[wasm_bindgen]
+pub struct WasmDisplayScheduler {
+    inner: DisplayScheduler,
+}
+
+#[wasm_bindgen]
+impl WasmDisplayScheduler {
+    /// Schedule the frames of `encoder` at `frames_per_second`. Drive it from
+    /// `requestAnimationFrame` by passing the elapsed milliseconds to `advance`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(encoder: &WasmEncoder, frames_per_second: f32) -> WasmDisplayScheduler {
+        Self { inner: DisplayScheduler::new(&encoder.inner, frames_per_second) }
+    }
+
+    #[wasm_bindgen]
+    pub fn set_frame_rate(&mut self, frames_per_second: f32) {
+        self.inner.set_frame_rate(frames_per_second);
+    }
+
+    /// Show one fountain frame after every `every` regular frames (UR only); 0 disables.
+    #[wasm_bindgen]
+    pub fn set_fountain_interval(&mut self, every: usize) {
+        self.inner.set_fountain_interval(every);
+    }
+
+    /// Show chunks the receiver is still missing before continuing the loop.
+    #[wasm_bindgen]
+    pub fn report_missing(&mut self, indices: &[u32]) {
+        let indices: Vec<usize> = indices.iter().map(|&index| index as usize).collect();
+        self.inner.report_missing(&indices);
+    }
+
+    /// Account for `elapsed_ms` since the previous call and return the frame to display.
+    #[wasm_bindgen]
+    pub fn advance(&mut self, elapsed_ms: f64) -> WasmScheduledFrame {
+        let frame = self.inner.advance(elapsed_ms.max(0.0) as u64);
+        WasmScheduledFrame {
+            index: frame.index,
+            changed: frame.changed,
+            next_change_ms: frame.next_change_ms.min(u32::MAX as u64) as u32,
+        }
+    }
+
+    /// Start over from the first chunk.
+    #[wasm_bindgen]
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
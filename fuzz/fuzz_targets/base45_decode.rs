@@ -0,0 +1,16 @@
+// Decode arbitrary text as Base45 the way the native decoder does; decoded
+// bytes must encode back to something that decodes to the same bytes.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(bytes) = base45::decode(text) else {
+        return;
+    };
+    let encoded = base45::encode(&bytes);
+    assert_eq!(base45::decode(&encoded).expect("encoded text must decode"), bytes);
+});
@@ -0,0 +1,21 @@
+// Parse arbitrary bytes as a native chunk; whatever parses must survive a
+// round trip through `to_bytes` and agree with the borrowed parser.
+#![no_main]
+
+use airgap_core::{Chunk, ChunkRef};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(chunk) = Chunk::from_bytes(data) else {
+        assert!(ChunkRef::from_bytes(data).is_err());
+        return;
+    };
+    assert_eq!(ChunkRef::from_bytes(data).expect("owned parse succeeded"), chunk.as_chunk_ref());
+    assert!(chunk.chunk_index < chunk.total_chunks);
+
+    let reparsed = Chunk::from_bytes(&chunk.to_bytes()).expect("serialized chunk must parse");
+    assert_eq!(reparsed.total_chunks, chunk.total_chunks);
+    assert_eq!(reparsed.chunk_index, chunk.chunk_index);
+    assert_eq!(reparsed.session_id, chunk.session_id);
+    assert_eq!(reparsed.data, chunk.data);
+});
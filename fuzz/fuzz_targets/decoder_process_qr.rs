@@ -0,0 +1,45 @@
+// Feed arbitrary frames to a decoder. The first byte picks the frame format,
+// the rest is split into QR strings on newlines. Limits are set as for an
+// untrusted camera feed so that compressed BBQr payloads stay bounded.
+#![no_main]
+
+use airgap_core::{BbqrFileType, Decoder, DecoderLimits, DecoderTimeouts, FrameFormat, ManualClock, UrType};
+use libfuzzer_sys::fuzz_target;
+
+const FORMATS: [FrameFormat; 5] = [
+    FrameFormat::Airgap,
+    FrameFormat::Ur(UrType::Bytes),
+    FrameFormat::Ur(UrType::CryptoPsbt),
+    FrameFormat::Bbqr(BbqrFileType::Binary),
+    FrameFormat::Bbqr(BbqrFileType::Psbt),
+];
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, frames)) = data.split_first() else {
+        return;
+    };
+    let format = FORMATS[selector as usize % FORMATS.len()];
+
+    let clock = ManualClock::new();
+    let mut decoder = Decoder::with_format(format);
+    decoder.set_clock(Box::new(clock.clone()));
+    decoder.set_timeouts(DecoderTimeouts { idle_ms: 50, total_ms: 500, auto_expire: selector & 0x80 != 0 });
+    decoder.set_limits(DecoderLimits {
+        max_total_chunks: 1024,
+        max_payload_bytes: 1 << 20,
+        max_sessions: 16,
+        max_frame_len: 4096,
+    });
+
+    for frame in String::from_utf8_lossy(frames).split('\n') {
+        clock.advance(10);
+        let _ = decoder.process_qr_string(frame);
+        let _ = decoder.status();
+    }
+
+    if decoder.is_complete() {
+        let _ = decoder.get_data();
+    } else {
+        assert!(decoder.get_data().is_err());
+    }
+});
@@ -7,11 +7,17 @@
 
 #import <Foundation/Foundation.h>
 #import "AGQRResult.h"
+#import "AGFrameFormat.h"
+#import "AGDecoderStatus.h"
 
 NS_ASSUME_NONNULL_BEGIN
 
 /**
  * AGDecoder decodes QR code chunks back into the original data
+ *
+ * AGDecoder is thread-safe: frames can be processed on a capture queue while
+ * the main thread reads progress. Progress reads never wait for a frame
+ * being processed.
  */
 @interface AGDecoder : NSObject
 
@@ -20,6 +26,13 @@ NS_ASSUME_NONNULL_BEGIN
  */
 - (instancetype)init;
 
+/**
+ * Creates a new decoder accepting frames in the given wire format
+ *
+ * @param format Wire format of the frames to decode
+ */
+- (nullable instancetype)initWithFormat:(AGFrameFormat)format;
+
 /**
  * Whether all chunks have been received and the data is complete
  */
@@ -40,6 +53,48 @@ NS_ASSUME_NONNULL_BEGIN
  */
 @property (nonatomic, readonly) NSInteger sessionId;
 
+/**
+ * Where the current session stands, taking the timeouts into account
+ */
+@property (nonatomic, readonly) AGDecoderStatus status;
+
+/**
+ * Report the session as stalled after idleTimeout without a new chunk and as
+ * expired totalTimeout after its first frame; 0 disables either limit
+ *
+ * @param idleTimeout Seconds without a new chunk before the session stalls
+ * @param totalTimeout Seconds after the first frame before the session expires
+ * @param autoExpire Drop stale sessions as new frames arrive; otherwise frames
+ *        for an expired session fail with AIRGAP_ERR_SESSION_EXPIRED
+ */
+- (void)setIdleTimeout:(NSTimeInterval)idleTimeout
+          totalTimeout:(NSTimeInterval)totalTimeout
+            autoExpire:(BOOL)autoExpire;
+
+/**
+ * Cap what the decoder accepts from untrusted frames; 0 disables a limit.
+ * New decoders allow 4096 chunks, 16 MiB of payload and 4296-character
+ * frames, with no session limit.
+ * Frames over a limit fail with AIRGAP_ERR_FRAME_TOO_LONG,
+ * AIRGAP_ERR_CHUNK_LIMIT, AIRGAP_ERR_PAYLOAD_TOO_LARGE or AIRGAP_ERR_SESSION_LIMIT
+ *
+ * @param maxTotalChunks Largest chunk count a frame may announce
+ * @param maxPayloadBytes Most bytes buffered for one session, including a decompressed BBQr payload
+ * @param maxSessions Sessions the decoder will start until the next reset, including ones after expiry
+ * @param maxFrameLength Longest QR string accepted, checked before any decoding
+ */
+- (void)setMaxTotalChunks:(NSUInteger)maxTotalChunks
+          maxPayloadBytes:(NSUInteger)maxPayloadBytes
+              maxSessions:(NSUInteger)maxSessions
+           maxFrameLength:(NSUInteger)maxFrameLength;
+
+/**
+ * Reset the session if it is stalled or expired
+ *
+ * @return YES if the session was reset
+ */
+- (BOOL)expireStale;
+
 /**
  * Process a QR code string
  *
@@ -16,8 +16,12 @@ static NSString *const AGDecoderErrorDomain = @"app.rkz.airgap.decoder";
 }
 
 - (instancetype)init {
+    return [self initWithFormat:AGFrameFormatAirgap];
+}
+
+- (nullable instancetype)initWithFormat:(AGFrameFormat)format {
     if (self = [super init]) {
-        _decoder = airgap_decoder_new();
+        _decoder = airgap_decoder_new_with_format((int)format);
         if (!_decoder) {
             return nil;
         }
@@ -49,6 +53,36 @@ static NSString *const AGDecoderErrorDomain = @"app.rkz.airgap.decoder";
     return airgap_decoder_get_session_id(_decoder);
 }
 
+- (AGDecoderStatus)status {
+    if (!_decoder) return AGDecoderStatusIdle;
+    return (AGDecoderStatus)airgap_decoder_get_status(_decoder);
+}
+
+- (void)setIdleTimeout:(NSTimeInterval)idleTimeout
+          totalTimeout:(NSTimeInterval)totalTimeout
+            autoExpire:(BOOL)autoExpire {
+    if (_decoder) {
+        airgap_decoder_set_timeouts(_decoder,
+                                    (uint64_t)MAX(idleTimeout * 1000.0, 0.0),
+                                    (uint64_t)MAX(totalTimeout * 1000.0, 0.0),
+                                    autoExpire);
+    }
+}
+
+- (void)setMaxTotalChunks:(NSUInteger)maxTotalChunks
+          maxPayloadBytes:(NSUInteger)maxPayloadBytes
+              maxSessions:(NSUInteger)maxSessions
+           maxFrameLength:(NSUInteger)maxFrameLength {
+    if (_decoder) {
+        airgap_decoder_set_limits(_decoder, maxTotalChunks, maxPayloadBytes, maxSessions, maxFrameLength);
+    }
+}
+
+- (BOOL)expireStale {
+    if (!_decoder) return NO;
+    return airgap_decoder_expire_stale(_decoder);
+}
+
 - (void)reset {
     if (_decoder) {
         airgap_decoder_reset(_decoder);
@@ -84,7 +118,7 @@ static NSString *const AGDecoderErrorDomain = @"app.rkz.airgap.decoder";
                                         code:result.code
                                     userInfo:@{NSLocalizedDescriptionKey: message}];
         }
-        result_error_message_free(result);
+        airgap_result_free(result);
         return nil;
     }
 
@@ -93,10 +127,9 @@ static NSString *const AGDecoderErrorDomain = @"app.rkz.airgap.decoder";
     if (result.payload) {
         struct QRResult *qr = (struct QRResult *)result.payload;
         qrResult = [[AGQRResult alloc] initWithChunkNumber:qr->chunk_number totalChunks:qr->total_chunk_count];
-        free((void *)result.payload);
     }
 
-    result_error_message_free(result);
+    airgap_result_free(result);
     return qrResult;
 }
 
@@ -128,7 +161,7 @@ static NSString *const AGDecoderErrorDomain = @"app.rkz.airgap.decoder";
                                         code:result.code
                                     userInfo:@{NSLocalizedDescriptionKey: message}];
         }
-        result_error_message_free(result);
+        airgap_result_free(result);
         return nil;
     }
 
@@ -138,7 +171,7 @@ static NSString *const AGDecoderErrorDomain = @"app.rkz.airgap.decoder";
                                         code:-1
                                     userInfo:@{NSLocalizedDescriptionKey: @"Retrieved empty data"}];
         }
-        result_error_message_free(result);
+        airgap_result_free(result);
         return nil;
     }
 
@@ -147,8 +180,7 @@ static NSString *const AGDecoderErrorDomain = @"app.rkz.airgap.decoder";
     NSData *data = [NSData dataWithBytes:byteArray->data length:byteArray->len];
 
     // Free resources
-    airgap_byte_array_free(*byteArray);
-    result_error_message_free(result);
+    airgap_result_free(result);
 
     return data;
 }
@@ -0,0 +1,25 @@
+//
+//  AGDecoderStatus.h
+//  Airgap
+//
+//  Where a decoding session stands
+//
+
+#import <Foundation/Foundation.h>
+#import "airgap.h"
+
+/**
+ * Status of the decoder's current session
+ */
+typedef NS_ENUM(NSInteger, AGDecoderStatus) {
+    /** No frame accepted yet */
+    AGDecoderStatusIdle = AIRGAP_STATUS_IDLE,
+    /** A session is in progress */
+    AGDecoderStatusReceiving = AIRGAP_STATUS_RECEIVING,
+    /** No new chunk within the idle timeout; the session resumes if one arrives */
+    AGDecoderStatusStalled = AIRGAP_STATUS_STALLED,
+    /** All chunks received */
+    AGDecoderStatusComplete = AIRGAP_STATUS_COMPLETE,
+    /** The session outlived the total timeout and accepts no more frames */
+    AGDecoderStatusExpired = AIRGAP_STATUS_EXPIRED,
+};
@@ -6,9 +6,12 @@
 //
 
 #import <Foundation/Foundation.h>
+#import "AGFrameFormat.h"
 
 NS_ASSUME_NONNULL_BEGIN
 
+struct AirgapQrConfig;
+
 /**
  * AGEncoder encodes data into QR code chunks for air-gapped communication
  */
@@ -27,6 +30,56 @@ NS_ASSUME_NONNULL_BEGIN
                                 qrSize: (NSUInteger)qrSize
                                 error:(NSError **)error;
 
+/**
+ * Creates a new encoder emitting frames in the given wire format
+ *
+ * @param data The data to encode
+ * @param chunkSize Size of each chunk (for UR, the maximum fragment length)
+ * @param qrSize Output PNG pixel dimensions
+ * @param format Wire format of the generated frames
+ * @param error Error pointer for initialization failures
+ * @return A new encoder instance, or nil if initialization fails
+ */
+- (nullable instancetype)initWithData:(NSData *)data
+                            chunkSize:(NSUInteger)chunkSize
+                               qrSize:(NSUInteger)qrSize
+                               format:(AGFrameFormat)format
+                                error:(NSError **)error;
+
+/**
+ * Creates a native-format encoder with a fixed session ID instead of a random
+ * one, so the frames are reproducible (e.g. for the golden test vectors)
+ *
+ * @param data The data to encode
+ * @param chunkSize Size of each chunk
+ * @param qrSize Output PNG pixel dimensions
+ * @param sessionId Session ID carried by every chunk
+ * @param error Error pointer for initialization failures
+ * @return A new encoder instance, or nil if initialization fails
+ */
+- (nullable instancetype)initWithData:(NSData *)data
+                            chunkSize:(NSUInteger)chunkSize
+                               qrSize:(NSUInteger)qrSize
+                            sessionId:(uint32_t)sessionId
+                                error:(NSError **)error;
+
+/**
+ * Creates a new encoder with full control over rendering: error correction
+ * level, colors, quiet zone, logo and frame indicators
+ *
+ * @param data The data to encode
+ * @param chunkSize Size of each chunk (for UR, the maximum fragment length)
+ * @param format Wire format of the generated frames
+ * @param config Rendering settings, starting from airgap_qr_config_default(); copied
+ * @param error Error pointer for initialization failures
+ * @return A new encoder instance, or nil if initialization fails
+ */
+- (nullable instancetype)initWithData:(NSData *)data
+                            chunkSize:(NSUInteger)chunkSize
+                               format:(AGFrameFormat)format
+                               config:(const struct AirgapQrConfig *)config
+                                error:(NSError **)error;
+
 /**
  * The total number of chunks this encoder will generate
  */
@@ -18,10 +18,39 @@ static NSString *const AGEncoderErrorDomain = @"app.rkz.airgap.encoder";
                             chunkSize:(NSUInteger)chunkSize
  								qrSize: (NSUInteger)qrSize
                                 error:(NSError **)error {
-    if (self = [super init]) {
-        // Call Rust FFI - all validation is done on Rust side
-        struct CResult result = airgap_encoder_new(data.bytes, data.length, chunkSize, qrSize);
+    return [self initWithData:data chunkSize:chunkSize qrSize:qrSize format:AGFrameFormatAirgap error:error];
+}
+
+- (nullable instancetype)initWithData:(NSData *)data
+                            chunkSize:(NSUInteger)chunkSize
+                               qrSize:(NSUInteger)qrSize
+                               format:(AGFrameFormat)format
+                                error:(NSError **)error {
+    // Call Rust FFI - all validation is done on Rust side
+    struct CResult result = airgap_encoder_new_with_format(data.bytes, data.length, chunkSize, qrSize, (int)format);
+    return [self initWithResult:result error:error];
+}
+
+- (nullable instancetype)initWithData:(NSData *)data
+                            chunkSize:(NSUInteger)chunkSize
+                               qrSize:(NSUInteger)qrSize
+                            sessionId:(uint32_t)sessionId
+                                error:(NSError **)error {
+    struct CResult result = airgap_encoder_new_with_session_id(data.bytes, data.length, chunkSize, qrSize, sessionId);
+    return [self initWithResult:result error:error];
+}
 
+- (nullable instancetype)initWithData:(NSData *)data
+                            chunkSize:(NSUInteger)chunkSize
+                               format:(AGFrameFormat)format
+                               config:(const struct AirgapQrConfig *)config
+                                error:(NSError **)error {
+    struct CResult result = airgap_encoder_new_with_config(data.bytes, data.length, chunkSize, (int)format, config);
+    return [self initWithResult:result error:error];
+}
+
+- (nullable instancetype)initWithResult:(struct CResult)result error:(NSError **)error {
+    if (self = [super init]) {
         if (result.code != AIRGAP_OK) {
             if (error) {
                 NSString *message =  [NSString stringWithUTF8String:result.error_message];
@@ -29,12 +58,14 @@ static NSString *const AGEncoderErrorDomain = @"app.rkz.airgap.encoder";
                                             code:result.code
                                         userInfo:@{NSLocalizedDescriptionKey: message}];
             }
-            result_error_message_free(result);
+            airgap_result_free(result);
             return nil;
         }
 
+        // Keep the encoder handle; it is freed in dealloc
         _encoder = (struct AirgapEncoder *)result.payload;
-        result_error_message_free(result);
+        result.payload = NULL;
+        airgap_result_free(result);
     }
     return self;
 }
@@ -75,7 +106,7 @@ static NSString *const AGEncoderErrorDomain = @"app.rkz.airgap.encoder";
                                         code:result.code
                                     userInfo:@{NSLocalizedDescriptionKey: message}];
         }
-        result_error_message_free(result);
+        airgap_result_free(result);
         return nil;
     }
 
@@ -85,7 +116,7 @@ static NSString *const AGEncoderErrorDomain = @"app.rkz.airgap.encoder";
                                         code:-1
                                     userInfo:@{NSLocalizedDescriptionKey: @"Generated empty QR string"}];
         }
-        result_error_message_free(result);
+        airgap_result_free(result);
         return nil;
     }
 
@@ -94,9 +125,7 @@ static NSString *const AGEncoderErrorDomain = @"app.rkz.airgap.encoder";
     NSString *qrString = [NSString stringWithUTF8String:(const char *)byteArray->data];
 
     // Free resources
-    airgap_byte_array_free(*byteArray);
-    free((void *)result.payload);
-    result_error_message_free(result);
+    airgap_result_free(result);
 
     return qrString;
 }
@@ -120,7 +149,7 @@ static NSString *const AGEncoderErrorDomain = @"app.rkz.airgap.encoder";
                                         code:result.code
                                     userInfo:@{NSLocalizedDescriptionKey: message}];
         }
-        result_error_message_free(result);
+        airgap_result_free(result);
         return nil;
     }
 
@@ -130,7 +159,7 @@ static NSString *const AGEncoderErrorDomain = @"app.rkz.airgap.encoder";
                                         code:-1
                                     userInfo:@{NSLocalizedDescriptionKey: @"Generated empty PNG data"}];
         }
-        result_error_message_free(result);
+        airgap_result_free(result);
         return nil;
     }
 
@@ -139,8 +168,7 @@ static NSString *const AGEncoderErrorDomain = @"app.rkz.airgap.encoder";
     NSData *pngData = [NSData dataWithBytes:byteArray->data length:byteArray->len];
 
     // Free resources
-    airgap_byte_array_free(*byteArray);
-    result_error_message_free(result);
+    airgap_result_free(result);
 
     return pngData;
 }
@@ -0,0 +1,35 @@
+//
+//  AGFrameFormat.h
+//  Airgap
+//
+//  Wire formats understood by the encoder and decoder
+//
+
+#import <Foundation/Foundation.h>
+#import "airgap.h"
+
+/**
+ * Wire format of the QR frames
+ */
+typedef NS_ENUM(NSInteger, AGFrameFormat) {
+    /** Native airgap chunks, Base45 encoded */
+    AGFrameFormatAirgap = AIRGAP_FORMAT_AIRGAP,
+    /** Blockchain Commons ur:bytes (fountain-coded when multipart) */
+    AGFrameFormatURBytes = AIRGAP_FORMAT_UR_BYTES,
+    /** Blockchain Commons ur:crypto-psbt (fountain-coded when multipart) */
+    AGFrameFormatURCryptoPSBT = AIRGAP_FORMAT_UR_CRYPTO_PSBT,
+    /** BBQr PSBT, zlib-compressed when that is smaller */
+    AGFrameFormatBBQrPSBT = AIRGAP_FORMAT_BBQR_PSBT,
+    /** BBQr signed transaction */
+    AGFrameFormatBBQrTransaction = AIRGAP_FORMAT_BBQR_TRANSACTION,
+    /** BBQr JSON */
+    AGFrameFormatBBQrJSON = AIRGAP_FORMAT_BBQR_JSON,
+    /** BBQr CBOR */
+    AGFrameFormatBBQrCBOR = AIRGAP_FORMAT_BBQR_CBOR,
+    /** BBQr Unicode text */
+    AGFrameFormatBBQrUnicodeText = AIRGAP_FORMAT_BBQR_UNICODE_TEXT,
+    /** BBQr binary */
+    AGFrameFormatBBQrBinary = AIRGAP_FORMAT_BBQR_BINARY,
+    /** BBQr executable */
+    AGFrameFormatBBQrExecutable = AIRGAP_FORMAT_BBQR_EXECUTABLE,
+};
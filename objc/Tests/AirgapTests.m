@@ -8,6 +8,7 @@
 #import <Foundation/Foundation.h>
 #import "AGEncoder.h"
 #import "AGDecoder.h"
+#import "airgap.h"
 
 #define TEST_ASSERT(condition, message) \
     if (!(condition)) { \
@@ -200,6 +201,118 @@ BOOL testDecoderReset(void) {
     return YES;
 }
 
+BOOL testSessionTimeouts(void) {
+    NSLog(@"\n=== Testing Session Timeouts ===");
+
+    NSMutableData *originalData = [NSMutableData dataWithLength:300];
+    NSError *error = nil;
+    AGEncoder *encoder = [[AGEncoder alloc] initWithData:originalData chunkSize:100 error:&error];
+    AGDecoder *decoder = [[AGDecoder alloc] init];
+    [decoder setIdleTimeout:0.05 totalTimeout:0.15 autoExpire:NO];
+
+    TEST_ASSERT(decoder.status == AGDecoderStatusIdle, @"New decoder should be idle");
+
+    [decoder processQRString:[encoder getQRStringAtIndex:0 error:&error] error:&error];
+    TEST_ASSERT(decoder.status == AGDecoderStatusReceiving, @"Decoder should be receiving");
+
+    [NSThread sleepForTimeInterval:0.08];
+    TEST_ASSERT(decoder.status == AGDecoderStatusStalled, @"Decoder should stall without new chunks");
+
+    [NSThread sleepForTimeInterval:0.1];
+    TEST_ASSERT(decoder.status == AGDecoderStatusExpired, @"Decoder should expire");
+
+    error = nil;
+    AGQRResult *result = [decoder processQRString:[encoder getQRStringAtIndex:1 error:nil] error:&error];
+    TEST_ASSERT(result == nil, @"Expired session should reject frames");
+    TEST_ASSERT_EQUAL(error.code, AIRGAP_ERR_SESSION_EXPIRED, @"Error should be session expired");
+
+    TEST_ASSERT([decoder expireStale], @"Expired session should be reset");
+    TEST_ASSERT(decoder.status == AGDecoderStatusIdle, @"Decoder should be idle after expiry");
+
+    return YES;
+}
+
+static NSData *dataFromHex(NSString *hex) {
+    NSMutableData *data = [NSMutableData dataWithCapacity:hex.length / 2];
+    for (NSUInteger i = 0; i + 1 < hex.length; i += 2) {
+        unsigned int byte = 0;
+        [[NSScanner scannerWithString:[hex substringWithRange:NSMakeRange(i, 2)]] scanHexInt:&byte];
+        uint8_t value = (uint8_t)byte;
+        [data appendBytes:&value length:1];
+    }
+    return data;
+}
+
+BOOL testGoldenVectors(void) {
+    NSLog(@"\n=== Testing Golden Vectors ===");
+
+    // Run from the repository root, as test-objc.sh does
+    NSError *error = nil;
+    NSString *vectors = [NSString stringWithContentsOfFile:@"vectors/chunk_format.tsv"
+                                                  encoding:NSUTF8StringEncoding
+                                                     error:&error];
+    TEST_ASSERT(vectors != nil, ([NSString stringWithFormat:@"Failed to read vectors: %@", error]));
+
+    AGEncoder *encoder = nil;
+    NSUInteger errorRows = 0;
+    for (NSString *line in [vectors componentsSeparatedByString:@"\n"]) {
+        if (line.length == 0 || [line hasPrefix:@"#"]) continue;
+        NSArray<NSString *> *fields = [line componentsSeparatedByString:@"\t"];
+
+        if ([fields[0] isEqualToString:@"encode"]) {
+            uint32_t sessionId = (uint32_t)[fields[2] longLongValue];
+            encoder = [[AGEncoder alloc] initWithData:dataFromHex(fields[4])
+                                            chunkSize:(NSUInteger)[fields[3] integerValue]
+                                               qrSize:400
+                                            sessionId:sessionId
+                                                error:&error];
+            TEST_ASSERT(encoder != nil, ([NSString stringWithFormat:@"Failed to create encoder for %@: %@", fields[1], error]));
+            TEST_ASSERT_EQUAL(encoder.sessionId, sessionId, @"Session ID mismatch");
+        } else if ([fields[0] isEqualToString:@"frame"]) {
+            NSString *frame = [encoder getQRStringAtIndex:(NSUInteger)[fields[1] integerValue] error:&error];
+            TEST_ASSERT([frame isEqualToString:fields[3]], ([NSString stringWithFormat:@"Frame %@ differs from vector", fields[1]]));
+        } else if ([fields[0] isEqualToString:@"error"]) {
+            AGDecoder *decoder = [[AGDecoder alloc] init];
+            error = nil;
+            AGQRResult *result = [decoder processQRString:fields[4] error:&error];
+            TEST_ASSERT(result == nil, ([NSString stringWithFormat:@"Vector %@ should be rejected", fields[1]]));
+            TEST_ASSERT_EQUAL(error.code, [fields[2] integerValue], fields[1]);
+            errorRows++;
+        }
+    }
+    TEST_ASSERT(errorRows > 0, @"Vectors should include malformed frames");
+
+    return YES;
+}
+
+BOOL testQrConfig(void) {
+    NSLog(@"\n=== Testing QR Config ===");
+
+    NSMutableData *originalData = [NSMutableData dataWithLength:2000];
+    NSError *error = nil;
+
+    struct AirgapQrConfig config = airgap_qr_config_default();
+    config.ec_level = AIRGAP_EC_LEVEL_H;
+    config.invert = true;
+    config.caption = true;
+    AGEncoder *encoder = [[AGEncoder alloc] initWithData:originalData
+                                               chunkSize:300
+                                                  format:AGFrameFormatAirgap
+                                                  config:&config
+                                                   error:&error];
+    TEST_ASSERT(encoder != nil, ([NSString stringWithFormat:@"Failed to create encoder: %@", error]));
+    NSData *png = [encoder generatePNGAtIndex:0 error:&error];
+    TEST_ASSERT(png.length > 0, @"PNG should not be empty");
+
+    config.ec_level = 7;
+    error = nil;
+    encoder = [[AGEncoder alloc] initWithData:originalData chunkSize:300 format:AGFrameFormatAirgap config:&config error:&error];
+    TEST_ASSERT(encoder == nil, @"Unknown EC level should be rejected");
+    TEST_ASSERT(error != nil, @"Error should be set");
+
+    return YES;
+}
+
 int main(int argc, const char * argv[]) {
     @autoreleasepool {
         NSLog(@"\n🧪 Running Airgap ObjC Tests\n");
@@ -210,6 +323,9 @@ int main(int argc, const char * argv[]) {
         allPassed &= testLargeDataRoundtrip();
         allPassed &= testOutOfOrderChunks();
         allPassed &= testDecoderReset();
+        allPassed &= testSessionTimeouts();
+        allPassed &= testGoldenVectors();
+        allPassed &= testQrConfig();
 
         if (allPassed) {
             NSLog(@"\n✅ All tests passed!");
@@ -0,0 +1,66 @@
+#!/bin/bash
+# Runs crates/airgap-ffi/tests/harness.c against the release staticlib under
+# leak checkers. The harness is not part of `cargo test`; by hand, from the
+# workspace root after `cargo build --release -p airgap-ffi`:
+#
+#   cc -g -fsanitize=address -fno-omit-frame-pointer -I./include \
+#       crates/airgap-ffi/tests/harness.c ./target/release/libairgap.a \
+#       -lpthread -ldl -lm -o harness-asan
+#   ASAN_OPTIONS=detect_leaks=1 ./harness-asan
+#
+#   cc -g -I./include crates/airgap-ffi/tests/harness.c \
+#       ./target/release/libairgap.a -lpthread -ldl -lm -o harness
+#   valgrind --leak-check=full --errors-for-leak-kinds=definite,indirect \
+#       --error-exitcode=1 ./harness
+#
+# On macOS link with `-framework Security -framework CoreFoundation` instead
+# of `-lpthread -ldl -lm`.
+set -e
+
+echo "🧪 Building and running the C harness under leak checkers..."
+
+# Build Rust library first
+echo "Building Rust library..."
+cargo build --release -p airgap-ffi
+
+TEMP_DIR="target/c-test-temp"
+mkdir -p "$TEMP_DIR"
+
+# Native libraries the Rust staticlib depends on
+case "$(uname)" in
+    Darwin) SYS_LIBS="-framework Security -framework CoreFoundation" ;;
+    *) SYS_LIBS="-lpthread -ldl -lm" ;;
+esac
+
+# AddressSanitizer, with LeakSanitizer reporting anything left allocated at exit
+echo ""
+echo "Running under AddressSanitizer..."
+cc -g -fsanitize=address -fno-omit-frame-pointer \
+    -I./include \
+    crates/airgap-ffi/tests/harness.c \
+    ./target/release/libairgap.a \
+    $SYS_LIBS \
+    -o "$TEMP_DIR/harness-asan"
+ASAN_OPTIONS=detect_leaks=1 "$TEMP_DIR/harness-asan"
+
+# Valgrind, where it is installed
+echo ""
+if command -v valgrind > /dev/null; then
+    echo "Running under Valgrind..."
+    cc -g \
+            -I./include \
+        crates/airgap-ffi/tests/harness.c \
+        ./target/release/libairgap.a \
+        $SYS_LIBS \
+        -o "$TEMP_DIR/harness"
+    valgrind --leak-check=full --errors-for-leak-kinds=definite,indirect --error-exitcode=1 "$TEMP_DIR/harness"
+else
+    echo "⚠️  valgrind not found, skipping"
+fi
+
+# Cleanup
+echo ""
+echo "Cleaning up..."
+rm -rf "$TEMP_DIR"
+
+echo "✅ Done!"
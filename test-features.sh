@@ -0,0 +1,37 @@
+#!/bin/bash
+set -e
+
+echo "🧪 Checking every feature combination..."
+
+# Lint and test one crate with one feature set
+check() {
+    echo ""
+    echo "== $*"
+    cargo clippy --all-targets "$@" -- -D warnings
+    cargo test "$@"
+}
+
+# Protocol core, as used by server-side decoders
+check -p airgap-core
+check -p airgap-core --features async
+check -p airgap-core --features parallel
+check -p airgap-core --features serde
+
+# Renderers without any bindings
+check -p airgap-render --no-default-features
+check -p airgap-render --no-default-features --features svg
+check -p airgap-render
+check -p airgap-render --all-features
+
+# Bindings and everything together
+check --workspace
+check --workspace --all-features
+
+# Embedded builds: the core without std
+echo ""
+echo "== no_std"
+cargo clippy -p airgap-core --no-default-features -- -D warnings
+cargo clippy -p airgap-core --no-default-features --features serde -- -D warnings
+
+echo ""
+echo "✅ All feature combinations passed"
@@ -5,7 +5,7 @@ echo "🧪 Building and running ObjC tests..."
 
 # Build Rust library first
 echo "Building Rust library..."
-cargo build --release
+cargo build --release -p airgap-ffi
 
 # Create temp directory for object files
 TEMP_DIR="target/objc-test-temp"